@@ -0,0 +1,166 @@
+//! Latencia bajo carga concurrente de logins.
+//!
+//! Levanta el router real contra `DATABASE_URL_TEST` y lanza `BENCH_LOGINS`
+//! logins (por defecto 200) con `BENCH_CONCURRENCY` clientes (por defecto 32),
+//! mientras otro cliente mide `GET /` para comprobar que el hashing no bloquea
+//! al resto de las peticiones.
+//!
+//!     cargo bench --bench login_load
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use r_auth_api::{
+    AppState,
+    auth::HASHING_POOL,
+    build_router,
+    database::{
+        connection::{GLOBAL_DB_POOL, initialize_global_db_pool},
+        models::dto::CreateUserDto,
+    },
+    services::UsersService,
+};
+use rand::{Rng, distr::Alphanumeric};
+use reqwest::StatusCode;
+
+const PASSWORD: &str = "Secret@123";
+
+fn env_number(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let logins = env_number("BENCH_LOGINS", 200);
+    let concurrency = env_number("BENCH_CONCURRENCY", 32);
+
+    let database_url =
+        std::env::var("DATABASE_URL_TEST").expect("DATABASE_URL_TEST no está configurada");
+    initialize_global_db_pool(&database_url).await.unwrap();
+    let pool = GLOBAL_DB_POOL.get().unwrap();
+
+    let suffix: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(10)
+        .map(char::from)
+        .collect::<String>()
+        .to_lowercase();
+    let email = format!("bench_{}@example.com", suffix);
+    UsersService::new(pool)
+        .create(CreateUserDto {
+            username: format!("bench_{}", suffix),
+            email: email.clone(),
+            password: PASSWORD.to_string(),
+            attributes: None,
+        })
+        .await
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let router = build_router(AppState::new(pool));
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    let body =
+        serde_json::to_vec(&serde_json::json!({ "email": email, "password": PASSWORD })).unwrap();
+
+    let running = Arc::new(AtomicBool::new(true));
+    let probe = {
+        let running = running.clone();
+        let url = format!("{}/", base_url);
+        let http = reqwest::Client::new();
+        tokio::spawn(async move {
+            let mut latencies = vec![];
+            while running.load(Ordering::Relaxed) {
+                let started = Instant::now();
+                http.get(&url).send().await.unwrap();
+                latencies.push(started.elapsed());
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            latencies
+        })
+    };
+
+    let started = Instant::now();
+    let per_client = logins.div_ceil(concurrency);
+    let workers: Vec<_> = (0..concurrency)
+        .map(|_| {
+            let url = format!("{}/api/users/login", base_url);
+            let body = body.clone();
+            let http = reqwest::Client::new();
+            tokio::spawn(async move {
+                let (mut latencies, mut rejected) = (vec![], 0);
+                for _ in 0..per_client {
+                    let t = Instant::now();
+                    let response = http
+                        .post(&url)
+                        .header("Content-Type", "application/json")
+                        .body(body.clone())
+                        .send()
+                        .await
+                        .unwrap();
+                    match response.status() {
+                        StatusCode::OK => latencies.push(t.elapsed()),
+                        StatusCode::SERVICE_UNAVAILABLE => rejected += 1,
+                        status => panic!("Login inesperado: {}", status),
+                    }
+                }
+                (latencies, rejected)
+            })
+        })
+        .collect();
+
+    let (mut login_latencies, mut rejected) = (vec![], 0);
+    for worker in workers {
+        let (latencies, r) = worker.await.unwrap();
+        login_latencies.extend(latencies);
+        rejected += r;
+    }
+    let elapsed = started.elapsed();
+    running.store(false, Ordering::Relaxed);
+    let mut probe_latencies = probe.await.unwrap();
+
+    let metrics = HASHING_POOL.metrics();
+    println!(
+        "{} logins, {} clientes, pool de {} hilos: {:.1} logins/s, {} rechazados (503)",
+        login_latencies.len(),
+        concurrency,
+        metrics.concurrency,
+        login_latencies.len() as f64 / elapsed.as_secs_f64(),
+        rejected
+    );
+    report("POST /api/users/login", &mut login_latencies);
+    report("GET / (durante la carga)", &mut probe_latencies);
+    println!(
+        "espera media en cola: {:.1} ms",
+        metrics.wait_seconds * 1000.0 / metrics.completed.max(1) as f64
+    );
+}
+
+fn report(name: &str, latencies: &mut [Duration]) {
+    if latencies.is_empty() {
+        println!("{}: sin muestras", name);
+        return;
+    }
+    latencies.sort();
+    let percentile = |p: f64| {
+        let index = ((latencies.len() as f64 * p).ceil() as usize).saturating_sub(1);
+        latencies[index].as_secs_f64() * 1000.0
+    };
+    println!(
+        "{}: p50 {:.1} ms, p95 {:.1} ms, p99 {:.1} ms",
+        name,
+        percentile(0.50),
+        percentile(0.95),
+        percentile(0.99)
+    );
+}
@@ -0,0 +1,156 @@
+//! Latencia de la búsqueda de usuarios sobre una tabla grande.
+//!
+//! Inserta `BENCH_USERS` usuarios (por defecto 1.000.000) en
+//! `DATABASE_URL_TEST`, compara la búsqueda libre `q` (trigramas) con el
+//! `ILIKE` sobre una columna y la paginación por página contra la de cursor,
+//! y al final borra los usuarios sembrados.
+//!
+//!     cargo bench --bench user_search
+
+use std::time::{Duration, Instant};
+
+use r_auth_api::{
+    database::{
+        connection::{GLOBAL_DB_POOL, initialize_global_db_pool},
+        models::FindQuery,
+    },
+    services::UsersService,
+};
+
+const SEED_PREFIX: &str = "seed_";
+const NAMES: [&str; 8] = [
+    "ana", "bruno", "carla", "diego", "elena", "fede", "gabriela", "hugo",
+];
+
+fn env_number(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let users = env_number("BENCH_USERS", 1_000_000) as i64;
+    let iterations = env_number("BENCH_ITERATIONS", 50);
+
+    let database_url =
+        std::env::var("DATABASE_URL_TEST").expect("DATABASE_URL_TEST no está configurada");
+    initialize_global_db_pool(&database_url).await.unwrap();
+    let pool = GLOBAL_DB_POOL.get().unwrap();
+    let client = pool.get().await.unwrap();
+
+    let started = Instant::now();
+    client
+        .execute(
+            r#"
+                INSERT INTO users (username, email, status, permissions, created_at)
+                SELECT
+                    $1 || ($2::text[])[1 + i % 8] || '_' || i,
+                    ($2::text[])[1 + i % 8] || '.' || i || '@' ||
+                        (ARRAY['example.com', 'corp.io', 'mail.net'])[1 + i % 3],
+                    1, 0, now() - make_interval(secs => i)
+                FROM generate_series(1, $3::bigint) AS i
+            "#,
+            &[&SEED_PREFIX, &NAMES.to_vec(), &users],
+        )
+        .await
+        .unwrap();
+    client.execute("ANALYZE users", &[]).await.unwrap();
+    println!(
+        "{} usuarios sembrados en {:.1} s",
+        users,
+        started.elapsed().as_secs_f64()
+    );
+
+    let service = UsersService::new(pool);
+    let target = users / 2;
+    let fragment = format!("rla_{}", target / 10);
+
+    let scenarios: Vec<(String, FindQuery)> = vec![
+        (
+            format!("q={} (trigramas, relevancia)", fragment),
+            FindQuery {
+                q: Some(fragment.clone()),
+                limit: Some(20),
+                include_total: Some(false),
+                ..Default::default()
+            },
+        ),
+        (
+            "q=gabreila (con error de tipeo)".to_string(),
+            FindQuery {
+                q: Some("gabreila".to_string()),
+                limit: Some(20),
+                include_total: Some(false),
+                ..Default::default()
+            },
+        ),
+        (
+            format!("queryKey=username&queryValue={} (ILIKE)", fragment),
+            FindQuery {
+                query_key: Some("username".to_string()),
+                query_value: Some(fragment.clone()),
+                limit: Some(20),
+                include_total: Some(false),
+                ..Default::default()
+            },
+        ),
+        (
+            "page=5000 (OFFSET, con total)".to_string(),
+            FindQuery {
+                sort: Some("created_at".to_string()),
+                page: Some(5000),
+                limit: Some(20),
+                ..Default::default()
+            },
+        ),
+        (
+            "cursor (primera página, sin total)".to_string(),
+            FindQuery {
+                cursor: Some(String::new()),
+                limit: Some(20),
+                ..Default::default()
+            },
+        ),
+    ];
+
+    for (name, query) in scenarios {
+        let mut latencies = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let t = Instant::now();
+            service.find(query.clone()).await.unwrap();
+            latencies.push(t.elapsed());
+        }
+        report(&name, &mut latencies);
+    }
+
+    let started = Instant::now();
+    client
+        .execute(
+            "DELETE FROM users WHERE username LIKE $1",
+            &[&format!("{}%", SEED_PREFIX)],
+        )
+        .await
+        .unwrap();
+    println!(
+        "usuarios sembrados borrados en {:.1} s",
+        started.elapsed().as_secs_f64()
+    );
+}
+
+fn report(name: &str, latencies: &mut [Duration]) {
+    latencies.sort();
+    let percentile = |p: f64| {
+        let index = ((latencies.len() as f64 * p).ceil() as usize).saturating_sub(1);
+        latencies[index].as_secs_f64() * 1000.0
+    };
+    println!(
+        "{}: p50 {:.1} ms, p95 {:.1} ms, p99 {:.1} ms",
+        name,
+        percentile(0.50),
+        percentile(0.95),
+        percentile(0.99)
+    );
+}
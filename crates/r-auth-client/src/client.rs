@@ -0,0 +1,366 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use r_auth_types::{
+    ChangePasswordDto, CreateUserDto, FindQuery, FindResult, LoginRequest, LoginResponse,
+    MessageResponse, OneResult, UpdateUserDto, User,
+};
+use reqwest::{Method, StatusCode, header};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::sync::Mutex;
+
+use crate::ClientError;
+
+/// El token se renueva si le quedan menos de estos segundos de vida.
+const REFRESH_MARGIN_SECONDS: u64 = 60;
+
+/// Cliente de la API de usuarios. Si se inició sesión con `login`, guarda las
+/// credenciales y vuelve a autenticarse cuando el token está por vencer o el
+/// servidor lo rechaza.
+pub struct RAuthClient {
+    base_url: String,
+    http: reqwest::Client,
+    session: Mutex<Session>,
+}
+
+struct HttpResponse {
+    status: StatusCode,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+#[derive(Default)]
+struct Session {
+    token: Option<String>,
+    expires_at: Option<u64>,
+    credentials: Option<LoginRequest>,
+}
+
+impl RAuthClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        RAuthClient {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            session: Mutex::new(Session::default()),
+        }
+    }
+
+    /// Cliente con un token ya emitido; no podrá renovarlo al vencer.
+    pub fn with_token(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        let client = Self::new(base_url);
+        let token = token.into();
+        client.session.try_lock().unwrap().set_token(token);
+        client
+    }
+
+    pub async fn token(&self) -> Option<String> {
+        self.session.lock().await.token.clone()
+    }
+
+    pub async fn login(&self, email: &str, password: &str) -> Result<String, ClientError> {
+        let credentials = LoginRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+        };
+        let mut session = self.session.lock().await;
+        session.credentials = Some(credentials);
+        let result = self.refresh(&mut session).await;
+        if result.is_err() {
+            session.credentials = None;
+        }
+        result
+    }
+
+    /// Guarda las credenciales sin iniciar sesión; el login ocurre en la
+    /// primera petición que lo necesite.
+    pub async fn set_credentials(&self, email: &str, password: &str) {
+        self.session.lock().await.credentials = Some(LoginRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+        });
+    }
+
+    pub async fn logout(&self) {
+        *self.session.lock().await = Session::default();
+    }
+
+    pub async fn create_user(&self, dto: &CreateUserDto) -> Result<User, ClientError> {
+        let result: OneResult<User> = self.call(Method::POST, "/api/users", Some(dto)).await?;
+        Ok(result.result)
+    }
+
+    pub async fn find_users(&self, query: &FindQuery) -> Result<FindResult<User>, ClientError> {
+        let mut params = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(key) = &query.query_key {
+            params.append_pair("queryKey", key);
+        }
+        if let Some(value) = &query.query_value {
+            params.append_pair("queryValue", value);
+        }
+        if let Some(q) = &query.q {
+            params.append_pair("q", q);
+        }
+        if let Some(mode) = &query.match_mode {
+            params.append_pair("match", mode);
+        }
+        if let Some(status) = query.status {
+            params.append_pair("status", status.name());
+        }
+        if let Some(permissions) = query.permissions {
+            params.append_pair("permissions", &permissions.to_string());
+        }
+        if let Some(attributes) = &query.attributes {
+            params.append_pair("attributes", attributes);
+        }
+        for (name, value) in [
+            ("createdFrom", query.created_from),
+            ("createdTo", query.created_to),
+            ("updatedFrom", query.updated_from),
+            ("updatedTo", query.updated_to),
+        ] {
+            if let Some(value) = value {
+                params.append_pair(name, &value.to_rfc3339());
+            }
+        }
+        if let Some(sort) = &query.sort {
+            params.append_pair("sort", sort);
+        }
+        if let Some(order) = &query.order {
+            params.append_pair("order", order);
+        }
+        if let Some(cursor) = &query.cursor {
+            params.append_pair("cursor", cursor);
+        }
+        if let Some(include_total) = query.include_total {
+            params.append_pair("includeTotal", &include_total.to_string());
+        }
+        if let Some(page) = query.page {
+            params.append_pair("page", &page.to_string());
+        }
+        if let Some(limit) = query.limit {
+            params.append_pair("limit", &limit.to_string());
+        }
+        let path = format!("/api/users?{}", params.finish());
+        self.call(Method::GET, &path, None::<&()>).await
+    }
+
+    pub async fn get_user(&self, id: i64) -> Result<User, ClientError> {
+        let path = format!("/api/users/{}", id);
+        let result: OneResult<User> = self.call(Method::GET, &path, None::<&()>).await?;
+        Ok(result.result)
+    }
+
+    pub async fn me(&self) -> Result<User, ClientError> {
+        let result: OneResult<User> = self.call(Method::GET, "/api/users/me", None::<&()>).await?;
+        Ok(result.result)
+    }
+
+    pub async fn update_user(&self, id: i64, dto: &UpdateUserDto) -> Result<User, ClientError> {
+        let path = format!("/api/users/{}", id);
+        let result: OneResult<User> = self.call(Method::PATCH, &path, Some(dto)).await?;
+        Ok(result.result)
+    }
+
+    pub async fn update_me(&self, dto: &UpdateUserDto) -> Result<User, ClientError> {
+        let result: OneResult<User> = self.call(Method::PATCH, "/api/users/me", Some(dto)).await?;
+        Ok(result.result)
+    }
+
+    /// Si el cambio es exitoso, las credenciales guardadas pasan a usar la
+    /// nueva contraseña.
+    pub async fn change_password(
+        &self,
+        dto: &ChangePasswordDto,
+    ) -> Result<MessageResponse, ClientError> {
+        let response: MessageResponse = self
+            .call(Method::PUT, "/api/users/change-password", Some(dto))
+            .await?;
+        if let Some(credentials) = self.session.lock().await.credentials.as_mut() {
+            credentials.password = dto.new_password.clone();
+        }
+        Ok(response)
+    }
+
+    pub async fn inactivate_user(&self, id: i64) -> Result<MessageResponse, ClientError> {
+        let path = format!("/api/users/inactive/{}", id);
+        self.call(Method::PUT, &path, None::<&()>).await
+    }
+
+    pub async fn inactivate_me(&self) -> Result<MessageResponse, ClientError> {
+        self.call(Method::PUT, "/api/users/inactive/me", None::<&()>)
+            .await
+    }
+
+    /// Vuelve a activar un usuario inactivo; `Conflict` si no lo está.
+    pub async fn reactivate_user(&self, id: i64) -> Result<MessageResponse, ClientError> {
+        let path = format!("/api/users/reactivate/{}", id);
+        self.call(Method::PUT, &path, None::<&()>).await
+    }
+
+    /// Recupera un usuario eliminado que todavía no se purgó.
+    pub async fn restore_user(&self, id: i64) -> Result<MessageResponse, ClientError> {
+        let path = format!("/api/users/restore/{}", id);
+        self.call(Method::PUT, &path, None::<&()>).await
+    }
+
+    pub async fn delete_user(&self, id: i64) -> Result<(), ClientError> {
+        let path = format!("/api/users/{}", id);
+        self.call_raw(Method::DELETE, &path, None).await?;
+        Ok(())
+    }
+
+    pub async fn delete_me(&self) -> Result<(), ClientError> {
+        self.call_raw(Method::DELETE, "/api/users/me", None).await?;
+        Ok(())
+    }
+
+    async fn call<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let body = body
+            .map(serde_json::to_vec)
+            .transpose()
+            .map_err(|e| ClientError::Decode(e.to_string()))?;
+        let response = self.call_raw(method, path, body).await?;
+        response
+            .json()
+            .map_err(|e| ClientError::Decode(e.to_string()))
+    }
+
+    /// Envía una petición autenticada. Ante un 401 con credenciales guardadas,
+    /// inicia sesión de nuevo y reintenta una vez.
+    async fn call_raw(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse, ClientError> {
+        let (token, can_refresh) = {
+            let mut session = self.session.lock().await;
+            let token = match session.valid_token() {
+                Some(token) => token,
+                None if session.credentials.is_some() => self.refresh(&mut session).await?,
+                None => session.token.clone().ok_or(ClientError::NotAuthenticated)?,
+            };
+            (token, session.credentials.is_some())
+        };
+
+        let response = self
+            .send(method.clone(), path, Some(&token), body.clone())
+            .await?;
+        if response.status != StatusCode::UNAUTHORIZED || !can_refresh {
+            return check(response);
+        }
+
+        let token = {
+            let mut session = self.session.lock().await;
+            self.refresh(&mut session).await?
+        };
+        check(self.send(method, path, Some(&token), body).await?)
+    }
+
+    async fn refresh(&self, session: &mut Session) -> Result<String, ClientError> {
+        let credentials = session
+            .credentials
+            .as_ref()
+            .ok_or(ClientError::NotAuthenticated)?;
+        let body =
+            serde_json::to_vec(credentials).map_err(|e| ClientError::Decode(e.to_string()))?;
+
+        let response = check(
+            self.send(Method::POST, "/api/users/login", None, Some(body))
+                .await?,
+        )?;
+        let login: LoginResponse = response
+            .json()
+            .map_err(|e| ClientError::Decode(e.to_string()))?;
+
+        let token = login.token.ok_or_else(|| {
+            ClientError::Decode(
+                "El servidor usa sesiones por cookie (SESSION_MODE=cookie)".to_string(),
+            )
+        })?;
+        session.set_token(token.clone());
+        Ok(token)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse, ClientError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self
+            .http
+            .request(method, url)
+            .header(header::ACCEPT, "application/json");
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body);
+        }
+
+        let transport = |e: reqwest::Error| ClientError::Transport(e.to_string());
+        let response = request.send().await.map_err(transport)?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(transport)?.to_vec();
+        Ok(HttpResponse { status, body })
+    }
+}
+
+impl Session {
+    fn set_token(&mut self, token: String) {
+        self.expires_at = token_expiration(&token);
+        self.token = Some(token);
+    }
+
+    /// Token actual si no está por vencer.
+    fn valid_token(&self) -> Option<String> {
+        let token = self.token.as_ref()?;
+        match self.expires_at {
+            Some(exp) if now() + REFRESH_MARGIN_SECONDS >= exp => None,
+            _ => Some(token.clone()),
+        }
+    }
+}
+
+fn check(response: HttpResponse) -> Result<HttpResponse, ClientError> {
+    if response.status.is_success() {
+        Ok(response)
+    } else {
+        Err(ClientError::from_response(response.status, &response.body))
+    }
+}
+
+/// Lee `exp` del payload sin verificar la firma; solo se usa para decidir
+/// cuándo renovar, la validación la hace el servidor.
+fn token_expiration(token: &str) -> Option<u64> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims.get("exp")?.as_u64()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
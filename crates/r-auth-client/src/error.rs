@@ -0,0 +1,77 @@
+use r_auth_types::HttpError;
+use reqwest::StatusCode;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// 400: el servidor rechazó los datos enviados.
+    #[error("Datos inválidos: {0:?}")]
+    BadRequest(HttpError),
+
+    #[error("No autenticado: {0:?}")]
+    Unauthorized(HttpError),
+
+    /// 403: permisos insuficientes o usuario inactivo.
+    #[error("Acceso denegado: {0:?}")]
+    Forbidden(HttpError),
+
+    #[error("No encontrado: {0:?}")]
+    NotFound(HttpError),
+
+    #[error("Conflicto: {0:?}")]
+    Conflict(HttpError),
+
+    #[error("Error {status}: {error:?}")]
+    Api {
+        status: StatusCode,
+        error: HttpError,
+    },
+
+    /// Ruta autenticada sin token ni credenciales con las que obtenerlo.
+    #[error("El cliente no tiene sesión iniciada")]
+    NotAuthenticated,
+
+    #[error("Error de transporte: {0}")]
+    Transport(String),
+
+    #[error("Respuesta inválida: {0}")]
+    Decode(String),
+}
+
+impl ClientError {
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        let error: HttpError = match serde_json::from_slice(body) {
+            Ok(error) => error,
+            Err(_) => {
+                return ClientError::Decode(format!(
+                    "{}: {}",
+                    status,
+                    String::from_utf8_lossy(body)
+                ));
+            }
+        };
+
+        match status {
+            StatusCode::BAD_REQUEST => ClientError::BadRequest(error),
+            StatusCode::UNAUTHORIZED => ClientError::Unauthorized(error),
+            StatusCode::FORBIDDEN => ClientError::Forbidden(error),
+            StatusCode::NOT_FOUND => ClientError::NotFound(error),
+            StatusCode::CONFLICT => ClientError::Conflict(error),
+            status => ClientError::Api { status, error },
+        }
+    }
+
+    /// Primer mensaje de error devuelto por el servidor, si lo hay.
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            ClientError::BadRequest(e)
+            | ClientError::Unauthorized(e)
+            | ClientError::Forbidden(e)
+            | ClientError::NotFound(e)
+            | ClientError::Conflict(e)
+            | ClientError::Api { error: e, .. } => {
+                e.errors.values().flatten().next().map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Cliente tipado para `/api/users/*` de r-auth. Usa los mismos DTOs que el
+//! servidor (`r-auth-types`), así que un cambio de contrato rompe la
+//! compilación del cliente en lugar de fallar en tiempo de ejecución.
+//!
+//! ```ignore
+//! let client = RAuthClient::new("http://localhost:3032");
+//! client.login("admin@example.com", "Secret@123").await?;
+//! let users = client.find_users(&FindQuery::default()).await?;
+//! ```
+
+mod client;
+mod error;
+
+pub use client::RAuthClient;
+pub use error::ClientError;
+
+pub use r_auth_types::{
+    ChangePasswordDto, CreateUserDto, FindQuery, FindResult, HttpError, LoginRequest,
+    LoginResponse, MessageResponse, OneResult, UpdateUserDto, User, UserStatus,
+};
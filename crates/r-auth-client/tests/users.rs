@@ -0,0 +1,311 @@
+use std::{net::SocketAddr, sync::OnceLock, time::Duration};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use r_auth_api::{
+    AppState, build_router,
+    database::connection::{GLOBAL_DB_POOL, create_pool, initialize_global_db_pool},
+    services::UsersService,
+    utils::Permissions,
+};
+use r_auth_client::{
+    ChangePasswordDto, ClientError, CreateUserDto, FindQuery, RAuthClient, UpdateUserDto,
+    UserStatus,
+};
+use rand::{Rng, distr::Alphanumeric};
+
+const PASSWORD: &str = "Secret@123";
+
+static SERVER: OnceLock<SocketAddr> = OnceLock::new();
+
+/// Levanta el router real una sola vez por binario de pruebas, en un hilo con
+/// su propio runtime para que sobreviva al runtime de cada test.
+fn server_url() -> String {
+    let addr = SERVER.get_or_init(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async move {
+                let database_url = std::env::var("DATABASE_URL_TEST")
+                    .expect("TEST ERROR: DATABASE_URL_TEST no está configurada");
+                initialize_global_db_pool(&database_url).await.unwrap();
+                let state = AppState::new(GLOBAL_DB_POOL.get().unwrap());
+
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, build_router(state)).await.unwrap();
+            });
+        });
+        rx.recv_timeout(Duration::from_secs(10)).unwrap()
+    });
+    format!("http://{}", addr)
+}
+
+fn unique_user() -> CreateUserDto {
+    let suffix: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(10)
+        .map(char::from)
+        .collect::<String>()
+        .to_lowercase();
+    CreateUserDto {
+        username: format!("client_{}", suffix),
+        email: format!("client_{}@example.com", suffix),
+        password: PASSWORD.to_string(),
+        attributes: None,
+    }
+}
+
+/// Crea un usuario directamente en la base con los permisos indicados.
+async fn seed_user(permissions: Permissions) -> CreateUserDto {
+    let database_url = std::env::var("DATABASE_URL_TEST").unwrap();
+    let pool = create_pool(&database_url).await.unwrap();
+    let dto = unique_user();
+    let user = UsersService::new(&pool).create(dto.clone()).await.unwrap();
+
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE users SET permissions = $1 WHERE id = $2",
+            &[&permissions.bits(), &user.id],
+        )
+        .await
+        .unwrap();
+    dto
+}
+
+async fn admin_client() -> RAuthClient {
+    let admin = seed_user(Permissions::ADMIN).await;
+    let client = RAuthClient::new(server_url());
+    client.login(&admin.email, PASSWORD).await.unwrap();
+    client
+}
+
+/// ---
+///
+/// ## Test Case 1: login guarda el token y `me` devuelve al usuario
+///
+#[tokio::test]
+async fn test_login_and_me() {
+    let user = seed_user(Permissions::READ_MYSELF | Permissions::UPDATE_MYSELF).await;
+    let client = RAuthClient::new(server_url());
+
+    let token = client.login(&user.email, PASSWORD).await.unwrap();
+    assert_eq!(client.token().await.as_deref(), Some(token.as_str()));
+
+    let me = client.me().await.unwrap();
+    assert_eq!(me.username, user.username);
+    assert_eq!(me.email, user.email);
+}
+
+/// ---
+///
+/// ## Test Case 2: Un administrador crea, busca y obtiene usuarios
+///
+#[tokio::test]
+async fn test_admin_create_find_and_get() {
+    let client = admin_client().await;
+    let dto = unique_user();
+
+    let created = client.create_user(&dto).await.unwrap();
+    assert_eq!(created.username, dto.username);
+
+    let found = client
+        .find_users(&FindQuery {
+            query_key: Some("username".to_string()),
+            query_value: Some(dto.username.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(found.results.iter().any(|u| u.id == created.id));
+
+    let fetched = client.get_user(created.id).await.unwrap();
+    assert_eq!(fetched.email, dto.email);
+}
+
+/// ---
+///
+/// ## Test Case 3: Actualizar al usuario propio y a otro usuario
+///
+#[tokio::test]
+async fn test_update_me_and_user() {
+    let client = admin_client().await;
+    let other = client.create_user(&unique_user()).await.unwrap();
+
+    let renamed = format!("{}_x", other.username);
+    let updated = client
+        .update_user(
+            other.id,
+            &UpdateUserDto {
+                username: Some(renamed.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.username, renamed);
+
+    let me = client.me().await.unwrap();
+    let new_email = format!("new_{}", me.email);
+    let me = client
+        .update_me(&UpdateUserDto {
+            email: Some(new_email.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(me.email, new_email);
+}
+
+/// ---
+///
+/// ## Test Case 4: Tras cambiar la contraseña, la renovación usa la nueva
+///
+#[tokio::test]
+async fn test_change_password_updates_credentials() {
+    let user = seed_user(Permissions::READ_MYSELF | Permissions::UPDATE_MYSELF).await;
+    let client = RAuthClient::new(server_url());
+    client.login(&user.email, PASSWORD).await.unwrap();
+
+    client
+        .change_password(&ChangePasswordDto {
+            previous_password: PASSWORD.to_string(),
+            new_password: "Another@456".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let fresh = RAuthClient::new(server_url());
+    assert!(matches!(
+        fresh.login(&user.email, PASSWORD).await,
+        Err(ClientError::Unauthorized(_))
+    ));
+
+    // Un token inválido obliga a iniciar sesión otra vez con las credenciales guardadas.
+    let renewed = RAuthClient::with_token(server_url(), "invalid.token.value");
+    renewed.set_credentials(&user.email, "Another@456").await;
+    assert_eq!(renewed.me().await.unwrap().email, user.email);
+}
+
+/// ---
+///
+/// ## Test Case 5: Un token vencido se renueva antes de enviar la petición
+///
+#[tokio::test]
+async fn test_expired_token_is_renewed() {
+    let user = seed_user(Permissions::READ_MYSELF).await;
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(r#"{"user_id":"1","exp":1,"iat":0}"#);
+    let expired = format!("{}.{}.signature", header, payload);
+
+    let client = RAuthClient::with_token(server_url(), expired.clone());
+    client.set_credentials(&user.email, PASSWORD).await;
+
+    let me = client.me().await.unwrap();
+    assert_eq!(me.username, user.username);
+    assert_ne!(client.token().await, Some(expired));
+}
+
+/// ---
+///
+/// ## Test Case 6: Los errores HTTP se traducen a variantes tipadas
+///
+#[tokio::test]
+async fn test_errors_are_typed() {
+    let anonymous = RAuthClient::new(server_url());
+    assert!(matches!(
+        anonymous.me().await,
+        Err(ClientError::NotAuthenticated)
+    ));
+    assert!(matches!(
+        anonymous.login("nobody@example.com", PASSWORD).await,
+        Err(ClientError::Unauthorized(_))
+    ));
+
+    let admin = admin_client().await;
+    let dto = unique_user();
+    admin.create_user(&dto).await.unwrap();
+    let duplicated = admin.create_user(&dto).await.unwrap_err();
+    assert!(matches!(duplicated, ClientError::Conflict(_)));
+    assert!(duplicated.message().is_some());
+
+    assert!(matches!(
+        admin.get_user(i64::MAX).await,
+        Err(ClientError::NotFound(_))
+    ));
+
+    let invalid = CreateUserDto {
+        email: "no-es-un-email".to_string(),
+        ..unique_user()
+    };
+    assert!(matches!(
+        admin.create_user(&invalid).await,
+        Err(ClientError::BadRequest(_))
+    ));
+
+    let user = seed_user(Permissions::READ_MYSELF).await;
+    let limited = RAuthClient::new(server_url());
+    limited.login(&user.email, PASSWORD).await.unwrap();
+    assert!(matches!(
+        limited.create_user(&unique_user()).await,
+        Err(ClientError::Forbidden(_))
+    ));
+}
+
+/// ---
+///
+/// ## Test Case 7: Inactivar y eliminar usuarios
+///
+#[tokio::test]
+async fn test_inactivate_and_delete() {
+    let admin = admin_client().await;
+    let dto = unique_user();
+    let user = admin.create_user(&dto).await.unwrap();
+    let id = user.id;
+
+    admin.inactivate_user(id).await.unwrap();
+    let client = RAuthClient::new(server_url());
+    assert!(matches!(
+        client.login(&dto.email, PASSWORD).await,
+        Err(ClientError::Forbidden(_))
+    ));
+
+    admin.delete_user(id).await.unwrap();
+    assert!(matches!(
+        client.login(&dto.email, PASSWORD).await,
+        Err(ClientError::Unauthorized(_))
+    ));
+
+    let own = seed_user(Permissions::READ_MYSELF | Permissions::DELETE_MYSELF).await;
+    let client = RAuthClient::new(server_url());
+    client.login(&own.email, PASSWORD).await.unwrap();
+    client.delete_me().await.unwrap();
+}
+
+/// ---
+///
+/// ## Test Case 8: Reactivar un usuario inactivo y restaurar uno eliminado
+///
+#[tokio::test]
+async fn test_reactivate_and_restore() {
+    let admin = admin_client().await;
+    let user = admin.create_user(&unique_user()).await.unwrap();
+
+    assert!(matches!(
+        admin.reactivate_user(user.id).await,
+        Err(ClientError::Conflict(_))
+    ));
+    admin.inactivate_user(user.id).await.unwrap();
+    admin.reactivate_user(user.id).await.unwrap();
+    assert_eq!(
+        admin.get_user(user.id).await.unwrap().status,
+        UserStatus::Active
+    );
+
+    admin.delete_user(user.id).await.unwrap();
+    admin.restore_user(user.id).await.unwrap();
+    assert_eq!(
+        admin.get_user(user.id).await.unwrap().status,
+        UserStatus::Active
+    );
+}
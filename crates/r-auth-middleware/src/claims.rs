@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{AuthError, Permissions};
+
+/// Claims de un token de login de r-auth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub user_id: String,
+    pub exp: usize,
+    pub iat: usize,
+
+    /// Bitmask de `Permissions` al momento de emitir el token.
+    #[serde(default)]
+    pub perms: i64,
+
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Los tokens con scope (p. ej. `password_change`) solo sirven en rutas
+    /// de r-auth y `TokenVerifier` los rechaza.
+    #[serde(default)]
+    pub scope: Option<String>,
+
+    /// Atributos personalizados que r-auth copia en el token.
+    #[serde(default)]
+    pub attrs: Option<Map<String, Value>>,
+}
+
+impl TokenClaims {
+    pub fn permissions(&self) -> Permissions {
+        Permissions::from_bits_retain(self.perms)
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&Value> {
+        self.attrs.as_ref()?.get(name)
+    }
+
+    /// `ADMIN` concede cualquier permiso, igual que en r-auth.
+    pub fn has_permission(&self, perm: Permissions) -> bool {
+        let perms = self.permissions();
+        perms.contains(perm) || perms.contains(Permissions::ADMIN)
+    }
+
+    pub fn require_permission(&self, perm: Permissions) -> Result<(), AuthError> {
+        if self.has_permission(perm) {
+            Ok(())
+        } else {
+            Err(AuthError::Forbidden)
+        }
+    }
+}
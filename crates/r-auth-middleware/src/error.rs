@@ -0,0 +1,63 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// Errores de verificación. Se responden con el mismo formato que `HttpError`
+/// de r-auth: `{"errors": {"client": ["..."]}}`.
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("Token faltante o inválido")]
+    MissingToken,
+
+    #[error("Token inválido")]
+    InvalidToken,
+
+    #[error("Permisos de usuario insuficientes")]
+    Forbidden,
+
+    #[error("Token CSRF inválido")]
+    CsrfMismatch,
+
+    #[error("El token solo permite cambiar la contraseña")]
+    RestrictedToken,
+
+    #[error("No se pudieron obtener las claves de verificación")]
+    KeysUnavailable,
+
+    #[error("Clave de verificación inválida: {0}")]
+    InvalidKey(String),
+
+    #[error("TokenVerifier no configurado")]
+    NotConfigured,
+}
+
+impl AuthError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AuthError::MissingToken | AuthError::InvalidToken => StatusCode::UNAUTHORIZED,
+            AuthError::Forbidden | AuthError::CsrfMismatch | AuthError::RestrictedToken => {
+                StatusCode::FORBIDDEN
+            }
+            AuthError::KeysUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            AuthError::InvalidKey(_) | AuthError::NotConfigured => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let key = if status.is_server_error() {
+            "server"
+        } else {
+            "client"
+        };
+        let body = json!({ "errors": { key: [self.to_string()] } });
+        (status, Json(body)).into_response()
+    }
+}
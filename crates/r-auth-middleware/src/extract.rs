@@ -0,0 +1,42 @@
+use std::ops::Deref;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{AuthError, TokenClaims, TokenVerifier, csrf_valid, request_token};
+
+/// Claims del usuario autenticado. Usa los que dejó `AuthLayer`; sin la capa,
+/// verifica el token con un `TokenVerifier` agregado como `Extension`.
+#[derive(Debug, Clone)]
+pub struct Authenticated(pub TokenClaims);
+
+impl Deref for Authenticated {
+    type Target = TokenClaims;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S> FromRequestParts<S> for Authenticated
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(claims) = parts.extensions.get::<TokenClaims>() {
+            return Ok(Authenticated(claims.clone()));
+        }
+
+        let verifier = parts
+            .extensions
+            .get::<TokenVerifier>()
+            .cloned()
+            .ok_or(AuthError::NotConfigured)?;
+        if !csrf_valid(&parts.method, &parts.headers) {
+            return Err(AuthError::CsrfMismatch);
+        }
+        let token = request_token(&parts.headers).ok_or(AuthError::MissingToken)?;
+        verifier.verify(&token).await.map(Authenticated)
+    }
+}
@@ -0,0 +1,17 @@
+//! Cliente HTTP compartido: lo usa el verificador para descargar el JWKS y el
+//! servidor de r-auth para sus llamadas salientes (OIDC, CAPTCHA).
+
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reutiliza las conexiones entre peticiones y corta las que tarden más de
+/// diez segundos.
+pub static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("No se pudo construir el cliente HTTP")
+});
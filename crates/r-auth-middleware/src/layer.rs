@@ -0,0 +1,101 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{AuthError, Permissions, TokenVerifier, csrf_valid, request_token};
+
+/// Exige un token válido (header Bearer o cookie de sesión, con CSRF para
+/// métodos que modifican estado) y deja los `TokenClaims` en las extensiones
+/// de la petición para `Authenticated`.
+#[derive(Clone)]
+pub struct AuthLayer {
+    verifier: TokenVerifier,
+    required: Permissions,
+}
+
+impl AuthLayer {
+    pub fn new(verifier: TokenVerifier) -> Self {
+        AuthLayer {
+            verifier,
+            required: Permissions::empty(),
+        }
+    }
+
+    /// Además del token válido exige estos permisos (o `ADMIN`).
+    pub fn require_permission(mut self, perm: Permissions) -> Self {
+        self.required |= perm;
+        self
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            verifier: self.verifier.clone(),
+            required: self.required,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthService<S> {
+    inner: S,
+    verifier: TokenVerifier,
+    required: Permissions,
+}
+
+impl<S> Service<Request> for AuthService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        // El servicio listo es `self.inner`; se usa ese y se deja un clon en su lugar.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let verifier = self.verifier.clone();
+        let required = self.required;
+
+        Box::pin(async move {
+            let claims = match request_token(request.headers()) {
+                _ if !csrf_valid(request.method(), request.headers()) => {
+                    Err(AuthError::CsrfMismatch)
+                }
+                Some(token) => verifier.verify(&token).await,
+                None => Err(AuthError::MissingToken),
+            }
+            .and_then(|claims| {
+                claims.require_permission(required)?;
+                Ok(claims)
+            });
+
+            match claims {
+                Ok(claims) => {
+                    request.extensions_mut().insert(claims);
+                    inner.call(request).await
+                }
+                Err(e) => Ok(e.into_response()),
+            }
+        })
+    }
+}
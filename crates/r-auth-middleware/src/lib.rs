@@ -0,0 +1,36 @@
+//! Verificación de tokens emitidos por r-auth para otros servicios axum/tower,
+//! sin acceso a la base de datos de usuarios.
+//!
+//! ```ignore
+//! let verifier = TokenVerifier::jwks("https://auth.example.com/.well-known/jwks.json");
+//! let app = Router::new()
+//!     .route("/reports", get(reports))
+//!     .layer(AuthLayer::new(verifier).require_permission(Permissions::READ_USERS));
+//!
+//! async fn reports(Authenticated(claims): Authenticated) -> String {
+//!     format!("Hola {}", claims.user_id)
+//! }
+//! ```
+//!
+//! Los permisos viajan en el token, así que un cambio de permisos se refleja
+//! recién cuando el usuario obtiene un token nuevo.
+
+mod claims;
+mod error;
+mod extract;
+mod http;
+mod layer;
+mod permissions;
+mod token;
+mod verifier;
+
+pub use claims::TokenClaims;
+pub use error::AuthError;
+pub use extract::Authenticated;
+pub use http::HTTP_CLIENT;
+pub use layer::{AuthLayer, AuthService};
+pub use permissions::{Permissions, parse_permission_names};
+pub use token::{
+    CSRF_COOKIE, CSRF_HEADER, SESSION_COOKIE, cookie_value, csrf_valid, request_token,
+};
+pub use verifier::{DEFAULT_JWKS_TTL, TokenVerifier};
@@ -0,0 +1,30 @@
+use bitflags::bitflags;
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Permissions: i64 {
+        const READ_MYSELF = 1 <<0;
+        const UPDATE_MYSELF = 1 << 1;
+        const DELETE_MYSELF = 1 << 2;
+
+        const ADMIN = 1 << 3;
+
+        const CREATE_USERS = 1 << 4;
+        const READ_USERS = 1 << 5;
+        const UPDATE_USERS = 1 << 6;
+        const DELETE_USERS = 1 << 7;
+
+    }
+}
+
+/// Convierte una lista de nombres de permisos (`ADMIN|READ_USERS` o
+/// `ADMIN,READ_USERS`) en su bitmask. Devuelve `None` si algún nombre no existe.
+pub fn parse_permission_names(names: &str) -> Option<Permissions> {
+    names
+        .split(['|', ','])
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .try_fold(Permissions::empty(), |acc, name| {
+            Permissions::from_name(&name.to_uppercase()).map(|p| acc | p)
+        })
+}
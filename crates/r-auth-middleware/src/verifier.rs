@@ -0,0 +1,205 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    jwk::{Jwk, JwkSet},
+};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{error, warn};
+
+use crate::{AuthError, HTTP_CLIENT, TokenClaims};
+
+/// Tiempo que se reutiliza un JWKS descargado.
+pub const DEFAULT_JWKS_TTL: Duration = Duration::from_secs(3600);
+
+/// Intervalo mínimo entre descargas forzadas por un `kid` desconocido, para
+/// que tokens con `kid` inventados no disparen una descarga por petición.
+const JWKS_MIN_REFRESH: Duration = Duration::from_secs(30);
+
+/// Verifica tokens de r-auth. Es barato de clonar y comparte la caché de claves.
+#[derive(Clone)]
+pub struct TokenVerifier {
+    keys: Arc<Keys>,
+}
+
+enum Keys {
+    Static {
+        key: DecodingKey,
+        algorithm: Algorithm,
+    },
+    Jwks(JwksCache),
+}
+
+struct JwksCache {
+    url: String,
+    ttl: Duration,
+    cached: RwLock<Option<CachedKeys>>,
+    refresh: Mutex<()>,
+}
+
+struct CachedKeys {
+    fetched_at: Instant,
+    keys: Vec<(Option<String>, DecodingKey, Option<Algorithm>)>,
+}
+
+impl TokenVerifier {
+    /// Tokens HS256 firmados con el `JWT_SECRET` de r-auth.
+    pub fn from_secret(secret: impl AsRef<[u8]>) -> Self {
+        Self::from_key(DecodingKey::from_secret(secret.as_ref()), Algorithm::HS256)
+    }
+
+    /// Tokens RS256 verificados con la clave pública en PEM.
+    pub fn from_rsa_pem(pem: &[u8]) -> Result<Self, AuthError> {
+        let key =
+            DecodingKey::from_rsa_pem(pem).map_err(|e| AuthError::InvalidKey(e.to_string()))?;
+        Ok(Self::from_key(key, Algorithm::RS256))
+    }
+
+    pub fn from_key(key: DecodingKey, algorithm: Algorithm) -> Self {
+        TokenVerifier {
+            keys: Arc::new(Keys::Static { key, algorithm }),
+        }
+    }
+
+    /// Claves públicas descargadas de un JWKS (ej. `/.well-known/jwks.json`
+    /// de r-auth) y cacheadas durante `DEFAULT_JWKS_TTL`.
+    pub fn jwks(url: impl Into<String>) -> Self {
+        Self::jwks_with_ttl(url, DEFAULT_JWKS_TTL)
+    }
+
+    pub fn jwks_with_ttl(url: impl Into<String>, ttl: Duration) -> Self {
+        TokenVerifier {
+            keys: Arc::new(Keys::Jwks(JwksCache {
+                url: url.into(),
+                ttl,
+                cached: RwLock::new(None),
+                refresh: Mutex::new(()),
+            })),
+        }
+    }
+
+    pub async fn verify(&self, token: &str) -> Result<TokenClaims, AuthError> {
+        let (key, algorithm) = match self.keys.as_ref() {
+            Keys::Static { key, algorithm } => (key.clone(), *algorithm),
+            Keys::Jwks(cache) => {
+                let header = decode_header(token).map_err(|_| AuthError::InvalidToken)?;
+                // Un JWKS solo publica claves asimétricas; aceptar HS* permitiría
+                // firmar con la clave pública como secreto.
+                if matches!(
+                    header.alg,
+                    Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+                ) {
+                    return Err(AuthError::InvalidToken);
+                }
+                let key = cache.key(header.kid.as_deref(), header.alg).await?;
+                (key, header.alg)
+            }
+        };
+
+        let claims = decode::<TokenClaims>(token, &key, &Validation::new(algorithm))
+            .map(|data| data.claims)
+            .map_err(|_| AuthError::InvalidToken)?;
+        if claims.scope.is_some() {
+            return Err(AuthError::RestrictedToken);
+        }
+        Ok(claims)
+    }
+}
+
+impl JwksCache {
+    async fn key(&self, kid: Option<&str>, alg: Algorithm) -> Result<DecodingKey, AuthError> {
+        {
+            let cached = self.cached.read().await;
+            if let Some(cached) = cached.as_ref()
+                && cached.fetched_at.elapsed() < self.ttl
+                && let Some(key) = cached.find(kid, alg)
+            {
+                return Ok(key);
+            }
+        }
+
+        let _guard = self.refresh.lock().await;
+        {
+            // Otra tarea pudo haber refrescado mientras esperábamos el lock.
+            let cached = self.cached.read().await;
+            if let Some(cached) = cached.as_ref() {
+                let fresh = cached.fetched_at.elapsed() < self.ttl;
+                if let Some(key) = cached.find(kid, alg).filter(|_| fresh) {
+                    return Ok(key);
+                }
+                if fresh && cached.fetched_at.elapsed() < JWKS_MIN_REFRESH {
+                    return Err(AuthError::InvalidToken);
+                }
+            }
+        }
+
+        match self.fetch().await {
+            Ok(keys) => {
+                let cached = CachedKeys {
+                    fetched_at: Instant::now(),
+                    keys,
+                };
+                let key = cached.find(kid, alg);
+                *self.cached.write().await = Some(cached);
+                key.ok_or(AuthError::InvalidToken)
+            }
+            Err(e) => {
+                // Si el JWKS no responde se siguen usando las claves anteriores.
+                let cached = self.cached.read().await;
+                match cached.as_ref() {
+                    Some(cached) => {
+                        warn!(error = %e, url = %self.url, "Usando JWKS en caché vencido");
+                        cached.find(kid, alg).ok_or(AuthError::InvalidToken)
+                    }
+                    None => {
+                        error!(error = %e, url = %self.url, "No se pudo obtener el JWKS");
+                        Err(AuthError::KeysUnavailable)
+                    }
+                }
+            }
+        }
+    }
+
+    async fn fetch(&self) -> Result<Vec<(Option<String>, DecodingKey, Option<Algorithm>)>, String> {
+        let response = HTTP_CLIENT
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("JWKS respondió {}", response.status()));
+        }
+        let set: JwkSet = response.json().await.map_err(|e| e.to_string())?;
+        Ok(set
+            .keys
+            .iter()
+            .filter_map(|jwk: &Jwk| {
+                let key = DecodingKey::from_jwk(jwk).ok()?;
+                let alg = jwk
+                    .common
+                    .key_algorithm
+                    .and_then(|a| a.to_string().parse::<Algorithm>().ok());
+                Some((jwk.common.key_id.clone(), key, alg))
+            })
+            .collect())
+    }
+}
+
+impl CachedKeys {
+    /// Sin `kid` solo se acepta un JWKS de una única clave.
+    fn find(&self, kid: Option<&str>, alg: Algorithm) -> Option<DecodingKey> {
+        let matches_alg = |a: &Option<Algorithm>| a.is_none_or(|a| a == alg);
+        match kid {
+            Some(kid) => self
+                .keys
+                .iter()
+                .find(|(k, _, a)| k.as_deref() == Some(kid) && matches_alg(a)),
+            None if self.keys.len() == 1 => self.keys.first().filter(|(_, _, a)| matches_alg(a)),
+            None => None,
+        }
+        .map(|(_, key, _)| key.clone())
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{Router, routing::get};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use r_auth_middleware::{AuthError, TokenClaims, TokenVerifier};
+
+const PRIVATE_KEY: &[u8] = include_bytes!("fixtures/rsa_private_key.pem");
+const JWKS: &str = include_str!("fixtures/jwks.json");
+
+fn claims() -> TokenClaims {
+    let now = jsonwebtoken::get_current_timestamp() as usize;
+    TokenClaims {
+        user_id: "7".to_string(),
+        exp: now + 600,
+        iat: now,
+        perms: 1,
+        username: None,
+        scope: None,
+        attrs: None,
+    }
+}
+
+fn rs256_token(kid: Option<&str>) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = kid.map(str::to_string);
+    encode(
+        &header,
+        &claims(),
+        &EncodingKey::from_rsa_pem(PRIVATE_KEY).unwrap(),
+    )
+    .unwrap()
+}
+
+/// Sirve el JWKS de prueba y cuenta cuántas veces se descargó.
+async fn jwks_server() -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let app = Router::new().route(
+        "/jwks.json",
+        get(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { ([("content-type", "application/json")], JWKS) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}/jwks.json", addr), hits)
+}
+
+/// ---
+///
+/// ## Test Case 1: Verifica tokens RS256 con el JWKS y lo cachea
+///
+#[tokio::test]
+async fn test_jwks_verifies_and_caches() {
+    let (url, hits) = jwks_server().await;
+    let verifier = TokenVerifier::jwks(url);
+
+    for _ in 0..3 {
+        let claims = verifier
+            .verify(&rs256_token(Some("test-key")))
+            .await
+            .expect("El token debería ser válido");
+        assert_eq!(claims.user_id, "7");
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 1, "El JWKS debería cachearse");
+
+    // Sin kid se acepta porque el JWKS tiene una sola clave.
+    assert!(verifier.verify(&rs256_token(None)).await.is_ok());
+}
+
+/// ---
+///
+/// ## Test Case 2: Un kid desconocido no fuerza descargas en cada petición
+///
+#[tokio::test]
+async fn test_jwks_unknown_kid() {
+    let (url, hits) = jwks_server().await;
+    let verifier = TokenVerifier::jwks(url);
+    verifier
+        .verify(&rs256_token(Some("test-key")))
+        .await
+        .unwrap();
+
+    for _ in 0..3 {
+        let err = verifier
+            .verify(&rs256_token(Some("other-key")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AuthError::InvalidToken));
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+/// ---
+///
+/// ## Test Case 3: El TTL vencido provoca una nueva descarga
+///
+#[tokio::test]
+async fn test_jwks_ttl_refresh() {
+    let (url, hits) = jwks_server().await;
+    let verifier = TokenVerifier::jwks_with_ttl(url, Duration::from_millis(50));
+
+    verifier
+        .verify(&rs256_token(Some("test-key")))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    verifier
+        .verify(&rs256_token(Some("test-key")))
+        .await
+        .unwrap();
+
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+/// ---
+///
+/// ## Test Case 4: Tokens HS256 no se aceptan contra un JWKS y un JWKS caído da 503
+///
+#[tokio::test]
+async fn test_jwks_rejects_hmac_and_unavailable() {
+    let (url, _) = jwks_server().await;
+    let verifier = TokenVerifier::jwks(url);
+    let hmac = encode(
+        &Header::default(),
+        &claims(),
+        &EncodingKey::from_secret(JWKS.as_bytes()),
+    )
+    .unwrap();
+    assert!(matches!(
+        verifier.verify(&hmac).await,
+        Err(AuthError::InvalidToken)
+    ));
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let down = TokenVerifier::jwks(format!("http://127.0.0.1:{}/jwks.json", port));
+    let err = down
+        .verify(&rs256_token(Some("test-key")))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+}
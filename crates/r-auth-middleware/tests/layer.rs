@@ -0,0 +1,207 @@
+use axum::{
+    Extension, Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    routing::{get, post},
+};
+use jsonwebtoken::{EncodingKey, Header, encode};
+use r_auth_middleware::{
+    AuthLayer, Authenticated, CSRF_COOKIE, CSRF_HEADER, Permissions, SESSION_COOKIE, TokenClaims,
+    TokenVerifier,
+};
+use tower::ServiceExt;
+
+const SECRET: &str = "middleware-test-secret";
+
+fn token(perms: Permissions, secret: &str) -> String {
+    scoped_token(perms, secret, None)
+}
+
+fn scoped_token(perms: Permissions, secret: &str, scope: Option<&str>) -> String {
+    let now = jsonwebtoken::get_current_timestamp() as usize;
+    let claims = TokenClaims {
+        user_id: "42".to_string(),
+        exp: now + 600,
+        iat: now,
+        perms: perms.bits(),
+        username: Some("tester".to_string()),
+        scope: scope.map(str::to_string),
+        attrs: None,
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn whoami(Authenticated(claims): Authenticated) -> String {
+    format!("{}:{}", claims.user_id, claims.username.unwrap_or_default())
+}
+
+async fn status(app: Router, request: Request<Body>) -> StatusCode {
+    app.oneshot(request).await.unwrap().status()
+}
+
+fn get_with(auth: Option<String>) -> Request<Body> {
+    let mut builder = Request::get("/");
+    if let Some(auth) = auth {
+        builder = builder.header(header::AUTHORIZATION, auth);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+/// ---
+///
+/// ## Test Case 1: La capa acepta tokens válidos y expone los claims
+///
+#[tokio::test]
+async fn test_layer_accepts_valid_token() {
+    let app = Router::new()
+        .route("/", get(whoami))
+        .layer(AuthLayer::new(TokenVerifier::from_secret(SECRET)));
+
+    let response = app
+        .oneshot(get_with(Some(format!(
+            "Bearer {}",
+            token(Permissions::READ_MYSELF, SECRET)
+        ))))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"42:tester");
+}
+
+/// ---
+///
+/// ## Test Case 2: Tokens faltantes o con otra firma se rechazan con 401
+///
+#[tokio::test]
+async fn test_layer_rejects_missing_or_forged_token() {
+    let app = Router::new()
+        .route("/", get(whoami))
+        .layer(AuthLayer::new(TokenVerifier::from_secret(SECRET)));
+
+    assert_eq!(
+        status(app.clone(), get_with(None)).await,
+        StatusCode::UNAUTHORIZED
+    );
+    let forged = format!("Bearer {}", token(Permissions::ADMIN, "otro-secreto"));
+    assert_eq!(
+        status(app, get_with(Some(forged))).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: require_permission exige el permiso o ADMIN
+///
+#[tokio::test]
+async fn test_layer_require_permission() {
+    let app = Router::new().route("/", get(whoami)).layer(
+        AuthLayer::new(TokenVerifier::from_secret(SECRET))
+            .require_permission(Permissions::READ_USERS),
+    );
+
+    let cases = [
+        (Permissions::READ_MYSELF, StatusCode::FORBIDDEN),
+        (Permissions::READ_USERS, StatusCode::OK),
+        (Permissions::ADMIN, StatusCode::OK),
+    ];
+    for (perms, expected) in cases {
+        let auth = format!("Bearer {}", token(perms, SECRET));
+        assert_eq!(status(app.clone(), get_with(Some(auth))).await, expected);
+    }
+}
+
+/// ---
+///
+/// ## Test Case 4: El extractor verifica por sí mismo con un TokenVerifier en Extension
+///
+#[tokio::test]
+async fn test_extractor_with_extension_and_cookie() {
+    let app = Router::new()
+        .route("/", get(whoami))
+        .layer(Extension(TokenVerifier::from_secret(SECRET)));
+
+    let request = Request::get("/")
+        .header(
+            header::COOKIE,
+            format!("{}={}", SESSION_COOKIE, token(Permissions::empty(), SECRET)),
+        )
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(status(app.clone(), request).await, StatusCode::OK);
+    assert_eq!(status(app, get_with(None)).await, StatusCode::UNAUTHORIZED);
+
+    let unconfigured = Router::new().route("/", get(whoami));
+    let auth = format!("Bearer {}", token(Permissions::empty(), SECRET));
+    assert_eq!(
+        status(unconfigured, get_with(Some(auth))).await,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+/// ---
+///
+/// ## Test Case 5: Un POST autenticado por cookie exige el token CSRF
+///
+#[tokio::test]
+async fn test_cookie_post_requires_csrf() {
+    let app = Router::new()
+        .route("/", post(whoami))
+        .layer(AuthLayer::new(TokenVerifier::from_secret(SECRET)));
+    let jwt = token(Permissions::empty(), SECRET);
+    let cookies = format!("{}={}; {}=abc123", SESSION_COOKIE, jwt, CSRF_COOKIE);
+
+    let post_with = |csrf: Option<&str>| {
+        let mut builder = Request::post("/").header(header::COOKIE, cookies.clone());
+        if let Some(csrf) = csrf {
+            builder = builder.header(CSRF_HEADER, csrf);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    assert_eq!(
+        status(app.clone(), post_with(None)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(app.clone(), post_with(Some("otro"))).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(app.clone(), post_with(Some("abc123"))).await,
+        StatusCode::OK
+    );
+
+    // Con Authorization no hace falta CSRF.
+    let request = Request::post("/")
+        .header(header::AUTHORIZATION, format!("Bearer {}", jwt))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(status(app, request).await, StatusCode::OK);
+}
+
+/// ---
+///
+/// ## Test Case 6: Los tokens limitados al cambio de contraseña se rechazan con 403
+///
+#[tokio::test]
+async fn test_layer_rejects_scoped_token() {
+    let app = Router::new()
+        .route("/", get(whoami))
+        .layer(AuthLayer::new(TokenVerifier::from_secret(SECRET)));
+
+    let scoped = scoped_token(Permissions::ADMIN, SECRET, Some("password_change"));
+    assert_eq!(
+        status(app, get_with(Some(format!("Bearer {}", scoped)))).await,
+        StatusCode::FORBIDDEN
+    );
+}
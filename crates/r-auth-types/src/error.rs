@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Cuerpo de todas las respuestas de error: mensajes agrupados por origen
+/// (`client`, `server`, `validation`) o por campo.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct HttpError {
+    pub errors: HashMap<String, Vec<String>>,
+}
+
+#[cfg(feature = "server")]
+mod responses {
+    use std::collections::HashMap;
+
+    use axum::{Json, http::StatusCode};
+    use validator::ValidationErrors;
+
+    use super::HttpError;
+
+    impl HttpError {
+        // Crea un HttpError para errores 500 genéricos
+        pub fn internal_server_error() -> (StatusCode, Json<Self>) {
+            let mut map = HashMap::new();
+            map.insert(
+                "server".to_string(),
+                vec!["Internal Server Error".to_string()],
+            );
+            let error = HttpError { errors: map };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error))
+        }
+
+        pub fn bad_request(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("client", StatusCode::BAD_REQUEST, message)
+        }
+
+        pub fn not_found(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("client", StatusCode::NOT_FOUND, message)
+        }
+
+        pub fn unauthorized(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("client", StatusCode::UNAUTHORIZED, message)
+        }
+
+        pub fn forbbiden(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("client", StatusCode::FORBIDDEN, message)
+        }
+
+        pub fn conflict(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("client", StatusCode::CONFLICT, message)
+        }
+
+        pub fn too_many_requests(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("client", StatusCode::TOO_MANY_REQUESTS, message)
+        }
+
+        pub fn bad_gateway(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("server", StatusCode::BAD_GATEWAY, message)
+        }
+
+        pub fn service_unavailable(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("server", StatusCode::SERVICE_UNAVAILABLE, message)
+        }
+
+        /// 403 con la clave `password_change_required`, para que los clientes
+        /// distingan este caso y redirijan al formulario de cambio de contraseña.
+        pub fn password_change_required(message: &str) -> (StatusCode, Json<Self>) {
+            Self::error("password_change_required", StatusCode::FORBIDDEN, message)
+        }
+
+        /// Varios mensajes para un mismo campo, p. ej. cada regla incumplida.
+        pub fn field_errors(
+            key: &str,
+            code: StatusCode,
+            messages: Vec<String>,
+        ) -> (StatusCode, Json<Self>) {
+            let mut map = HashMap::new();
+            map.insert(key.to_string(), messages);
+            (code, Json(HttpError { errors: map }))
+        }
+
+        fn error(key: &str, code: StatusCode, message: &str) -> (StatusCode, Json<Self>) {
+            let mut map = HashMap::new();
+            map.insert(key.to_string(), vec![message.to_string()]);
+            let error = HttpError { errors: map };
+            (code, Json(error))
+        }
+
+        pub fn errors(e: ValidationErrors) -> (StatusCode, Json<Self>) {
+            let mut map: HashMap<String, Vec<String>> = HashMap::new();
+            map.insert("validation".to_string(), vec![format!("{}", e)]);
+            let http_err = HttpError { errors: map };
+            (StatusCode::BAD_REQUEST, Json(http_err))
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! DTOs y entidades de `/api/users/*` compartidos por el servidor y el
+//! cliente. Sin features solo depende de serde y chrono; la feature `server`
+//! agrega validación, esquemas OpenAPI, lectura desde Postgres y las
+//! respuestas de error de axum.
+
+mod error;
+mod login;
+mod normalize;
+mod query;
+mod response;
+mod status;
+mod user;
+mod user_dto;
+
+pub use error::HttpError;
+pub use login::{LoginRequest, LoginResponse};
+pub use normalize::{normalize_email, normalize_username};
+pub use query::FindQuery;
+pub use response::{FindResult, MessageResponse, OneResult};
+pub use status::{UserStatus, serialize_status_name};
+pub use user::User;
+pub use user_dto::{ChangePasswordDto, CreateUserDto, UpdateUserDto};
+#[cfg(feature = "server")]
+pub use user_dto::{USERNAME_RE, validate_avatar_url, validate_locale, validate_phone_number};
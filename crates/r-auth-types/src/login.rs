@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(validator::Validate, utoipa::ToSchema))]
+pub struct LoginRequest {
+    #[cfg_attr(
+        feature = "server",
+        validate(
+            email(message = "El email es obligatorio"),
+            length(
+                max = 100,
+                message = "La longitud máxima del email es de 100 caracteres"
+            )
+        )
+    )]
+    pub email: String,
+
+    /// La política de contraseñas se aplica al fijarla, no al iniciar sesión;
+    /// aquí solo se acota el tamaño para no hashear entradas arbitrarias.
+    #[cfg_attr(
+        feature = "server",
+        validate(length(
+            max = 1024,
+            message = "La longitud máxima de la contraseña es de 1024 caracteres"
+        ))
+    )]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct LoginResponse {
+    /// JWT de la sesión; se omite cuando `SESSION_MODE=cookie`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Token a reenviar en `X-CSRF-Token` en modo cookie.
+    #[serde(rename = "csrfToken", skip_serializing_if = "Option::is_none")]
+    pub csrf_token: Option<String>,
+}
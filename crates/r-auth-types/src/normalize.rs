@@ -0,0 +1,13 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Forma canónica de un email: sin espacios alrededor, NFKC y en minúsculas.
+/// Es la que se guarda y la que se compara contra `lower(email)`.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().nfkc().collect::<String>().to_lowercase()
+}
+
+/// Forma canónica de un username: sin espacios alrededor y NFKC. Conserva
+/// las mayúsculas; la unicidad se compara con `lower(username)`.
+pub fn normalize_username(username: &str) -> String {
+    username.trim().nfkc().collect()
+}
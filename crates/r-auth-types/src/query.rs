@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::UserStatus;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "server",
+    derive(validator::Validate, utoipa::ToSchema, utoipa::IntoParams)
+)]
+pub struct FindQuery {
+    #[serde(rename = "queryKey")]
+    #[cfg_attr(
+        feature = "server",
+        validate(length(
+            min = 1,
+            message = "The search key of the query must have at least 1 characters"
+        ))
+    )]
+    pub query_key: Option<String>,
+
+    #[serde(rename = "queryValue")]
+    #[cfg_attr(
+        feature = "server",
+        validate(length(
+            min = 1,
+            message = "The search value of the query must have at least 1 characters"
+        ))
+    )]
+    pub query_value: Option<String>,
+
+    /// Búsqueda libre sobre username y email a la vez: coincidencias
+    /// parciales y por similitud de trigramas. Sin `sort` explícito los
+    /// resultados se ordenan por relevancia.
+    #[cfg_attr(
+        feature = "server",
+        validate(length(
+            min = 1,
+            max = 100,
+            message = "The search text must be between 1 and 100 characters"
+        ))
+    )]
+    pub q: Option<String>,
+
+    /// `contains` (por defecto) o `exact`. La comparación de texto no
+    /// distingue mayúsculas.
+    #[serde(rename = "match")]
+    pub match_mode: Option<String>,
+
+    /// Sin filtro de estado no se incluyen los usuarios eliminados.
+    pub status: Option<UserStatus>,
+
+    /// Bits de permisos que el usuario debe tener (todos).
+    pub permissions: Option<i64>,
+
+    /// Objeto JSON con los atributos personalizados que el usuario debe
+    /// tener, p. ej. `{"department":"ventas"}`.
+    pub attributes: Option<String>,
+
+    /// Rango `[createdFrom, createdTo)` en RFC 3339; igual para `updated*`.
+    #[serde(rename = "createdFrom")]
+    pub created_from: Option<DateTime<Utc>>,
+
+    #[serde(rename = "createdTo")]
+    pub created_to: Option<DateTime<Utc>>,
+
+    #[serde(rename = "updatedFrom")]
+    pub updated_from: Option<DateTime<Utc>>,
+
+    #[serde(rename = "updatedTo")]
+    pub updated_to: Option<DateTime<Utc>>,
+
+    /// `id` (por defecto), `username`, `email`, `status`, `created_at` o
+    /// `updated_at`. A igualdad se desempata por id.
+    pub sort: Option<String>,
+
+    /// `asc` (por defecto) o `desc`.
+    pub order: Option<String>,
+
+    /// Activa la paginación por cursor sobre `(created_at, id)`: vacío para la
+    /// primera página y luego el `nextCursor` recibido. No se combina con
+    /// `page` y solo admite `sort=created_at`.
+    pub cursor: Option<String>,
+
+    /// Calcula `total`. Por defecto sí con páginas y no con cursor.
+    #[serde(rename = "includeTotal")]
+    pub include_total: Option<bool>,
+
+    #[cfg_attr(
+        feature = "server",
+        validate(range(min = 1, message = "The pagination page must be greather than 1"))
+    )]
+    pub page: Option<i32>,
+
+    #[cfg_attr(
+        feature = "server",
+        validate(range(
+            min = 1,
+            max = 100,
+            message = "The pagination limit must between 1 and 100"
+        ))
+    )]
+    pub limit: Option<i32>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct FindResult<T> {
+    pub results: Vec<T>,
+
+    /// Se omite cuando no se pidió el conteo (ver `includeTotal`).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub total: Option<u64>,
+
+    /// Cursor de la página siguiente; solo en modo cursor y si quedan resultados.
+    #[serde(
+        rename = "nextCursor",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct OneResult<T> {
+    pub result: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct MessageResponse {
+    pub message: String,
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+/// Estado del ciclo de vida de un usuario. Tanto en la base como en el JSON
+/// de la API es el número de siempre (1 activo, 2 inactivo, 3 eliminado);
+/// al leer se acepta también el nombre (`active`, `inactive`, `deleted`),
+/// que es como viaja en los query params y en la importación.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserStatus {
+    Active,
+    Inactive,
+    /// Borrado lógico: se puede restaurar hasta que lo purgue el job.
+    Deleted,
+}
+
+impl UserStatus {
+    pub const fn code(self) -> i32 {
+        match self {
+            UserStatus::Active => 1,
+            UserStatus::Inactive => 2,
+            UserStatus::Deleted => 3,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            1 => Some(UserStatus::Active),
+            2 => Some(UserStatus::Inactive),
+            3 => Some(UserStatus::Deleted),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "active" => Some(UserStatus::Active),
+            "inactive" => Some(UserStatus::Inactive),
+            "deleted" => Some(UserStatus::Deleted),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            UserStatus::Active => "active",
+            UserStatus::Inactive => "inactive",
+            UserStatus::Deleted => "deleted",
+        }
+    }
+
+    /// Transiciones permitidas. Un usuario eliminado solo vuelve a estar
+    /// activo mediante `restore`; pasar al mismo estado no es una transición.
+    pub fn can_transition_to(self, next: UserStatus) -> bool {
+        matches!(
+            (self, next),
+            (UserStatus::Active, UserStatus::Inactive)
+                | (UserStatus::Active, UserStatus::Deleted)
+                | (UserStatus::Inactive, UserStatus::Active)
+                | (UserStatus::Inactive, UserStatus::Deleted)
+                | (UserStatus::Deleted, UserStatus::Active)
+        )
+    }
+}
+
+impl fmt::Display for UserStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for UserStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for UserStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(StatusVisitor)
+    }
+}
+
+/// Serializa el estado por nombre; para formatos pensados para personas,
+/// como la exportación de usuarios.
+pub fn serialize_status_name<S: Serializer>(
+    status: &Option<UserStatus>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match status {
+        Some(status) => serializer.serialize_str(status.name()),
+        None => serializer.serialize_none(),
+    }
+}
+
+struct StatusVisitor;
+
+impl de::Visitor<'_> for StatusVisitor {
+    type Value = UserStatus;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("1, 2, 3, \"active\", \"inactive\" o \"deleted\"")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<UserStatus, E> {
+        i32::try_from(v)
+            .ok()
+            .and_then(UserStatus::from_code)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<UserStatus, E> {
+        i32::try_from(v)
+            .ok()
+            .and_then(UserStatus::from_code)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<UserStatus, E> {
+        // Los query params llegan siempre como texto, también los números.
+        UserStatus::from_name(v)
+            .or_else(|| v.parse().ok().and_then(UserStatus::from_code))
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(feature = "server")]
+mod schema {
+    use utoipa::{
+        PartialSchema, ToSchema,
+        openapi::{
+            KnownFormat, ObjectBuilder, RefOr, Schema, SchemaFormat, Type, schema::SchemaType,
+        },
+    };
+
+    use super::UserStatus;
+
+    impl PartialSchema for UserStatus {
+        fn schema() -> RefOr<Schema> {
+            ObjectBuilder::new()
+                .schema_type(SchemaType::Type(Type::Integer))
+                .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32)))
+                .enum_values(Some([1, 2, 3]))
+                .description(Some(
+                    "1 activo, 2 inactivo, 3 eliminado. Al enviarlo se acepta también \
+                     `active`, `inactive` o `deleted`.",
+                ))
+                .into()
+        }
+    }
+
+    impl ToSchema for UserStatus {}
+}
+
+#[cfg(feature = "server")]
+mod sql {
+    use std::error::Error;
+
+    use bytes::BytesMut;
+    use tokio_postgres::types::{FromSql, IsNull, ToSql, Type, to_sql_checked};
+
+    use super::UserStatus;
+
+    impl ToSql for UserStatus {
+        fn to_sql(
+            &self,
+            ty: &Type,
+            out: &mut BytesMut,
+        ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+            self.code().to_sql(ty, out)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <i32 as ToSql>::accepts(ty)
+        }
+
+        to_sql_checked!();
+    }
+
+    impl<'a> FromSql<'a> for UserStatus {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+            let code = i32::from_sql(ty, raw)?;
+            UserStatus::from_code(code)
+                .ok_or_else(|| format!("status desconocido: {}", code).into())
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <i32 as FromSql>::accepts(ty)
+        }
+    }
+}
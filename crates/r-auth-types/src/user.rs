@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::UserStatus;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+
+    #[serde(skip_serializing)]
+    pub password: Option<String>,
+
+    #[serde(skip_serializing, default)]
+    pub permissions: i64,
+
+    pub status: UserStatus,
+
+    /// Momento del borrado lógico; se usa para purgar.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    /// Baja pedida por el usuario; sus datos se anonimizan en esta fecha.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub erasure_scheduled_at: Option<DateTime<Utc>>,
+
+    /// Un administrador exige que cambie la contraseña antes de seguir.
+    #[serde(default)]
+    pub must_change_password: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub display_name: Option<String>,
+
+    /// Etiqueta BCP 47, p. ej. `es-AR`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub locale: Option<String>,
+
+    /// Zona horaria IANA, p. ej. `America/Argentina/Buenos_Aires`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timezone: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub avatar_url: Option<String>,
+
+    /// Formato E.164, p. ej. `+5491122334455`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub phone_number: Option<String>,
+
+    /// Atributos personalizados, validados contra sus definiciones.
+    #[serde(default)]
+    #[cfg_attr(feature = "server", schema(value_type = Object))]
+    pub attributes: Map<String, Value>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn new(id: i64, username: String, email: String) -> Self {
+        User {
+            id,
+            username,
+            email,
+            password: Some(String::from("fake_password")),
+            permissions: 0,
+            status: UserStatus::Active,
+            deleted_at: None,
+            erasure_scheduled_at: None,
+            must_change_password: false,
+            display_name: None,
+            locale: None,
+            timezone: None,
+            avatar_url: None,
+            phone_number: None,
+            attributes: Map::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        User {
+            id: 0,
+            username: "".to_string(),
+            email: "".to_string(),
+            password: Some("".to_string()),
+            permissions: 0,
+            status: UserStatus::Active,
+            deleted_at: None,
+            erasure_scheduled_at: None,
+            must_change_password: false,
+            display_name: None,
+            locale: None,
+            timezone: None,
+            avatar_url: None,
+            phone_number: None,
+            attributes: Map::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+mod rows {
+    use chrono::{DateTime, Utc};
+    use serde_json::{Map, Value};
+    use tokio_postgres::types::Json;
+
+    use super::User;
+
+    impl User {
+        pub fn from_row(row: &tokio_postgres::Row) -> Result<Self, Box<dyn std::error::Error>> {
+            let created_at: DateTime<Utc> = row.try_get("created_at")?;
+            let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+
+            let user = Self {
+                id: row.try_get("id")?,
+                username: row.try_get("username")?,
+                email: row.try_get("email")?,
+                permissions: row.try_get("permissions")?,
+                password: None,
+                status: row.try_get("status")?,
+                deleted_at: row.try_get("deleted_at").unwrap_or(None),
+                erasure_scheduled_at: row.try_get("erasure_scheduled_at").unwrap_or(None),
+                must_change_password: must_change_password(row),
+                display_name: row.try_get("display_name").unwrap_or(None),
+                locale: row.try_get("locale").unwrap_or(None),
+                timezone: row.try_get("timezone").unwrap_or(None),
+                avatar_url: row.try_get("avatar_url").unwrap_or(None),
+                phone_number: row.try_get("phone_number").unwrap_or(None),
+                attributes: attributes(row),
+                created_at,
+                updated_at,
+            };
+            Ok(user)
+        }
+
+        pub fn from_row_without_perms(
+            row: &tokio_postgres::Row,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            let created_at: DateTime<Utc> = row.try_get("created_at")?;
+            let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+
+            let user = Self {
+                id: row.try_get("id")?,
+                username: row.try_get("username")?,
+                email: row.try_get("email")?,
+                permissions: 0,
+                password: None,
+                status: row.try_get("status")?,
+                deleted_at: row.try_get("deleted_at").unwrap_or(None),
+                erasure_scheduled_at: row.try_get("erasure_scheduled_at").unwrap_or(None),
+                must_change_password: must_change_password(row),
+                display_name: row.try_get("display_name").unwrap_or(None),
+                locale: row.try_get("locale").unwrap_or(None),
+                timezone: row.try_get("timezone").unwrap_or(None),
+                avatar_url: row.try_get("avatar_url").unwrap_or(None),
+                phone_number: row.try_get("phone_number").unwrap_or(None),
+                attributes: attributes(row),
+                created_at,
+                updated_at,
+            };
+            Ok(user)
+        }
+
+        pub fn from_row_with_password(
+            row: &tokio_postgres::Row,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            let created_at: DateTime<Utc> = row.try_get("created_at")?;
+            let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+
+            let password = row.try_get("password").unwrap_or_else(|_| String::new());
+
+            let user = Self {
+                id: row.try_get("id")?,
+                username: row.try_get("username")?,
+                email: row.try_get("email")?,
+                permissions: row.try_get("permissions")?,
+                password: Some(password),
+                status: row.try_get("status")?,
+                deleted_at: row.try_get("deleted_at").unwrap_or(None),
+                erasure_scheduled_at: row.try_get("erasure_scheduled_at").unwrap_or(None),
+                must_change_password: must_change_password(row),
+                display_name: row.try_get("display_name").unwrap_or(None),
+                locale: row.try_get("locale").unwrap_or(None),
+                timezone: row.try_get("timezone").unwrap_or(None),
+                avatar_url: row.try_get("avatar_url").unwrap_or(None),
+                phone_number: row.try_get("phone_number").unwrap_or(None),
+                attributes: attributes(row),
+                created_at,
+                updated_at,
+            };
+            Ok(user)
+        }
+    }
+
+    /// No todas las consultas seleccionan la columna; si falta se asume `false`.
+    fn must_change_password(row: &tokio_postgres::Row) -> bool {
+        row.try_get("must_change_password").unwrap_or(false)
+    }
+
+    /// Igual que `must_change_password`: si la columna falta queda vacío.
+    fn attributes(row: &tokio_postgres::Row) -> Map<String, Value> {
+        row.try_get::<_, Json<Map<String, Value>>>("attributes")
+            .map(|json| json.0)
+            .unwrap_or_default()
+    }
+}
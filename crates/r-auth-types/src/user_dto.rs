@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{normalize_email, normalize_username};
+#[cfg(feature = "server")]
+pub use validation::*;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "server", derive(validator::Validate, utoipa::ToSchema))]
+pub struct CreateUserDto {
+    #[cfg_attr(
+        feature = "server",
+        validate(
+            length(
+                min = 3,
+                max = 100,
+                message = "The username must be between 3 and 100 characters"
+            ),
+            regex(
+                path = "*USERNAME_RE",
+                message = "Username contains invalid characters"
+            )
+        )
+    )]
+    pub username: String,
+
+    #[cfg_attr(
+        feature = "server",
+        validate(email(message = "El correo electrónico es obligatorio"))
+    )]
+    pub email: String,
+
+    /// El largo y el resto de las reglas los define la política de contraseñas.
+    pub password: String,
+
+    /// Atributos personalizados; los obligatorios deben venir en el alta.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "server", schema(value_type = Option<Object>))]
+    pub attributes: Option<Map<String, Value>>,
+}
+
+impl CreateUserDto {
+    /// Username y email en su forma canónica; se aplica antes de validar.
+    pub fn normalized(self) -> Self {
+        CreateUserDto {
+            username: normalize_username(&self.username),
+            email: normalize_email(&self.email),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "server", derive(validator::Validate, utoipa::ToSchema))]
+pub struct UpdateUserDto {
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+
+    #[cfg_attr(
+        feature = "server",
+        validate(
+            length(
+                min = 3,
+                max = 100,
+                message = "The username must be between 3 and 100 characters"
+            ),
+            regex(
+                path = "*USERNAME_RE",
+                message = "Username contains invalid characters"
+            )
+        )
+    )]
+    pub username: Option<String>,
+
+    #[cfg_attr(
+        feature = "server",
+        validate(email(message = "The user mail is required"))
+    )]
+    pub email: Option<String>,
+
+    pub permissions: Option<i64>,
+
+    /// Exige cambiar la contraseña en el próximo login; solo administradores.
+    #[serde(rename = "mustChangePassword", skip_serializing_if = "Option::is_none")]
+    pub must_change_password: Option<bool>,
+
+    /// En los campos de perfil un texto vacío borra el valor.
+    #[serde(rename = "displayName", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "server",
+        validate(length(max = 100, message = "The display name must be at most 100 characters"))
+    )]
+    pub display_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "server", validate(custom(function = "validate_locale")))]
+    pub locale: Option<String>,
+
+    /// Debe ser una zona de `pg_timezone_names`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "server",
+        validate(length(max = 64, message = "The timezone must be at most 64 characters"))
+    )]
+    pub timezone: Option<String>,
+
+    #[serde(rename = "avatarUrl", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "server",
+        validate(
+            length(max = 512, message = "The avatar URL must be at most 512 characters"),
+            custom(function = "validate_avatar_url")
+        )
+    )]
+    pub avatar_url: Option<String>,
+
+    #[serde(rename = "phoneNumber", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "server",
+        validate(custom(function = "validate_phone_number"))
+    )]
+    pub phone_number: Option<String>,
+
+    /// Se fusiona con los atributos actuales; `null` borra la clave.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "server", schema(value_type = Option<Object>))]
+    pub attributes: Option<Map<String, Value>>,
+}
+
+impl UpdateUserDto {
+    pub fn normalized(self) -> Self {
+        let trim = |value: Option<String>| value.map(|v| v.trim().to_string());
+        UpdateUserDto {
+            username: self.username.as_deref().map(normalize_username),
+            email: self.email.as_deref().map(normalize_email),
+            display_name: trim(self.display_name),
+            locale: trim(self.locale),
+            timezone: trim(self.timezone),
+            avatar_url: trim(self.avatar_url),
+            phone_number: trim(self.phone_number),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(validator::Validate, utoipa::ToSchema))]
+pub struct ChangePasswordDto {
+    #[serde(rename = "previousPassword")]
+    pub previous_password: String,
+
+    #[serde(rename = "newPassword")]
+    pub new_password: String,
+}
+
+#[cfg(feature = "server")]
+mod validation {
+    use std::borrow::Cow;
+
+    use once_cell::sync::Lazy;
+    use regex::Regex;
+    use validator::ValidationError;
+
+    pub static USERNAME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_]+$").unwrap());
+
+    /// Etiqueta BCP 47 simplificada: idioma y subetiquetas opcionales.
+    static LOCALE_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^[a-zA-Z]{2,3}(-[a-zA-Z0-9]{2,8})*$").unwrap());
+
+    static PHONE_NUMBER_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^\+[1-9][0-9]{6,14}$").unwrap());
+
+    static AVATAR_URL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^https?://\S+$").unwrap());
+
+    pub fn validate_locale(value: &str) -> Result<(), ValidationError> {
+        empty_or_matches(value, &LOCALE_RE, "locale", "Invalid locale")
+    }
+
+    pub fn validate_avatar_url(value: &str) -> Result<(), ValidationError> {
+        empty_or_matches(
+            value,
+            &AVATAR_URL_RE,
+            "avatar_url",
+            "The avatar URL must be http or https",
+        )
+    }
+
+    pub fn validate_phone_number(value: &str) -> Result<(), ValidationError> {
+        empty_or_matches(
+            value,
+            &PHONE_NUMBER_RE,
+            "phone_number",
+            "The phone number must be in E.164 format",
+        )
+    }
+
+    /// El texto vacío es válido porque borra el campo.
+    fn empty_or_matches(
+        value: &str,
+        re: &Regex,
+        code: &'static str,
+        message: &'static str,
+    ) -> Result<(), ValidationError> {
+        if value.is_empty() || re.is_match(value) {
+            Ok(())
+        } else {
+            Err(ValidationError::new(code).with_message(Cow::Borrowed(message)))
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ldap3::{LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry, SearchOptions};
+use tracing::{error, warn};
+
+use super::AuthBackend;
+use crate::{
+    config::LdapConfig,
+    database::{connection::PgPool, models::entities::user_status::UserStatus},
+    utils::{
+        ApiError, Permissions, USER_PERMISSIONS, available_username, commit_transaction,
+        errors::HttpError, get_pg_client, get_transaction, map_db_error, normalize_email,
+    },
+};
+
+const LDAP_TIMEOUT: Duration = Duration::from_secs(10);
+const IDENTITY_PROVIDER: &str = "ldap";
+
+const RESULT_SUCCESS: u32 = 0;
+const RESULT_SIZE_LIMIT_EXCEEDED: u32 = 4;
+const RESULT_NO_SUCH_OBJECT: u32 = 32;
+const RESULT_INVALID_CREDENTIALS: u32 = 49;
+
+/// Autenticación por bind contra un directorio LDAP / Active Directory. El primer
+/// login exitoso aprovisiona un usuario local y sus permisos se sincronizan con
+/// los grupos del directorio en cada login.
+pub struct LdapBackend {
+    pool: PgPool,
+    config: LdapConfig,
+}
+
+struct DirectoryUser {
+    dn: String,
+    username: String,
+    email: String,
+    permissions: Permissions,
+}
+
+impl LdapBackend {
+    pub fn new(pool: &PgPool, config: LdapConfig) -> Self {
+        LdapBackend {
+            pool: pool.clone(),
+            config,
+        }
+    }
+
+    async fn bind_user(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, LdapError> {
+        let config = &self.config;
+        let settings = LdapConnSettings::new().set_conn_timeout(LDAP_TIMEOUT);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &config.url).await?;
+        ldap3::drive!(conn);
+
+        if !config.bind_dn.is_empty() {
+            ldap.simple_bind(&config.bind_dn, &config.bind_password)
+                .await?
+                .success()?;
+        }
+
+        let filter = config
+            .user_filter
+            .replace("{email}", &ldap3::ldap_escape(email));
+        let attributes = vec![
+            config.username_attribute.as_str(),
+            config.email_attribute.as_str(),
+            config.group_attribute.as_str(),
+        ];
+        let result = ldap
+            .with_search_options(SearchOptions::new().sizelimit(2))
+            .search(&config.search_base, Scope::Subtree, &filter, attributes)
+            .await?;
+        let mut entries = match result.1.rc {
+            RESULT_SUCCESS | RESULT_SIZE_LIMIT_EXCEEDED => result.0,
+            RESULT_NO_SUCH_OBJECT => Vec::new(),
+            _ => return Err(LdapError::LdapResult { result: result.1 }),
+        };
+        if entries.len() != 1 {
+            if entries.len() > 1 {
+                warn!(email, "Más de una entrada LDAP coincide con el email");
+            }
+            let _ = ldap.unbind().await;
+            return Ok(None);
+        }
+        let entry = SearchEntry::construct(entries.remove(0));
+
+        let groups = match &config.group_search_base {
+            Some(base) => {
+                let filter = config
+                    .group_filter
+                    .replace("{dn}", &ldap3::ldap_escape(entry.dn.as_str()));
+                let (groups, _) = ldap
+                    .search(base, Scope::Subtree, &filter, vec!["cn"])
+                    .await?
+                    .success()?;
+                groups
+                    .into_iter()
+                    .map(|g| SearchEntry::construct(g).dn)
+                    .collect()
+            }
+            None => values(&entry, &config.group_attribute).to_vec(),
+        };
+
+        let bind = ldap.simple_bind(&entry.dn, password).await?;
+        let _ = ldap.unbind().await;
+        match bind.rc {
+            RESULT_SUCCESS => {}
+            RESULT_INVALID_CREDENTIALS => return Ok(None),
+            _ => return Err(LdapError::LdapResult { result: bind }),
+        }
+
+        let first = |attribute: &str| values(&entry, attribute).first().cloned();
+        Ok(Some(DirectoryUser {
+            username: first(&config.username_attribute)
+                .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string()),
+            email: first(&config.email_attribute).unwrap_or_else(|| email.to_string()),
+            permissions: self.permissions_for(&groups),
+            dn: entry.dn,
+        }))
+    }
+
+    fn permissions_for(&self, groups: &[String]) -> Permissions {
+        let groups: Vec<String> = groups.iter().map(|g| g.to_lowercase()).collect();
+        self.config
+            .group_permissions
+            .iter()
+            .filter(|(group, _)| groups.contains(group))
+            .fold(USER_PERMISSIONS, |acc, (_, perms)| acc | *perms)
+    }
+
+    /// Vincula la entrada del directorio con un usuario local (creándolo si hace
+    /// falta). Solo se sincronizan los permisos de los usuarios que aprovisionó
+    /// el propio backend; una cuenta local vinculada por email conserva los suyos.
+    /// El directorio no trae atributos personalizados, así que el usuario
+    /// aprovisionado queda exento de los obligatorios: los completa un
+    /// administrador después (ver `new_user_attributes`).
+    async fn provision(&self, user: DirectoryUser) -> Result<Option<i64>, ApiError> {
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let linked = tx
+            .query_opt(
+                r#"
+                    SELECT u.id, u.status, i.provisioned FROM user_identities i
+                    JOIN users u ON u.id = i.user_id
+                    WHERE i.provider = $1 AND i.subject = $2
+                "#,
+                &[&IDENTITY_PROVIDER, &user.dn],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando la identidad LDAP", e))?;
+
+        let (user_id, status, provisioned): (i64, UserStatus, bool) = match linked {
+            Some(row) => (row.get("id"), row.get("status"), row.get("provisioned")),
+            None => {
+                let email = normalize_email(&user.email);
+                let existing = tx
+                    .query_opt(
+                        "SELECT id, status FROM users WHERE lower(email) = $1",
+                        &[&email],
+                    )
+                    .await
+                    .map_err(|e| map_db_error("Error consultando el usuario por email", e))?;
+
+                let (user_id, provisioned) = match existing {
+                    Some(row)
+                        if self.config.link_by_email
+                            && row.get::<_, UserStatus>("status") == UserStatus::Active =>
+                    {
+                        (row.get("id"), false)
+                    }
+                    Some(_) => {
+                        // Sin vinculación explícita, quien controle una entrada
+                        // del directorio con el mismo `mail` podría tomar la cuenta.
+                        warn!(dn = %user.dn, "Entrada LDAP con el email de una cuenta local no vinculada");
+                        return Ok(None);
+                    }
+                    None => {
+                        let username = available_username(&tx, &user.username).await?;
+                        let user_id: i64 = tx
+                            .query_one(
+                                r#"
+                                    INSERT INTO users (username, email, password, permissions, status)
+                                    VALUES ($1, $2, NULL, $3, $4)
+                                    RETURNING id
+                                "#,
+                                &[
+                                    &username,
+                                    &email,
+                                    &user.permissions.bits(),
+                                    &UserStatus::Active,
+                                ],
+                            )
+                            .await
+                            .map_err(|e| map_db_error("Error aprovisionando el usuario LDAP", e))?
+                            .get("id");
+                        (user_id, true)
+                    }
+                };
+
+                tx.execute(
+                    r#"
+                        INSERT INTO user_identities (user_id, provider, subject, email, provisioned)
+                        VALUES ($1, $2, $3, $4, $5)
+                    "#,
+                    &[
+                        &user_id,
+                        &IDENTITY_PROVIDER,
+                        &user.dn,
+                        &user.email,
+                        &provisioned,
+                    ],
+                )
+                .await
+                .map_err(|e| map_db_error("Error vinculando la identidad LDAP", e))?;
+
+                (user_id, UserStatus::Active, provisioned)
+            }
+        };
+
+        match status {
+            UserStatus::Active => {}
+            UserStatus::Inactive => return Err(HttpError::forbbiden("Usuario inactivo")),
+            UserStatus::Deleted => return Ok(None),
+        }
+
+        if provisioned {
+            tx.execute(
+                "UPDATE users SET permissions = $1, updated_at = now() WHERE id = $2",
+                &[&user.permissions.bits(), &user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error sincronizando los permisos LDAP", e))?;
+        }
+
+        commit_transaction(tx, "Error haciendo commit del usuario LDAP").await?;
+        Ok(Some(user_id))
+    }
+}
+
+/// Valores de un atributo; los nombres de atributo LDAP no distinguen mayúsculas.
+fn values<'a>(entry: &'a SearchEntry, attribute: &str) -> &'a [String] {
+    entry
+        .attrs
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(attribute))
+        .map(|(_, values)| values.as_slice())
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl AuthBackend for LdapBackend {
+    fn name(&self) -> &'static str {
+        "ldap"
+    }
+
+    async fn authenticate(&self, email: &str, password: &str) -> Result<Option<i64>, ApiError> {
+        // Un bind con contraseña vacía es un bind no autenticado que muchos
+        // servidores aceptan como éxito.
+        if password.is_empty() {
+            return Ok(None);
+        }
+
+        let user = match tokio::time::timeout(LDAP_TIMEOUT, self.bind_user(email, password)).await {
+            Ok(Ok(Some(user))) => user,
+            Ok(Ok(None)) => return Ok(None),
+            Ok(Err(e)) => {
+                error!(error = %e, "Error autenticando contra LDAP");
+                return Ok(None);
+            }
+            Err(_) => {
+                error!("Tiempo de espera agotado autenticando contra LDAP");
+                return Ok(None);
+            }
+        };
+
+        self.provision(user).await
+    }
+}
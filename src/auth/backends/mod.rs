@@ -0,0 +1,45 @@
+pub mod ldap;
+mod password;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+pub use ldap::LdapBackend;
+pub use password::PasswordBackend;
+
+use crate::{
+    config::{AuthBackendKind, get_config},
+    database::connection::PgPool,
+    utils::ApiError,
+};
+
+/// Origen de credenciales consultado por `UsersService::login`.
+#[async_trait]
+pub trait AuthBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Devuelve el id del usuario local si las credenciales son válidas para este
+    /// backend, o `None` para que se pruebe el siguiente.
+    async fn authenticate(&self, email: &str, password: &str) -> Result<Option<i64>, ApiError>;
+}
+
+pub fn backends_from_config(pool: &PgPool) -> Vec<Arc<dyn AuthBackend>> {
+    let config = get_config();
+    config
+        .auth_backends
+        .iter()
+        .map(|kind| -> Arc<dyn AuthBackend> {
+            match kind {
+                AuthBackendKind::Password => Arc::new(PasswordBackend::new(pool)),
+                AuthBackendKind::Ldap => Arc::new(LdapBackend::new(
+                    pool,
+                    config
+                        .ldap
+                        .clone()
+                        .expect("LDAP backend enabled without configuration"),
+                )),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use tracing::{error, info};
+
+use super::AuthBackend;
+use crate::{
+    auth::{hash_password_pooled, needs_rehash, verify_password_pooled},
+    database::{connection::PgPool, models::entities::user_status::UserStatus},
+    utils::{ApiError, errors::HttpError, get_pg_client, map_db_error, normalize_email},
+};
+
+/// Contraseñas hasheadas con Argon2 en la tabla `users`.
+pub struct PasswordBackend {
+    pool: PgPool,
+}
+
+impl PasswordBackend {
+    pub fn new(pool: &PgPool) -> Self {
+        PasswordBackend { pool: pool.clone() }
+    }
+
+    /// Regenera el hash con la configuración actual. Es el único momento en
+    /// que se conoce la contraseña en claro, así que un fallo solo se registra:
+    /// el login ya fue exitoso y se reintentará en el próximo.
+    async fn rehash(&self, id: i64, password: &str, old_hash: &str) {
+        let Ok(hash) = hash_password_pooled(password).await else {
+            return;
+        };
+
+        let Ok(client) = get_pg_client(&self.pool).await else {
+            return;
+        };
+        // Si el hash cambió mientras tanto (cambio de contraseña) no se pisa.
+        match client
+            .execute(
+                "UPDATE users SET password = $1 WHERE id = $2 AND password = $3",
+                &[&hash, &id, &old_hash],
+            )
+            .await
+        {
+            Ok(_) => info!(user_id = id, "Hash de contraseña actualizado"),
+            Err(e) => error!(error = %e, "Error guardando el hash regenerado"),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthBackend for PasswordBackend {
+    fn name(&self) -> &'static str {
+        "password"
+    }
+
+    async fn authenticate(&self, email: &str, password: &str) -> Result<Option<i64>, ApiError> {
+        let email = normalize_email(email);
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                "SELECT id, password, status FROM users WHERE lower(email) = $1",
+                &[&email],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando el usuario por email", e))?;
+
+        let (id, hash, status): (i64, Option<String>, UserStatus) = match row {
+            Some(r) => (r.get("id"), r.get("password"), r.get("status")),
+            None => return Ok(None),
+        };
+        // Un usuario eliminado se trata como inexistente.
+        let hash = match hash {
+            Some(h) if status != UserStatus::Deleted => h,
+            _ => return Ok(None),
+        };
+
+        if !verify_password_pooled(password, &hash).await? {
+            error!("Fallo de verificación de password para el usuario: {}", id);
+            return Ok(None);
+        }
+
+        // Solo se revela que la cuenta está inactiva a quien conoce la contraseña.
+        if status == UserStatus::Inactive {
+            return Err(HttpError::forbbiden("Usuario inactivo"));
+        }
+
+        if needs_rehash(&hash) {
+            self.rehash(id, password, &hash).await;
+        }
+
+        Ok(Some(id))
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use colored::Colorize;
+use once_cell::sync::Lazy;
+use ring::digest::{SHA1_FOR_LEGACY_USE_ONLY, digest};
+use tracing::error;
+
+use crate::config::get_config;
+
+const FILTER_MAGIC: &[u8; 8] = b"RAUTHBF1";
+
+/// Lista de contraseñas filtradas configurada en `BREACHED_PASSWORDS_PATH`.
+/// Se carga al primer uso; un archivo inválido detiene el proceso igual que
+/// cualquier otro error de configuración.
+pub static BREACHED_PASSWORDS: Lazy<Option<BreachedPasswords>> = Lazy::new(|| {
+    let path = get_config()
+        .password_policy
+        .breached_passwords_path
+        .as_ref()?;
+    match BreachedPasswords::open(path) {
+        Ok(list) => Some(list),
+        Err(e) => {
+            eprintln!(
+                "{}",
+                format!("Error reading BREACHED_PASSWORDS_PATH ({}): {}", path, e).red()
+            );
+            exit(1);
+        }
+    }
+});
+
+/// Contraseñas conocidas por filtraciones, consultadas sin salir del servidor.
+pub enum BreachedPasswords {
+    /// Directorio con el formato de descarga de HIBP: un archivo
+    /// `<PREFIJO>.txt` por cada prefijo de 5 caracteres del SHA-1, con
+    /// líneas `<SUFIJO>:<CANTIDAD>`. Se lee un solo archivo por consulta.
+    PrefixDirectory(PathBuf),
+    /// Filtro de Bloom generado con `r-auth-api breach-filter`; entra en
+    /// memoria y admite una tasa baja de falsos positivos.
+    Filter(BloomFilter),
+}
+
+impl BreachedPasswords {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return Ok(BreachedPasswords::PrefixDirectory(path.to_path_buf()));
+        }
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        BloomFilter::read_from(&mut BufReader::new(file)).map(BreachedPasswords::Filter)
+    }
+
+    /// Si no se puede leer la lista se registra el error y se deja pasar la
+    /// contraseña: la comprobación no debe impedir crear usuarios. Puede leer
+    /// del disco, así que desde código async se llama con `spawn_blocking`.
+    pub fn contains(&self, password: &str) -> bool {
+        let hash = sha1_hex(password);
+        match self {
+            BreachedPasswords::Filter(filter) => filter.contains_hash(&hash),
+            BreachedPasswords::PrefixDirectory(dir) => {
+                let (prefix, suffix) = hash.split_at(5);
+                let path = dir.join(format!("{}.txt", prefix));
+                match std::fs::read_to_string(&path) {
+                    Ok(content) => content
+                        .lines()
+                        .filter_map(parse_line)
+                        .any(|(s, _)| s.eq_ignore_ascii_case(suffix)),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+                    Err(e) => {
+                        error!(error = %e, path = %path.display(), "Error leyendo la lista de contraseñas filtradas");
+                        false
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// SHA-1 en hexadecimal en mayúsculas, como lo publica HIBP.
+pub fn sha1_hex(password: &str) -> String {
+    digest(&SHA1_FOR_LEGACY_USE_ONLY, password.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+/// `<HASH>:<CANTIDAD>`; la cantidad es opcional.
+fn parse_line(line: &str) -> Option<(&str, u64)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    match line.split_once(':') {
+        Some((hash, count)) => Some((hash, count.trim().parse().unwrap_or(1))),
+        None => Some((line, 1)),
+    }
+}
+
+/// Filtro de Bloom sobre los SHA-1: el propio hash ya es uniforme, así que
+/// las `k` posiciones salen de dos mitades del digest (doble hashing).
+pub struct BloomFilter {
+    hashes: u32,
+    bits: Vec<u8>,
+}
+
+impl BloomFilter {
+    /// Filtro vacío dimensionado para `entries` elementos con la tasa de
+    /// falsos positivos indicada.
+    pub fn with_capacity(entries: u64, false_positive_rate: f64) -> Self {
+        let entries = entries.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-entries * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let bits = bits.max(64).div_ceil(8) * 8;
+        let hashes = ((bits as f64 / entries) * ln2).round().clamp(1.0, 30.0) as u32;
+        BloomFilter {
+            hashes,
+            bits: vec![0; (bits / 8) as usize],
+        }
+    }
+
+    /// Arma el filtro a partir de un volcado de HIBP: un archivo con líneas
+    /// `<SHA1>:<CANTIDAD>` o un directorio de archivos por prefijo. Se omiten
+    /// los hashes vistos menos de `min_count` veces.
+    pub fn build_from_dump(
+        path: impl AsRef<Path>,
+        false_positive_rate: f64,
+        min_count: u64,
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut entries = 0;
+        for_each_dump_hash(path, min_count, |_| entries += 1)?;
+
+        let mut filter = BloomFilter::with_capacity(entries, false_positive_rate);
+        let mut invalid = None;
+        for_each_dump_hash(path, min_count, |hash| {
+            if !filter.insert_hash(hash) && invalid.is_none() {
+                invalid = Some(hash.to_string());
+            }
+        })?;
+        match invalid {
+            Some(hash) => Err(format!("hash SHA-1 inválido: {}", hash)),
+            None => Ok(filter),
+        }
+    }
+
+    pub fn insert_hash(&mut self, hash: &str) -> bool {
+        let Some(positions) = self.positions(hash).map(Iterator::collect::<Vec<_>>) else {
+            return false;
+        };
+        for bit in positions {
+            self.bits[(bit / 8) as usize] |= 1 << (bit % 8);
+        }
+        true
+    }
+
+    pub fn contains_hash(&self, hash: &str) -> bool {
+        self.positions(hash).is_some_and(|mut positions| {
+            positions.all(|bit| self.bits[(bit / 8) as usize] & (1 << (bit % 8)) != 0)
+        })
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn positions(&self, hash: &str) -> Option<impl Iterator<Item = u64> + '_> {
+        if hash.len() != 40 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let first = u64::from_str_radix(&hash[..16], 16).ok()?;
+        let second = u64::from_str_radix(&hash[16..32], 16).ok()? | 1;
+        let bits = self.bits.len() as u64 * 8;
+        Some(
+            (0..self.hashes as u64).map(move |i| first.wrapping_add(i.wrapping_mul(second)) % bits),
+        )
+    }
+
+    /// `RAUTHBF1`, cantidad de funciones (u32 LE), largo en bytes (u64 LE) y
+    /// los bits.
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(FILTER_MAGIC)?;
+        writer.write_all(&self.hashes.to_le_bytes())?;
+        writer.write_all(&(self.bits.len() as u64).to_le_bytes())?;
+        writer.write_all(&self.bits)
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> Result<Self, String> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(|e| e.to_string())?;
+        if &magic != FILTER_MAGIC {
+            return Err("no es un filtro generado con breach-filter".to_string());
+        }
+        let mut hashes = [0u8; 4];
+        let mut length = [0u8; 8];
+        reader.read_exact(&mut hashes).map_err(|e| e.to_string())?;
+        reader.read_exact(&mut length).map_err(|e| e.to_string())?;
+        let hashes = u32::from_le_bytes(hashes);
+        let length = u64::from_le_bytes(length);
+        if hashes == 0 || length == 0 {
+            return Err("filtro vacío o dañado".to_string());
+        }
+
+        let mut bits = vec![];
+        reader
+            .take(length)
+            .read_to_end(&mut bits)
+            .map_err(|e| e.to_string())?;
+        if bits.len() as u64 != length {
+            return Err("filtro truncado".to_string());
+        }
+        Ok(BloomFilter { hashes, bits })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+}
+
+fn for_each_dump_hash(path: &Path, min_count: u64, mut f: impl FnMut(&str)) -> Result<(), String> {
+    let mut visit = |file: &Path, prefix: &str| -> Result<(), String> {
+        let reader = BufReader::new(File::open(file).map_err(|e| e.to_string())?);
+        for line in reader.lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            if let Some((hash, count)) = parse_line(&line)
+                && count >= min_count
+            {
+                f(&format!("{}{}", prefix, hash).to_uppercase());
+            }
+        }
+        Ok(())
+    };
+
+    if !path.is_dir() {
+        return visit(path, "");
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+        .collect();
+    files.sort();
+    for file in files {
+        let prefix = file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| s.len() == 5)
+            .ok_or_else(|| format!("nombre de archivo inesperado: {}", file.display()))?
+            .to_string();
+        visit(&file, &prefix)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use tokio::sync::Semaphore;
+use tracing::{error, warn};
+
+use crate::{
+    config::get_config,
+    utils::{ApiError, errors::HttpError},
+};
+
+/// Pool compartido por todos los hashes y verificaciones de contraseñas.
+pub static HASHING_POOL: Lazy<HashingPool> = Lazy::new(|| {
+    let config = &get_config().hashing_pool;
+    HashingPool::new(
+        config.concurrency as usize,
+        Duration::from_millis(config.queue_timeout_ms as u64),
+    )
+});
+
+/// Ejecuta trabajo de CPU (Argon2) en el pool de hilos bloqueantes de Tokio,
+/// con un máximo de trabajos simultáneos. Así una ráfaga de logins no ocupa
+/// los workers async que atienden el resto de las peticiones; si la espera en
+/// cola supera el timeout se responde 503 en lugar de acumular memoria.
+pub struct HashingPool {
+    semaphore: Arc<Semaphore>,
+    concurrency: usize,
+    queue_timeout: Duration,
+    counters: Arc<Counters>,
+}
+
+#[derive(Default)]
+struct Counters {
+    waiting: AtomicUsize,
+    running: AtomicUsize,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+    wait_micros: AtomicU64,
+    run_micros: AtomicU64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashingPoolMetrics {
+    pub concurrency: usize,
+    pub queue_depth: usize,
+    pub in_flight: usize,
+    pub completed: u64,
+    pub rejected: u64,
+    pub wait_seconds: f64,
+    pub run_seconds: f64,
+}
+
+/// Decrementa el contador al soltarse, aunque el futuro se cancele.
+struct Gauge<'a>(&'a AtomicUsize);
+
+impl<'a> Gauge<'a> {
+    fn enter(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Gauge(counter)
+    }
+}
+
+impl Drop for Gauge<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl HashingPool {
+    pub fn new(concurrency: usize, queue_timeout: Duration) -> Self {
+        let concurrency = concurrency.max(1);
+        HashingPool {
+            semaphore: Arc::new(Semaphore::new(concurrency)),
+            concurrency,
+            queue_timeout,
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    pub async fn run<T, F>(&self, job: F) -> Result<T, ApiError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.execute(job, Some(self.queue_timeout)).await
+    }
+
+    /// Como `run`, pero espera turno sin límite. Es para trabajos en lote
+    /// (importación) que ya acotan cuántos encolan a la vez y no deben fallar
+    /// por la carga de logins.
+    pub async fn run_queued<T, F>(&self, job: F) -> Result<T, ApiError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.execute(job, None).await
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    async fn execute<T, F>(&self, job: F, queue_timeout: Option<Duration>) -> Result<T, ApiError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let queued_at = Instant::now();
+        let permit = {
+            let _waiting = Gauge::enter(&self.counters.waiting);
+            let acquire = self.semaphore.clone().acquire_owned();
+            match queue_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, acquire).await.ok(),
+                None => Some(acquire.await),
+            }
+        };
+        let permit = match permit {
+            Some(Ok(permit)) => permit,
+            _ => {
+                self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    queue_depth = self.counters.waiting.load(Ordering::Relaxed),
+                    "Cola de hashing saturada"
+                );
+                return Err(HttpError::service_unavailable(
+                    "El servidor está ocupado, intente nuevamente en unos segundos",
+                ));
+            }
+        };
+        self.counters
+            .wait_micros
+            .fetch_add(queued_at.elapsed().as_micros() as u64, Ordering::Relaxed);
+
+        let counters = self.counters.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let _running = Gauge::enter(&counters.running);
+            let started_at = Instant::now();
+            let result = job();
+            counters
+                .run_micros
+                .fetch_add(started_at.elapsed().as_micros() as u64, Ordering::Relaxed);
+            counters.completed.fetch_add(1, Ordering::Relaxed);
+            result
+        })
+        .await;
+
+        result.map_err(|e| {
+            error!(error = %e, "Error en el trabajo de hashing");
+            HttpError::internal_server_error()
+        })
+    }
+
+    pub fn metrics(&self) -> HashingPoolMetrics {
+        let counters = &self.counters;
+        HashingPoolMetrics {
+            concurrency: self.concurrency,
+            queue_depth: counters.waiting.load(Ordering::Relaxed),
+            in_flight: counters.running.load(Ordering::Relaxed),
+            completed: counters.completed.load(Ordering::Relaxed),
+            rejected: counters.rejected.load(Ordering::Relaxed),
+            wait_seconds: counters.wait_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+            run_seconds: counters.run_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        }
+    }
+}
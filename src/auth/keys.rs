@@ -0,0 +1,75 @@
+//! Claves de los JWT de login: HS256 con `JWT_SECRET` o, si se configura
+//! `JWT_PRIVATE_KEY_PATH`, RS256 con la clave pública publicada como JWKS para
+//! que otros servicios verifiquen tokens sin conocer el secreto.
+
+use std::process::exit;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use colored::Colorize;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use once_cell::sync::Lazy;
+use ring::rsa::{KeyPair, PublicKeyComponents};
+use rustls::pki_types::PrivateKeyDer;
+use serde_json::{Value, json};
+
+use crate::config::get_config;
+
+pub struct SigningKeys {
+    pub algorithm: Algorithm,
+    pub key_id: Option<String>,
+    pub encoding: EncodingKey,
+    pub decoding: DecodingKey,
+    jwk: Option<Value>,
+}
+
+pub static SIGNING_KEYS: Lazy<SigningKeys> = Lazy::new(|| {
+    let config = &get_config().auth;
+    match &config.private_key_pem {
+        Some(pem) => rsa_keys(pem, &config.key_id).unwrap_or_else(|e| {
+            eprintln!("{}", format!("Invalid JWT private key: {}", e).red());
+            exit(1);
+        }),
+        None => SigningKeys {
+            algorithm: Algorithm::HS256,
+            key_id: None,
+            encoding: EncodingKey::from_secret(config.secret.as_ref()),
+            decoding: DecodingKey::from_secret(config.secret.as_ref()),
+            jwk: None,
+        },
+    }
+});
+
+/// JWKS con las claves públicas de firma; vacío si los tokens usan HS256.
+pub fn public_jwks() -> Value {
+    json!({ "keys": SIGNING_KEYS.jwk.iter().collect::<Vec<_>>() })
+}
+
+fn rsa_keys(pem: &str, key_id: &str) -> Result<SigningKeys, String> {
+    let der = rustls_pemfile::private_key(&mut pem.as_bytes())
+        .map_err(|e| e.to_string())?
+        .ok_or("no private key found in PEM")?;
+    let key_pair = match &der {
+        PrivateKeyDer::Pkcs1(key) => KeyPair::from_der(key.secret_pkcs1_der()),
+        PrivateKeyDer::Pkcs8(key) => KeyPair::from_pkcs8(key.secret_pkcs8_der()),
+        _ => return Err("only RSA keys are supported".to_string()),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let public: PublicKeyComponents<Vec<u8>> = key_pair.public().into();
+    let encoding = EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| e.to_string())?;
+
+    Ok(SigningKeys {
+        algorithm: Algorithm::RS256,
+        key_id: Some(key_id.to_string()),
+        encoding,
+        decoding: DecodingKey::from_rsa_raw_components(&public.n, &public.e),
+        jwk: Some(json!({
+            "kty": "RSA",
+            "use": "sig",
+            "alg": "RS256",
+            "kid": key_id,
+            "n": URL_SAFE_NO_PAD.encode(&public.n),
+            "e": URL_SAFE_NO_PAD.encode(&public.e),
+        })),
+    })
+}
@@ -1,72 +1,159 @@
+pub mod backends;
+mod breached;
+mod hashing_pool;
+mod keys;
+mod password;
+mod password_policy;
+mod session;
+mod tokens;
+
+pub use breached::*;
+pub use hashing_pool::*;
+pub use keys::*;
+pub use password::*;
+pub use password_policy::*;
+pub use r_auth_middleware::{
+    CSRF_COOKIE, CSRF_HEADER, SESSION_COOKIE, cookie_value, csrf_valid, request_token,
+};
+pub use session::*;
+pub use tokens::*;
+
 use crate::{
-    config::get_config,
     database::{
-        connection::GLOBAL_DB_POOL,
-        models::{claims::Claims, entities::user::User},
+        connection::{GLOBAL_DB_POOL, PgPool},
+        models::{
+            claims::{Claims, PASSWORD_CHANGE_SCOPE},
+            entities::{user::User, user_status::UserStatus},
+        },
     },
-    utils::errors::HttpError,
+    services::token_attributes,
+    utils::{ApiError, errors::HttpError, get_pg_client, map_db_error},
 };
-use argon2::{self, Config, Variant, Version};
 use axum::{
     Json,
     extract::FromRequestParts,
     http::{StatusCode, request::Parts},
 };
-use fancy_regex::Regex;
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
-use rand::{RngCore, rng};
+use jsonwebtoken::{Header, Validation, decode, encode};
 use tracing::error;
 
-pub fn hash_password(password: &str) -> Result<String, argon2::Error> {
-    let config = get_config();
-
-    let mut salt = [0u8; 16];
-    rng().fill_bytes(&mut salt);
-
-    let pwd_config = Config {
-        variant: Variant::Argon2i,
-        version: Version::Version13,
-        mem_cost: config.password.mem_cost,
-        time_cost: config.password.time_cost,
-        lanes: config.password.lanes,
-        secret: &[],
-        ad: &[],
-        hash_length: config.password.hash_length,
-    };
-
-    let hash = argon2::hash_encoded(password.as_bytes(), &salt, &pwd_config)?;
-    Ok(hash)
+pub fn generate_jwt(claims: Claims) -> Result<String, jsonwebtoken::errors::Error> {
+    let mut header = Header::new(SIGNING_KEYS.algorithm);
+    header.kid = SIGNING_KEYS.key_id.clone();
+    encode(&header, &claims, &SIGNING_KEYS.encoding)
 }
 
-pub fn verify_password(password: &str, hash: &str) -> bool {
-    match argon2::verify_encoded(&hash, password.as_bytes()) {
-        Ok(r) => r,
-        Err(e) => {
-            eprintln!("{}", e);
-            false
-        }
+pub const TOKEN_EXPIRATION_MINUTES: i64 = 60;
+pub const PASSWORD_CHANGE_TOKEN_MINUTES: i64 = 10;
+
+/// Emite el token de login del usuario. Incluye username, permisos y los
+/// atributos marcados para el token, para que otros servicios puedan
+/// autorizar sin consultar la base de datos.
+pub async fn generate_login_token(pool: &PgPool, user_id: i64) -> Result<String, ApiError> {
+    issue_token(pool, user_id, None, TOKEN_EXPIRATION_MINUTES).await
+}
+
+/// Token que se entrega al terminar cualquier login (contraseña, enlace
+/// mágico u OIDC): si un administrador pidió el cambio de contraseña o la
+/// contraseña local venció, solo se emite el token para cambiarla.
+/// `max_age_days = 0` desactiva el vencimiento.
+pub async fn issue_login_token(
+    pool: &PgPool,
+    user_id: i64,
+    max_age_days: u32,
+) -> Result<String, ApiError> {
+    if requires_password_change(pool, user_id, max_age_days).await? {
+        return generate_password_change_token(pool, user_id).await;
     }
+    generate_login_token(pool, user_id).await
 }
 
-pub fn generate_jwt(claims: Claims) -> Result<String, jsonwebtoken::errors::Error> {
-    let config = get_config();
+/// Los usuarios sin contraseña local no tienen nada que cambiar.
+async fn requires_password_change(
+    pool: &PgPool,
+    user_id: i64,
+    max_age_days: u32,
+) -> Result<bool, ApiError> {
+    let client = get_pg_client(pool).await?;
+    let row = client
+        .query_opt(
+            r#"
+            SELECT must_change_password
+                OR ($2 > 0 AND password_changed_at < now() - make_interval(days => $2))
+                AS required
+            FROM users
+            WHERE id = $1 AND password IS NOT NULL
+            "#,
+            &[&user_id, &(max_age_days as i32)],
+        )
+        .await
+        .map_err(|e| map_db_error("Error consultando la vigencia de la contraseña", e))?;
+    Ok(row
+        .and_then(|r| r.get::<_, Option<bool>>("required"))
+        .unwrap_or(false))
+}
 
-    let header = Header::new(Algorithm::HS256);
-    let encoding_key = EncodingKey::from_secret(config.auth.secret.as_ref());
-    encode(&header, &claims, &encoding_key)
+/// Token de vida corta que solo acepta `PUT /api/users/change-password`; se
+/// emite en el login cuando la contraseña venció.
+pub async fn generate_password_change_token(
+    pool: &PgPool,
+    user_id: i64,
+) -> Result<String, ApiError> {
+    issue_token(
+        pool,
+        user_id,
+        Some(PASSWORD_CHANGE_SCOPE),
+        PASSWORD_CHANGE_TOKEN_MINUTES,
+    )
+    .await
 }
 
-pub fn decode_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let config = get_config();
+async fn issue_token(
+    pool: &PgPool,
+    user_id: i64,
+    scope: Option<&str>,
+    expiration_minutes: i64,
+) -> Result<String, ApiError> {
+    let client = get_pg_client(pool).await?;
+    let row = client
+        .query_opt(
+            "SELECT username, permissions FROM users WHERE id = $1",
+            &[&user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error consultando el usuario del token", e))?
+        .ok_or_else(|| HttpError::not_found("Usuario no encontrado"))?;
+
+    let mut claims = Claims::new(user_id.to_string(), expiration_minutes);
+    claims.username = row.get("username");
+    claims.perms = row.get::<_, Option<i64>>("permissions").unwrap_or(0);
+    claims.scope = scope.map(str::to_string);
+    // Los tokens con scope no salen de r-auth: no necesitan los atributos.
+    if scope.is_none() {
+        let attrs = token_attributes(&client, user_id).await?;
+        claims.attrs = (!attrs.is_empty()).then_some(attrs);
+    }
+
+    generate_jwt(claims).map_err(|e| {
+        error!("Error generando JWT: {}", e);
+        HttpError::internal_server_error()
+    })
+}
 
-    let decoding_key = DecodingKey::from_secret(config.auth.secret.as_ref());
-    let validation = Validation::new(Algorithm::HS256);
-    let token_data = decode::<Claims>(token, &decoding_key, &validation)?;
+pub fn decode_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let validation = Validation::new(SIGNING_KEYS.algorithm);
+    let token_data = decode::<Claims>(token, &SIGNING_KEYS.decoding, &validation)?;
     Ok(token_data.claims)
 }
 
+/// Claims de un token de login completo. Los tokens limitados al cambio de
+/// contraseña se rechazan con 403.
 pub struct AuthenticatedClaims(pub Claims);
 
+/// Acepta además los tokens limitados al cambio de contraseña; solo debe
+/// usarse en esa ruta.
+pub struct PasswordChangeClaims(pub Claims);
+
 impl<S> FromRequestParts<S> for AuthenticatedClaims
 where
     S: Send + Sync + 'static,
@@ -74,90 +161,107 @@ where
     type Rejection = (StatusCode, Json<HttpError>);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let auth_header = parts
-            .headers
-            .get("Authorization")
-            .and_then(|h| h.to_str().ok())
-            .filter(|s| s.starts_with("Bearer "))
-            .map(|s| s.trim_start_matches("Bearer ").to_string())
-            .ok_or((
-                StatusCode::UNAUTHORIZED,
-                "Token de autorización faltante o inválido",
-            ))
-            .map_err(|_| HttpError::unauthorized("Token faltante o inválido"))?;
-
-        match decode_jwt(&auth_header) {
-            Ok(mut claims) => {
-                let pool = match GLOBAL_DB_POOL.get() {
-                    Some(p) => p,
-                    None => return Err(HttpError::internal_server_error()),
-                };
-                let client = pool
-                    .get()
-                    .await
-                    .map_err(|_| HttpError::internal_server_error())?;
-
-                let sql = r#"
-                    SELECT
-                        id,
-                        username,
-                        email,
-                        permissions,
-                        status,
-                        created_at,
-                        updated_at
-                    FROM users WHERE id = $1
-                "#;
-                let id: i64 = claims
-                    .user_id
-                    .parse()
-                    .map_err(|_| HttpError::bad_request("Id de usuario inválido"))?;
-                let row = match client.query_opt(sql, &[&id]).await {
-                    Ok(r) => match r {
-                        Some(r) => r,
-                        None => {
-                            return Err(HttpError::not_found("Usuario no encontrado"));
-                        }
-                    },
-                    Err(e) => {
-                        error!(error = %e, "Error al obtener el usuario");
-                        return Err(HttpError::internal_server_error());
-                    }
-                };
-                let user = match User::from_row(&row) {
-                    Ok(u) => u,
-                    Err(e) => {
-                        error!("Error al intentar crear el usuario: {}", e);
-                        return Err(HttpError::internal_server_error());
-                    }
-                };
-                let user = match user.status {
-                    1 => user,
-                    2 => return Err(HttpError::forbbiden("Usuario inactivo")),
-                    _ => return Err(HttpError::not_found("Usuario no encontrado")),
-                };
+        let claims = authenticate(parts).await?;
+        if claims.is_password_change_only() {
+            return Err(HttpError::password_change_required(
+                "La contraseña expiró: debe cambiarla antes de continuar",
+            ));
+        }
+        if claims.get_user().is_some_and(|u| u.must_change_password) {
+            return Err(HttpError::password_change_required(
+                "Debe cambiar la contraseña antes de continuar",
+            ));
+        }
+        Ok(AuthenticatedClaims(claims))
+    }
+}
 
-                claims.set_user(user);
+impl<S> FromRequestParts<S> for PasswordChangeClaims
+where
+    S: Send + Sync + 'static,
+{
+    type Rejection = (StatusCode, Json<HttpError>);
 
-                Ok(AuthenticatedClaims(claims))
-            }
-            Err(e) => {
-                error!("Error verificando el TOKEN: {}", e);
-                return Err(HttpError::unauthorized("Token inválido"));
-            }
-        }
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        authenticate(parts).await.map(PasswordChangeClaims)
     }
 }
 
-pub fn validate_password(password: &str) -> Result<bool, (StatusCode, Json<HttpError>)> {
-    let pattern = r"^(?=.*[a-z])(?=.*[A-Z])(?=.*\d)(?=.*[^A-Za-z\d]).+$";
-    let re = Regex::new(pattern).unwrap();
-    let is_valid = re.is_match(password).unwrap_or(false);
-    if !is_valid {
-        return Err(HttpError::bad_request(
-            "La contraseña debe tener al menos: 1 minúscula, 1 mayúscula, 1 número y 1 caracter especial",
-        ));
+/// Verifica el token de la petición y carga al usuario, que debe estar activo.
+async fn authenticate(parts: &Parts) -> Result<Claims, ApiError> {
+    if !csrf_valid(&parts.method, &parts.headers) {
+        return Err(HttpError::forbbiden("Token CSRF inválido"));
     }
 
-    Ok(is_valid)
+    let auth_header = request_token(&parts.headers)
+        .ok_or_else(|| HttpError::unauthorized("Token faltante o inválido"))?;
+
+    match decode_jwt(&auth_header) {
+        Ok(mut claims) => {
+            let pool = match GLOBAL_DB_POOL.get() {
+                Some(p) => p,
+                None => return Err(HttpError::internal_server_error()),
+            };
+            let client = pool
+                .get()
+                .await
+                .map_err(|_| HttpError::internal_server_error())?;
+
+            let sql = r#"
+                SELECT
+                    id,
+                    username,
+                    email,
+                    permissions,
+                    status,
+                    must_change_password,
+                    display_name,
+                    locale,
+                    timezone,
+                    avatar_url,
+                    phone_number,
+                    attributes,
+                    erasure_scheduled_at,
+                    created_at,
+                    updated_at
+                FROM users WHERE id = $1
+            "#;
+            let id: i64 = claims
+                .user_id
+                .parse()
+                .map_err(|_| HttpError::bad_request("Id de usuario inválido"))?;
+            let row = match client.query_opt(sql, &[&id]).await {
+                Ok(r) => match r {
+                    Some(r) => r,
+                    None => {
+                        return Err(HttpError::not_found("Usuario no encontrado"));
+                    }
+                },
+                Err(e) => {
+                    error!(error = %e, "Error al obtener el usuario");
+                    return Err(HttpError::internal_server_error());
+                }
+            };
+            let user = match User::from_row(&row) {
+                Ok(u) => u,
+                Err(e) => {
+                    error!("Error al intentar crear el usuario: {}", e);
+                    return Err(HttpError::internal_server_error());
+                }
+            };
+            match user.status {
+                UserStatus::Active => {}
+                UserStatus::Inactive => return Err(HttpError::forbbiden("Usuario inactivo")),
+                UserStatus::Deleted => return Err(HttpError::not_found("Usuario no encontrado")),
+            }
+
+            claims.set_user(user);
+
+            Ok(claims)
+        }
+        Err(e) => {
+            error!("Error verificando el TOKEN: {}", e);
+            Err(HttpError::unauthorized("Token inválido"))
+        }
+    }
 }
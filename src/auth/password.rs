@@ -0,0 +1,260 @@
+use argon2::{self, Config, Variant, Version};
+use base64::{Engine, engine::general_purpose::STANDARD_NO_PAD};
+use once_cell::sync::Lazy;
+use rand::{RngCore, rng};
+use regex::Regex;
+use tracing::error;
+
+use crate::{
+    auth::HASHING_POOL,
+    config::{PasswordHashingConfig, get_config},
+    utils::{ApiError, errors::HttpError},
+};
+
+static BCRYPT_HASH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\$2[aby]\$[0-3][0-9]\$[./A-Za-z0-9]{53}$").unwrap());
+
+/// Parámetros con los que se generó un hash Argon2, leídos de su formato
+/// codificado (`$argon2id$v=19$m=65536,t=4,p=4,keyid=p1$<salt>$<hash>`).
+/// `keyid` es el pepper usado como secreto de Argon2, si hubo uno.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashParams {
+    pub variant: Variant,
+    pub version: Version,
+    pub mem_cost: u32,
+    pub time_cost: u32,
+    pub lanes: u32,
+    pub hash_length: u32,
+    pub pepper_id: Option<String>,
+}
+
+impl HashParams {
+    /// Parámetros configurados para los hashes nuevos.
+    pub fn current() -> Self {
+        Self::from_config(&get_config().password)
+    }
+
+    pub fn from_config(config: &PasswordHashingConfig) -> Self {
+        HashParams {
+            variant: config.variant,
+            version: Version::Version13,
+            mem_cost: config.mem_cost,
+            time_cost: config.time_cost,
+            lanes: config.lanes,
+            hash_length: config.hash_length,
+            pepper_id: config.current_pepper.clone(),
+        }
+    }
+
+    pub fn parse(encoded: &str) -> Option<Self> {
+        let parts: Vec<&str> = encoded.split('$').collect();
+        // Los hashes de la versión 0x10 pueden no incluir el segmento `v=`.
+        let (variant, version, params, hash) = match parts.as_slice() {
+            [_, variant, version, params, _salt, hash] => {
+                (*variant, version.strip_prefix("v=")?, *params, *hash)
+            }
+            [_, variant, params, _salt, hash] => (*variant, "16", *params, *hash),
+            _ => return None,
+        };
+
+        let variant = Variant::from_str(variant).ok()?;
+        let version = Version::from_str(version).ok()?;
+
+        let (mut mem_cost, mut time_cost, mut lanes, mut pepper_id) = (None, None, None, None);
+        for param in params.split(',') {
+            let (key, value) = param.split_once('=')?;
+            match key {
+                "m" => mem_cost = Some(value.parse().ok()?),
+                "t" => time_cost = Some(value.parse().ok()?),
+                "p" => lanes = Some(value.parse().ok()?),
+                "keyid" => pepper_id = Some(value.to_string()),
+                _ => return None,
+            }
+        }
+
+        let hash_length = STANDARD_NO_PAD.decode(hash).ok()?.len() as u32;
+
+        Some(HashParams {
+            variant,
+            version,
+            mem_cost: mem_cost?,
+            time_cost: time_cost?,
+            lanes: lanes?,
+            hash_length,
+            pepper_id,
+        })
+    }
+
+    fn config<'a>(&self, secret: &'a [u8]) -> Config<'a> {
+        Config {
+            variant: self.variant,
+            version: self.version,
+            mem_cost: self.mem_cost,
+            time_cost: self.time_cost,
+            lanes: self.lanes,
+            secret,
+            ad: &[],
+            hash_length: self.hash_length,
+        }
+    }
+}
+
+impl std::fmt::Display for HashParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} v={} m={} t={} p={} len={}",
+            self.variant.as_lowercase_str(),
+            self.version.as_u32(),
+            self.mem_cost,
+            self.time_cost,
+            self.lanes,
+            self.hash_length
+        )?;
+        if let Some(pepper_id) = &self.pepper_id {
+            write!(f, " pepper={}", pepper_id)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, argon2::Error> {
+    hash_password_with(password, &get_config().password)
+}
+
+pub fn hash_password_with(
+    password: &str,
+    config: &PasswordHashingConfig,
+) -> Result<String, argon2::Error> {
+    let params = HashParams::from_config(config);
+    let secret = match &params.pepper_id {
+        Some(id) => config
+            .pepper(id)
+            .map(|p| p.secret.as_slice())
+            .unwrap_or(&[]),
+        None => &[],
+    };
+
+    let mut salt = [0u8; 16];
+    rng().fill_bytes(&mut salt);
+
+    let encoded = argon2::hash_encoded(password.as_bytes(), &salt, &params.config(secret))?;
+    Ok(match &params.pepper_id {
+        Some(id) => with_key_id(&encoded, id),
+        None => encoded,
+    })
+}
+
+/// Verifica con los parámetros y el pepper indicados en el propio hash, así
+/// que los hashes viejos siguen funcionando después de cambiar la
+/// configuración o rotar el pepper, mientras su pepper siga en el archivo.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    verify_password_with(password, hash, &get_config().password)
+}
+
+pub fn verify_password_with(password: &str, hash: &str, config: &PasswordHashingConfig) -> bool {
+    if is_bcrypt_hash(hash) {
+        return bcrypt::verify(password, hash).unwrap_or_else(|e| {
+            error!(error = %e, "Error verificando hash bcrypt");
+            false
+        });
+    }
+
+    let (encoded, secret) = match HashParams::parse(hash).and_then(|p| p.pepper_id) {
+        Some(id) => match config.pepper(&id) {
+            Some(pepper) => (without_key_id(hash), pepper.secret.as_slice()),
+            None => {
+                error!(pepper_id = %id, "Hash con un pepper que ya no está configurado");
+                return false;
+            }
+        },
+        None => (hash.to_string(), &[][..]),
+    };
+
+    match argon2::verify_encoded_ext(&encoded, password.as_bytes(), secret, &[]) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+/// Hashes bcrypt (`$2a$`, `$2b$`, `$2y$`) importados de otros sistemas. Se
+/// aceptan en el login y se reemplazan por Argon2 en el primero exitoso.
+pub fn is_bcrypt_hash(hash: &str) -> bool {
+    BCRYPT_HASH_RE.is_match(hash)
+}
+
+/// Hash que `verify_password_with` sabe verificar: bcrypt o Argon2 sin
+/// pepper o con uno configurado.
+pub fn is_supported_hash_with(hash: &str, config: &PasswordHashingConfig) -> bool {
+    is_bcrypt_hash(hash)
+        || HashParams::parse(hash).is_some_and(|params| {
+            params
+                .pepper_id
+                .is_none_or(|id| config.pepper(&id).is_some())
+        })
+}
+
+/// Un hash con otra variante, parámetros o pepper que los configurados debe
+/// regenerarse en el próximo login exitoso.
+pub fn needs_rehash(hash: &str) -> bool {
+    needs_rehash_with(hash, &get_config().password)
+}
+
+pub fn needs_rehash_with(hash: &str, config: &PasswordHashingConfig) -> bool {
+    HashParams::parse(hash).is_none_or(|params| params != HashParams::from_config(config))
+}
+
+/// `hash_password` en `HASHING_POOL`; es la versión a usar desde código async.
+pub async fn hash_password_pooled(password: &str) -> Result<String, ApiError> {
+    let password = password.to_string();
+    HASHING_POOL
+        .run(move || hash_password(&password))
+        .await?
+        .map_err(|e| {
+            error!("Error hasheando password: {}", e);
+            HttpError::internal_server_error()
+        })
+}
+
+/// `hash_password` en `HASHING_POOL` sin timeout de cola; para lotes.
+pub async fn hash_password_queued(password: &str) -> Result<String, ApiError> {
+    let password = password.to_string();
+    HASHING_POOL
+        .run_queued(move || hash_password(&password))
+        .await?
+        .map_err(|e| {
+            error!("Error hasheando password: {}", e);
+            HttpError::internal_server_error()
+        })
+}
+
+/// `verify_password` en `HASHING_POOL`.
+pub async fn verify_password_pooled(password: &str, hash: &str) -> Result<bool, ApiError> {
+    let (password, hash) = (password.to_string(), hash.to_string());
+    HASHING_POOL
+        .run(move || verify_password(&password, &hash))
+        .await
+}
+
+/// `rust-argon2` no conoce el parámetro `keyid` del formato PHC: se agrega
+/// después de codificar y se quita antes de verificar.
+fn with_key_id(encoded: &str, id: &str) -> String {
+    let mut parts: Vec<String> = encoded.split('$').map(str::to_string).collect();
+    let params = parts.len() - 3;
+    parts[params] = format!("{},keyid={}", parts[params], id);
+    parts.join("$")
+}
+
+fn without_key_id(encoded: &str) -> String {
+    let mut parts: Vec<String> = encoded.split('$').map(str::to_string).collect();
+    let params = parts.len() - 3;
+    parts[params] = parts[params]
+        .split(',')
+        .filter(|p| !p.starts_with("keyid="))
+        .collect::<Vec<_>>()
+        .join(",");
+    parts.join("$")
+}
@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use once_cell::sync::Lazy;
+use tracing::error;
+
+use crate::{
+    auth::BREACHED_PASSWORDS,
+    config::{CharacterClass, PasswordPolicyConfig, get_config},
+    utils::{ApiError, errors::HttpError},
+};
+
+/// Contraseñas y palabras más usadas, ordenadas por frecuencia. El puesto en
+/// la lista es la cantidad de intentos que necesita un atacante.
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "password",
+    "12345678",
+    "qwerty",
+    "123456789",
+    "12345",
+    "1234",
+    "111111",
+    "1234567",
+    "dragon",
+    "123123",
+    "baseball",
+    "abc123",
+    "football",
+    "monkey",
+    "letmein",
+    "shadow",
+    "master",
+    "666666",
+    "qwertyuiop",
+    "123321",
+    "mustang",
+    "1234567890",
+    "michael",
+    "654321",
+    "superman",
+    "1qaz2wsx",
+    "7777777",
+    "121212",
+    "000000",
+    "qazwsx",
+    "123qwe",
+    "killer",
+    "trustno1",
+    "jordan",
+    "jennifer",
+    "zxcvbnm",
+    "asdfgh",
+    "hunter",
+    "buster",
+    "soccer",
+    "harley",
+    "batman",
+    "andrew",
+    "tigger",
+    "sunshine",
+    "iloveyou",
+    "2000",
+    "charlie",
+    "robert",
+    "thomas",
+    "hockey",
+    "ranger",
+    "daniel",
+    "starwars",
+    "klaster",
+    "112233",
+    "george",
+    "computer",
+    "michelle",
+    "jessica",
+    "pepper",
+    "1111",
+    "zxcvbn",
+    "555555",
+    "11111111",
+    "131313",
+    "freedom",
+    "777777",
+    "pass",
+    "maggie",
+    "159753",
+    "aaaaaa",
+    "ginger",
+    "princess",
+    "joshua",
+    "cheese",
+    "amanda",
+    "summer",
+    "love",
+    "ashley",
+    "nicole",
+    "chelsea",
+    "biteme",
+    "matthew",
+    "access",
+    "yankees",
+    "987654321",
+    "dallas",
+    "austin",
+    "thunder",
+    "taylor",
+    "matrix",
+    "admin",
+    "welcome",
+    "secret",
+    "login",
+    "passw0rd",
+    "contraseña",
+    "contrasena",
+    "hola",
+    "teamo",
+    "qwerty123",
+    "asdf",
+    "qwer",
+    "zxcv",
+    "changeme",
+    "default",
+    "root",
+    "user",
+    "test",
+    "guest",
+];
+
+/// Largo en caracteres de la palabra más larga de `COMMON_PASSWORDS`.
+static LONGEST_COMMON: Lazy<usize> = Lazy::new(|| {
+    COMMON_PASSWORDS
+        .iter()
+        .map(|word| word.chars().count())
+        .max()
+        .unwrap_or(0)
+});
+
+static COMMON_RANKS: Lazy<HashMap<&'static str, usize>> = Lazy::new(|| {
+    COMMON_PASSWORDS
+        .iter()
+        .enumerate()
+        .map(|(rank, word)| (*word, rank + 1))
+        .collect()
+});
+
+pub const BREACHED_MESSAGE: &str =
+    "La contraseña aparece en filtraciones de datos conocidas; elija otra";
+
+/// Valida la contraseña contra la política configurada y, si hay una, contra
+/// la lista de contraseñas filtradas. `user_inputs` son datos del propio
+/// usuario (nombre de usuario, email) que no debe contener.
+pub async fn validate_password(password: &str, user_inputs: &[&str]) -> Result<(), ApiError> {
+    validate_password_with(password, user_inputs, &get_config().password_policy).await
+}
+
+pub async fn validate_password_with(
+    password: &str,
+    user_inputs: &[&str],
+    policy: &PasswordPolicyConfig,
+) -> Result<(), ApiError> {
+    let violations = password_violations(password, user_inputs, policy).await?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(HttpError::field_errors(
+        "password",
+        StatusCode::BAD_REQUEST,
+        violations,
+    ))
+}
+
+/// El puntaje de fortaleza y la consulta de filtradas (que lee archivos y
+/// carga la lista en el primer uso) gastan CPU, así que se hacen en el pool
+/// de tareas bloqueantes.
+async fn password_violations(
+    password: &str,
+    user_inputs: &[&str],
+    policy: &PasswordPolicyConfig,
+) -> Result<Vec<String>, ApiError> {
+    let password = password.to_string();
+    let user_inputs: Vec<String> = user_inputs.iter().map(|s| s.to_string()).collect();
+    let policy = policy.clone();
+    tokio::task::spawn_blocking(move || {
+        let user_inputs: Vec<&str> = user_inputs.iter().map(String::as_str).collect();
+        let mut violations = policy_violations(&password, &user_inputs, &policy);
+        if policy.breached_passwords_path.is_some()
+            && BREACHED_PASSWORDS
+                .as_ref()
+                .is_some_and(|list| list.contains(&password))
+        {
+            violations.push(BREACHED_MESSAGE.to_string());
+        }
+        violations
+    })
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Error validando la contraseña");
+        HttpError::internal_server_error()
+    })
+}
+
+/// Todas las reglas que la contraseña no cumple, en el orden de la política.
+pub fn policy_violations(
+    password: &str,
+    user_inputs: &[&str],
+    policy: &PasswordPolicyConfig,
+) -> Vec<String> {
+    let mut violations = vec![];
+    let length = password.chars().count() as u32;
+
+    if length < policy.min_length {
+        violations.push(format!(
+            "La contraseña debe tener al menos {} caracteres",
+            policy.min_length
+        ));
+    }
+    if length > policy.max_length {
+        violations.push(format!(
+            "La contraseña debe tener como máximo {} caracteres",
+            policy.max_length
+        ));
+    }
+
+    for class in &policy.required_classes {
+        if !password.chars().any(|c| class.matches(c)) {
+            violations.push(
+                match class {
+                    CharacterClass::Lowercase => "La contraseña debe tener al menos una minúscula",
+                    CharacterClass::Uppercase => "La contraseña debe tener al menos una mayúscula",
+                    CharacterClass::Digit => "La contraseña debe tener al menos un número",
+                    CharacterClass::Symbol => {
+                        "La contraseña debe tener al menos un caracter especial"
+                    }
+                }
+                .to_string(),
+            );
+        }
+    }
+
+    if policy.forbid_user_info {
+        let lowered = password.to_lowercase();
+        if user_terms(user_inputs)
+            .iter()
+            .any(|term| lowered.contains(term.as_str()))
+        {
+            violations.push(
+                "La contraseña no puede contener el nombre de usuario ni el email".to_string(),
+            );
+        }
+    }
+
+    if policy.max_repeated_chars > 0 && longest_run(password) > policy.max_repeated_chars {
+        violations.push(format!(
+            "La contraseña no puede repetir el mismo caracter más de {} veces seguidas",
+            policy.max_repeated_chars
+        ));
+    }
+
+    // Una contraseña demasiado larga ya se rechaza; no vale la pena puntuarla.
+    if policy.min_strength > 0
+        && length <= policy.max_length
+        && password_strength(password, user_inputs) < policy.min_strength
+    {
+        violations.push("La contraseña es demasiado fácil de adivinar".to_string());
+    }
+
+    violations
+}
+
+/// Puntaje de 0 (trivial) a 4 (muy difícil) al estilo de zxcvbn: la
+/// contraseña se parte en palabras comunes, datos del usuario, repeticiones,
+/// secuencias y caracteres sueltos, y se suman los intentos estimados para
+/// adivinar cada parte.
+pub fn password_strength(password: &str, user_inputs: &[&str]) -> u32 {
+    let guesses = estimate_guesses_log10(password, user_inputs);
+    match guesses {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4,
+    }
+}
+
+/// Términos prohibidos: el usuario, el email y la parte local del email, en
+/// minúsculas. Los de menos de 3 caracteres se ignoran.
+fn user_terms(user_inputs: &[&str]) -> Vec<String> {
+    let mut terms = vec![];
+    for input in user_inputs {
+        let input = input.trim().to_lowercase();
+        if let Some((local, _)) = input.split_once('@') {
+            terms.push(local.to_string());
+        }
+        terms.push(input);
+    }
+    terms.retain(|t| t.chars().count() >= 3);
+    terms
+}
+
+fn longest_run(password: &str) -> u32 {
+    let (mut longest, mut current, mut previous) = (0, 0, None);
+    for c in password.chars() {
+        current = if Some(c) == previous { current + 1 } else { 1 };
+        longest = longest.max(current);
+        previous = Some(c);
+    }
+    longest
+}
+
+/// Caracteres posibles para un caracter suelto, según las clases presentes.
+fn cardinality(chars: &[char]) -> f64 {
+    let mut cardinality = 0.0;
+    for (class, size) in [
+        (CharacterClass::Lowercase, 26.0),
+        (CharacterClass::Uppercase, 26.0),
+        (CharacterClass::Digit, 10.0),
+        (CharacterClass::Symbol, 33.0),
+    ] {
+        if chars.iter().any(|c| class.matches(*c)) {
+            cardinality += size;
+        }
+    }
+    f64::max(cardinality, 10.0)
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '@' | '4' => 'a',
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '$' | '5' => 's',
+        '7' | '+' => 't',
+        _ => c,
+    }
+}
+
+fn estimate_guesses_log10(password: &str, user_inputs: &[&str]) -> f64 {
+    let chars: Vec<char> = password.chars().collect();
+    let lowered: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    if lowered.len() != chars.len() {
+        // Minúsculas de más de un caracter: se estima solo por fuerza bruta.
+        return chars.len() as f64 * cardinality(&chars).log10();
+    }
+    let unleeted: Vec<char> = lowered.iter().map(|c| unleet(*c)).collect();
+
+    let user_terms = user_terms(user_inputs);
+    // Los datos del usuario son lo primero que prueba un atacante.
+    let rank = |word: &str| {
+        if user_terms.iter().any(|t| t == word) {
+            Some(1)
+        } else {
+            COMMON_RANKS.get(word).copied()
+        }
+    };
+
+    // Ninguna palabra es más larga que esto, así que no hace falta probar
+    // fragmentos mayores.
+    let longest_word = user_terms
+        .iter()
+        .map(|t| t.chars().count())
+        .fold(*LONGEST_COMMON, usize::max);
+
+    let brute_force = cardinality(&chars).log10();
+    let mut total = 0.0;
+    let mut i = 0;
+    while i < chars.len() {
+        let (length, guesses) =
+            dictionary_match(&chars, &lowered, &unleeted, i, longest_word, &rank)
+                .or_else(|| repeat_match(&chars, i, brute_force))
+                .or_else(|| sequence_match(&chars, i))
+                .unwrap_or((1, brute_force));
+        total += guesses;
+        i += length;
+    }
+    total
+}
+
+/// Palabra del diccionario más larga que empieza en `start`, tal cual o
+/// deshaciendo sustituciones tipo l33t (`p@ssw0rd`).
+fn dictionary_match(
+    chars: &[char],
+    lowered: &[char],
+    unleeted: &[char],
+    start: usize,
+    longest_word: usize,
+    rank: &dyn Fn(&str) -> Option<usize>,
+) -> Option<(usize, f64)> {
+    let last = chars.len().min(start + longest_word);
+    for end in (start + 3..=last).rev() {
+        let plain: String = lowered[start..end].iter().collect();
+        let substituted: String = unleeted[start..end].iter().collect();
+        let (rank, l33t) = match rank(&plain) {
+            Some(rank) => (rank, false),
+            None => match rank(&substituted) {
+                Some(rank) => (rank, true),
+                None => continue,
+            },
+        };
+
+        let mut guesses = (rank as f64).log10();
+        let segment = &chars[start..end];
+        if segment.iter().any(|c| c.is_uppercase()) {
+            let only_first = segment[1..].iter().all(|c| !c.is_uppercase());
+            guesses += if only_first {
+                2f64.log10()
+            } else {
+                4f64.log10()
+            };
+        }
+        if l33t {
+            guesses += 2f64.log10();
+        }
+        return Some((end - start, guesses));
+    }
+    None
+}
+
+/// Tres o más repeticiones del mismo caracter valen poco más que uno solo.
+fn repeat_match(chars: &[char], start: usize, brute_force: f64) -> Option<(usize, f64)> {
+    let length = chars[start..]
+        .iter()
+        .take_while(|c| **c == chars[start])
+        .count();
+    (length >= 3).then(|| (length, brute_force + (length as f64).log10()))
+}
+
+/// Tres o más caracteres consecutivos (`abc`, `987`).
+fn sequence_match(chars: &[char], start: usize) -> Option<(usize, f64)> {
+    let step = |a: char, b: char| b as i64 - a as i64;
+    let delta = step(*chars.get(start)?, *chars.get(start + 1)?);
+    if delta.abs() != 1 {
+        return None;
+    }
+    let mut end = start + 2;
+    while end < chars.len() && step(chars[end - 1], chars[end]) == delta {
+        end += 1;
+    }
+    if end - start < 3 {
+        return None;
+    }
+
+    let first = chars[start];
+    let base: f64 = if matches!(first, 'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9') {
+        4.0
+    } else if first.is_ascii_digit() {
+        10.0
+    } else {
+        26.0
+    };
+    Some((end - start, (base * (end - start) as f64).log10()))
+}
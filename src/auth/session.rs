@@ -0,0 +1,86 @@
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{AppendHeaders, IntoResponse, Response},
+};
+
+use crate::{
+    auth::{CSRF_COOKIE, SESSION_COOKIE, TOKEN_EXPIRATION_MINUTES, generate_opaque_token},
+    config::{SessionConfig, SessionMode},
+    database::models::dto::LoginResponse,
+};
+
+/// Respuesta de un login exitoso. En modo cookie el JWT no llega al
+/// JavaScript: se guarda en una cookie `HttpOnly` y el cuerpo solo lleva el
+/// token CSRF, que también queda en una cookie legible.
+pub fn login_response(token: String, config: &SessionConfig) -> Response {
+    match config.mode {
+        SessionMode::Token => (
+            StatusCode::OK,
+            Json(LoginResponse {
+                token: Some(token),
+                csrf_token: None,
+            }),
+        )
+            .into_response(),
+        SessionMode::Cookie => {
+            let csrf_token = generate_opaque_token();
+            let max_age = TOKEN_EXPIRATION_MINUTES * 60;
+            let cookies = AppendHeaders([
+                (
+                    header::SET_COOKIE,
+                    cookie(config, SESSION_COOKIE, &token, max_age, true),
+                ),
+                (
+                    header::SET_COOKIE,
+                    cookie(config, CSRF_COOKIE, &csrf_token, max_age, false),
+                ),
+            ]);
+            let body = LoginResponse {
+                token: None,
+                csrf_token: Some(csrf_token),
+            };
+            (StatusCode::OK, cookies, Json(body)).into_response()
+        }
+    }
+}
+
+/// Borra las cookies de sesión. En modo token no hay nada que borrar del
+/// lado del servidor, pero la respuesta es la misma.
+pub fn logout_response(config: &SessionConfig) -> Response {
+    let cookies = AppendHeaders([
+        (
+            header::SET_COOKIE,
+            cookie(config, SESSION_COOKIE, "", 0, true),
+        ),
+        (
+            header::SET_COOKIE,
+            cookie(config, CSRF_COOKIE, "", 0, false),
+        ),
+    ]);
+    (StatusCode::NO_CONTENT, cookies).into_response()
+}
+
+fn cookie(
+    config: &SessionConfig,
+    name: &str,
+    value: &str,
+    max_age: i64,
+    http_only: bool,
+) -> String {
+    let mut cookie = format!(
+        "{}={}; Path=/; Max-Age={}; SameSite={}",
+        name, value, max_age, config.cookie_same_site
+    );
+    if let Some(domain) = &config.cookie_domain {
+        cookie.push_str(&format!("; Domain={}", domain));
+    }
+    if http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    // Los navegadores descartan SameSite=None sin Secure.
+    if config.cookie_secure || config.cookie_same_site == "None" {
+        cookie.push_str("; Secure");
+    }
+    cookie
+}
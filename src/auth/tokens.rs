@@ -0,0 +1,16 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::{RngCore, rng};
+use sha2::{Digest, Sha256};
+
+/// Genera un token aleatorio de un solo uso, apto para incluir en una URL.
+pub fn generate_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Hash SHA-256 (hex) con el que se persisten los tokens opacos.
+pub fn hash_opaque_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    config::{CaptchaConfig, get_config},
+    utils::HTTP_CLIENT,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CaptchaError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Unexpected status: {0}")]
+    Status(u16),
+}
+
+/// Punto de extensión para verificar el CAPTCHA del registro público. En los
+/// tests se reemplaza por un stub.
+#[async_trait]
+pub trait CaptchaVerifier: Send + Sync {
+    /// `Ok(false)` si el token no es válido; `Err` solo si no se pudo verificar.
+    async fn verify(&self, token: &str) -> Result<bool, CaptchaError>;
+}
+
+/// Acepta cualquier token. Se usa cuando no hay un secreto configurado.
+pub struct NoCaptcha;
+
+#[async_trait]
+impl CaptchaVerifier for NoCaptcha {
+    async fn verify(&self, _token: &str) -> Result<bool, CaptchaError> {
+        Ok(true)
+    }
+}
+
+/// Verifica contra un endpoint `siteverify` (reCAPTCHA, hCaptcha, Turnstile):
+/// todos aceptan `secret` y `response` como formulario y devuelven `success`.
+pub struct SiteVerifyCaptcha {
+    verify_url: String,
+    secret: String,
+}
+
+impl SiteVerifyCaptcha {
+    pub fn new(config: &CaptchaConfig) -> Self {
+        SiteVerifyCaptcha {
+            verify_url: config.verify_url.clone(),
+            secret: config.secret.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+}
+
+#[async_trait]
+impl CaptchaVerifier for SiteVerifyCaptcha {
+    async fn verify(&self, token: &str) -> Result<bool, CaptchaError> {
+        if token.is_empty() {
+            return Ok(false);
+        }
+        let response = HTTP_CLIENT
+            .post(&self.verify_url)
+            .form(&[("secret", self.secret.as_str()), ("response", token)])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(CaptchaError::Status(response.status().as_u16()));
+        }
+        Ok(response.json::<SiteVerifyResponse>().await?.success)
+    }
+}
+
+pub fn captcha_verifier_from_config() -> Arc<dyn CaptchaVerifier> {
+    match &get_config().signup.captcha {
+        Some(config) => Arc::new(SiteVerifyCaptcha::new(config)),
+        None => Arc::new(NoCaptcha),
+    }
+}
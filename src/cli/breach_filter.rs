@@ -0,0 +1,52 @@
+use colored::Colorize;
+
+use crate::auth::BloomFilter;
+
+const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.001;
+
+/// `breach-filter <volcado> <salida> [tasa de falsos positivos] [cantidad mínima]`
+///
+/// El volcado puede ser el archivo `<SHA1>:<CANTIDAD>` completo de HIBP o el
+/// directorio por prefijos que genera su descargador.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let [input, output, rest @ ..] = args else {
+        return Err(
+            "Uso: r-auth-api breach-filter <volcado> <salida> [tasa_falsos_positivos] [cantidad_minima]"
+                .into(),
+        );
+    };
+    let false_positive_rate = match rest.first() {
+        Some(rate) => rate
+            .parse::<f64>()
+            .ok()
+            .filter(|r| *r > 0.0 && *r < 1.0)
+            .ok_or("La tasa de falsos positivos debe estar entre 0 y 1")?,
+        None => DEFAULT_FALSE_POSITIVE_RATE,
+    };
+    let min_count = match rest.get(1) {
+        Some(count) => count
+            .parse::<u64>()
+            .map_err(|_| "La cantidad mínima debe ser un número")?,
+        None => 1,
+    };
+
+    println!("Leyendo {}...", input.yellow());
+    let filter = BloomFilter::build_from_dump(input, false_positive_rate, min_count)?;
+    filter.save(output)?;
+
+    println!(
+        "{}",
+        format!(
+            "Filtro guardado en {} ({:.1} MiB, tasa de falsos positivos {})",
+            output,
+            filter.size_bytes() as f64 / (1024.0 * 1024.0),
+            false_positive_rate
+        )
+        .green()
+    );
+    println!(
+        "Configurar BREACHED_PASSWORDS_PATH={} y reiniciar el servidor.",
+        output
+    );
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use colored::Colorize;
+
+use super::api_error;
+use crate::{database::connection::PgPool, services::UsersService};
+
+/// `force-password-change <email> [--clear]`
+pub async fn run(pool: &PgPool, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (email, clear) = match args {
+        [email] => (email, false),
+        [email, flag] if flag == "--clear" => (email, true),
+        _ => return Err("Uso: r-auth-api force-password-change <email> [--clear]".into()),
+    };
+
+    let service = UsersService::new(pool);
+    let user = service.find_by_email(email).await.map_err(api_error)?;
+    service
+        .set_must_change_password(user.id, !clear)
+        .await
+        .map_err(api_error)?;
+
+    if clear {
+        println!(
+            "{}",
+            format!("{} ya no debe cambiar la contraseña.", user.email).green()
+        );
+    } else {
+        println!(
+            "{}",
+            format!(
+                "{} deberá cambiar la contraseña antes de usar la API.",
+                user.email
+            )
+            .green()
+        );
+    }
+    Ok(())
+}
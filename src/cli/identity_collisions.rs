@@ -0,0 +1,47 @@
+use colored::Colorize;
+
+use super::api_error;
+use crate::{database::connection::PgPool, services::UsersService};
+
+/// `identity-collisions`: lista las cuentas que comparten email o username
+/// una vez normalizados, para fusionarlas antes de crear los índices únicos.
+pub async fn run(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let collisions = UsersService::new(pool)
+        .identity_collisions()
+        .await
+        .map_err(api_error)?;
+
+    if collisions.is_empty() {
+        println!("{}", "No hay emails ni usernames repetidos.".green());
+        return Ok(());
+    }
+
+    for collision in &collisions {
+        println!(
+            "{} {}",
+            collision.field.yellow(),
+            collision.value.as_str().bold()
+        );
+        for user in &collision.users {
+            println!(
+                "  {:>8}  {:<30}  {:<40}  {:<8}  {}",
+                user.id,
+                user.username,
+                user.email,
+                user.status,
+                user.created_at.format("%Y-%m-%d %H:%M")
+            );
+        }
+    }
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{} colisiones. Conserve una cuenta por grupo (la primera es la más antigua), \
+             mueva o borre las demás y vuelva a aplicar init.sql.",
+            collisions.len()
+        )
+        .red()
+    );
+    Ok(())
+}
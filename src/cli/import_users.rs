@@ -0,0 +1,62 @@
+use colored::Colorize;
+
+use super::api_error;
+use crate::{
+    database::{connection::PgPool, models::dto::TransferFormat},
+    services::UserTransferService,
+};
+
+const USAGE: &str = "Uso: r-auth-api import-users <archivo.csv|archivo.ndjson> [--dry-run]";
+
+/// `import-users <archivo> [--dry-run]`: el formato sale de la extensión
+/// (`.csv`, o `.ndjson`/`.jsonl`).
+pub async fn run(pool: &PgPool, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, dry_run) = match args {
+        [path] => (path, false),
+        [path, flag] if flag == "--dry-run" => (path, true),
+        _ => return Err(USAGE.into()),
+    };
+    let format = match path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()) {
+        Some(ext) if ext == "csv" => TransferFormat::Csv,
+        Some(ext) if ext == "ndjson" || ext == "jsonl" => TransferFormat::Ndjson,
+        _ => return Err(USAGE.into()),
+    };
+
+    let body = std::fs::read(path)?;
+    let report = UserTransferService::new(pool)
+        .import(&body, format, dry_run)
+        .await
+        .map_err(api_error)?;
+
+    for error in &report.errors {
+        let messages: Vec<&str> = error
+            .errors
+            .values()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        println!(
+            "  línea {:>6}  {:<30}  {}",
+            error.line,
+            error.username.as_deref().unwrap_or("-"),
+            messages.join(", ").red()
+        );
+    }
+    let summary = if dry_run {
+        format!(
+            "{} filas: {} válidas, {} con errores (sin cambios).",
+            report.total, report.imported, report.failed
+        )
+    } else {
+        format!(
+            "{} filas: {} usuarios importados, {} con errores.",
+            report.total, report.imported, report.failed
+        )
+    };
+    if report.failed == 0 {
+        println!("{}", summary.green());
+    } else {
+        println!("{}", summary.yellow());
+    }
+    Ok(())
+}
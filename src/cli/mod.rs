@@ -0,0 +1,71 @@
+//! Comandos de mantenimiento: `r-auth-api <comando>`. Sin argumentos (o con
+//! `serve`) se levanta el servidor.
+
+mod breach_filter;
+mod force_password_change;
+mod identity_collisions;
+mod import_users;
+mod password_report;
+mod pepper;
+mod purge_users;
+
+use axum::Json;
+use colored::Colorize;
+use dotenv::dotenv;
+
+use crate::{
+    config,
+    database::connection::{PgPool, create_pool},
+    utils::ApiError,
+};
+
+const USAGE: &str = "Uso: r-auth-api [comando]
+
+Comandos:
+  serve              Levanta el servidor HTTP (por defecto)
+  password-report    Cuenta los usuarios con hashes de contraseña desactualizados
+  pepper-rotate      Agrega un pepper nuevo; los hashes se regeneran en el próximo login
+  breach-filter      Genera el filtro de contraseñas filtradas a partir de un volcado de HIBP
+  force-password-change <email> [--clear]
+                     Exige (o deja de exigir) que el usuario cambie la contraseña
+  purge-users [dias] Ejecuta las bajas vencidas y borra los usuarios eliminados hace más de N días
+  identity-collisions
+                     Lista las cuentas con el mismo email o username sin distinguir mayúsculas
+  import-users <archivo.csv|archivo.ndjson> [--dry-run]
+                     Crea usuarios en lote; con --dry-run solo valida y reporta los errores
+  help               Muestra esta ayuda";
+
+pub async fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let command = args.first().map(String::as_str).unwrap_or("help");
+    match command {
+        "password-report" => password_report::run(&connect().await?).await,
+        "pepper-rotate" => pepper::rotate(),
+        "breach-filter" => breach_filter::run(&args[1..]),
+        "purge-users" => purge_users::run(&connect().await?, &args[1..]).await,
+        "identity-collisions" => identity_collisions::run(&connect().await?).await,
+        "import-users" => import_users::run(&connect().await?, &args[1..]).await,
+        "force-password-change" => force_password_change::run(&connect().await?, &args[1..]).await,
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => {
+            eprintln!("{}", USAGE);
+            Err(format!("Comando desconocido: {}", other).into())
+        }
+    }
+}
+
+async fn connect() -> Result<PgPool, Box<dyn std::error::Error>> {
+    dotenv().ok();
+    config::init_config()?;
+    let pool = create_pool(&config::get_config().db.database_url).await?;
+    println!("{}", "Conectado a la base de datos.".green());
+    Ok(pool)
+}
+
+/// Los servicios devuelven `ApiError`; en la terminal solo interesan los mensajes.
+fn api_error((status, Json(error)): ApiError) -> Box<dyn std::error::Error> {
+    let messages: Vec<String> = error.errors.into_values().flatten().collect();
+    format!("{}: {}", status, messages.join(", ")).into()
+}
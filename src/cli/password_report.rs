@@ -0,0 +1,34 @@
+use colored::Colorize;
+
+use super::api_error;
+use crate::{database::connection::PgPool, services::UsersService};
+
+pub async fn run(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let report = UsersService::new(pool)
+        .password_hash_report()
+        .await
+        .map_err(api_error)?;
+
+    println!("Parámetros actuales: {}", report.current_params.yellow());
+    println!();
+    for group in &report.groups {
+        let params = group.params.as_deref().unwrap_or("formato desconocido");
+        let line = format!("{:>8}  {}", group.users, params);
+        if group.current {
+            println!("{}", line.green());
+        } else {
+            println!("{}", line.red());
+        }
+    }
+    println!();
+    println!(
+        "Total: {}  Actualizados: {}  Pendientes: {}",
+        report.total,
+        report.current.to_string().green(),
+        report.outdated.to_string().red()
+    );
+    if report.outdated > 0 {
+        println!("Los hashes pendientes se regeneran en el próximo login de cada usuario.");
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::{fs::OpenOptions, io::Write};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use colored::Colorize;
+use rand::{RngCore, rng};
+
+use crate::config::{Pepper, parse_peppers, pepper_file_path};
+
+/// Agrega un pepper nuevo al archivo. Como el pepper actual es el último del
+/// archivo (salvo que `PASSWORD_PEPPER_ID` diga otro), al reiniciar el
+/// servidor los hashes se regeneran con él en el próximo login de cada
+/// usuario. Los peppers anteriores se conservan para poder verificar.
+pub fn rotate() -> Result<(), Box<dyn std::error::Error>> {
+    let path = pepper_file_path().ok_or("PASSWORD_PEPPER_FILE no está configurada")?;
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(content) => parse_peppers(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e.into()),
+    };
+
+    let id = next_pepper_id(&existing);
+    let mut secret = [0u8; 32];
+    rng().fill_bytes(&mut secret);
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    writeln!(file, "{}={}", id, STANDARD.encode(secret))?;
+
+    println!(
+        "{}",
+        format!("Pepper {} agregado a {}", id.yellow(), path).green()
+    );
+    if std::env::var("PASSWORD_PEPPER_ID").is_ok_and(|v| !v.is_empty()) {
+        println!(
+            "PASSWORD_PEPPER_ID está fijado: cambiarlo a {} para usarlo.",
+            id
+        );
+    }
+    println!(
+        "Reiniciar el servidor para que los hashes se regeneren en el próximo login de cada usuario."
+    );
+    println!(
+        "Un pepper anterior se puede quitar del archivo cuando `password-report` ya no lo muestre."
+    );
+    Ok(())
+}
+
+fn next_pepper_id(existing: &[Pepper]) -> String {
+    let last = existing
+        .iter()
+        .filter_map(|p| p.id.strip_prefix('p')?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("p{}", last + 1)
+}
@@ -0,0 +1,47 @@
+use colored::Colorize;
+
+use super::api_error;
+use crate::{
+    config::get_config,
+    database::connection::PgPool,
+    services::{PrivacyService, UsersService},
+};
+
+/// `purge-users [dias]`; sin argumento usa `USER_PURGE_AFTER_DAYS`. Antes
+/// ejecuta las bajas cuyo período de gracia terminó. Con 0 días no se borra
+/// a nadie, igual que en el job.
+pub async fn run(pool: &PgPool, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let days = match args.first() {
+        Some(days) => days
+            .parse()
+            .map_err(|_| format!("Cantidad de días inválida: {}", days))?,
+        None => get_config().user_purge.after_days,
+    };
+
+    let erased = PrivacyService::new(pool)
+        .erase_due()
+        .await
+        .map_err(api_error)?;
+    println!(
+        "{}",
+        format!("{} usuarios anonimizados por bajas vencidas.", erased).green()
+    );
+
+    if days == 0 {
+        println!("{}", "Borrado definitivo deshabilitado (0 días).".yellow());
+        return Ok(());
+    }
+    let purged = UsersService::new(pool)
+        .purge_deleted(days)
+        .await
+        .map_err(api_error)?;
+    println!(
+        "{}",
+        format!(
+            "{} usuarios eliminados hace más de {} días fueron borrados.",
+            purged, days
+        )
+        .green()
+    );
+    Ok(())
+}
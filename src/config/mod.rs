@@ -1,34 +1,311 @@
+use argon2::Variant;
+use base64::{Engine, engine::general_purpose::STANDARD};
 use colored::Colorize;
+use jsonwebtoken::Algorithm;
 use once_cell::sync::OnceCell;
 use std::process::exit;
 
+use crate::utils::{Permissions, parse_permission_names};
+
 const PASSWORD_HASH_MEMORY_COST: &str = "PASSWORD_HASH_MEMORY_COST";
 const PASSWORD_HASH_TIME_COST: &str = "PASSWORD_HASH_TIME_COST";
 const PASSWORD_HASH_LANES: &str = "PASSWORD_HASH_LANES";
 const PASSWORD_HASH_LENGTH: &str = "PASSWORD_HASH_LENGTH";
+const PASSWORD_HASH_VARIANT: &str = "PASSWORD_HASH_VARIANT";
+const PASSWORD_PEPPER_FILE: &str = "PASSWORD_PEPPER_FILE";
+const PASSWORD_PEPPER_ID: &str = "PASSWORD_PEPPER_ID";
+const PASSWORD_HASH_CONCURRENCY: &str = "PASSWORD_HASH_CONCURRENCY";
+const PASSWORD_HASH_QUEUE_TIMEOUT_MS: &str = "PASSWORD_HASH_QUEUE_TIMEOUT_MS";
+const PASSWORD_MIN_LENGTH: &str = "PASSWORD_MIN_LENGTH";
+const PASSWORD_MAX_LENGTH: &str = "PASSWORD_MAX_LENGTH";
+const PASSWORD_REQUIRED_CLASSES: &str = "PASSWORD_REQUIRED_CLASSES";
+const PASSWORD_FORBID_USER_INFO: &str = "PASSWORD_FORBID_USER_INFO";
+const PASSWORD_MAX_REPEATED_CHARS: &str = "PASSWORD_MAX_REPEATED_CHARS";
+const PASSWORD_MIN_STRENGTH: &str = "PASSWORD_MIN_STRENGTH";
+const BREACHED_PASSWORDS_PATH: &str = "BREACHED_PASSWORDS_PATH";
+const PASSWORD_HISTORY_SIZE: &str = "PASSWORD_HISTORY_SIZE";
+const PASSWORD_MAX_AGE_DAYS: &str = "PASSWORD_MAX_AGE_DAYS";
 const JWT_SECRET: &str = "JWT_SECRET";
+const JWT_PRIVATE_KEY_PATH: &str = "JWT_PRIVATE_KEY_PATH";
+const JWT_KEY_ID: &str = "JWT_KEY_ID";
 const DATABASE_URL: &str = "DATABASE_URL";
 const RUST_ENVIRONMENT: &str = "RUST_ENVIRONMENT";
+const MAILER: &str = "MAILER";
+const MAILER_FROM: &str = "MAILER_FROM";
+const MAILER_FILE_PATH: &str = "MAILER_FILE_PATH";
+const MAGIC_LINK_URL: &str = "MAGIC_LINK_URL";
+const MAGIC_LINK_TTL_MINUTES: &str = "MAGIC_LINK_TTL_MINUTES";
+const MAGIC_LINK_RATE_LIMIT: &str = "MAGIC_LINK_RATE_LIMIT";
+const MAGIC_LINK_RATE_LIMIT_WINDOW_SECONDS: &str = "MAGIC_LINK_RATE_LIMIT_WINDOW_SECONDS";
+const INVITATION_URL: &str = "INVITATION_URL";
+const INVITATION_TTL_HOURS: &str = "INVITATION_TTL_HOURS";
+const OIDC_PROVIDERS: &str = "OIDC_PROVIDERS";
+const AUTH_BACKENDS: &str = "AUTH_BACKENDS";
+const LDAP_URL: &str = "LDAP_URL";
+const LDAP_BIND_DN: &str = "LDAP_BIND_DN";
+const LDAP_BIND_PASSWORD: &str = "LDAP_BIND_PASSWORD";
+const LDAP_SEARCH_BASE: &str = "LDAP_SEARCH_BASE";
+const LDAP_USER_FILTER: &str = "LDAP_USER_FILTER";
+const LDAP_USERNAME_ATTRIBUTE: &str = "LDAP_USERNAME_ATTRIBUTE";
+const LDAP_EMAIL_ATTRIBUTE: &str = "LDAP_EMAIL_ATTRIBUTE";
+const LDAP_GROUP_ATTRIBUTE: &str = "LDAP_GROUP_ATTRIBUTE";
+const LDAP_GROUP_SEARCH_BASE: &str = "LDAP_GROUP_SEARCH_BASE";
+const LDAP_GROUP_FILTER: &str = "LDAP_GROUP_FILTER";
+const LDAP_GROUP_PERMISSIONS: &str = "LDAP_GROUP_PERMISSIONS";
+const LDAP_LINK_BY_EMAIL: &str = "LDAP_LINK_BY_EMAIL";
+const SCIM_BEARER_TOKEN: &str = "SCIM_BEARER_TOKEN";
+const SESSION_MODE: &str = "SESSION_MODE";
+const SESSION_COOKIE_SECURE: &str = "SESSION_COOKIE_SECURE";
+const SESSION_COOKIE_SAME_SITE: &str = "SESSION_COOKIE_SAME_SITE";
+const SESSION_COOKIE_DOMAIN: &str = "SESSION_COOKIE_DOMAIN";
+const USER_PURGE_AFTER_DAYS: &str = "USER_PURGE_AFTER_DAYS";
+const USER_PURGE_INTERVAL_MINUTES: &str = "USER_PURGE_INTERVAL_MINUTES";
+const USER_ERASURE_GRACE_DAYS: &str = "USER_ERASURE_GRACE_DAYS";
+const SIGNUP_MODE: &str = "SIGNUP_MODE";
+const SIGNUP_ALLOWED_DOMAINS: &str = "SIGNUP_ALLOWED_DOMAINS";
+const SIGNUP_USERNAME_BLOCKLIST: &str = "SIGNUP_USERNAME_BLOCKLIST";
+const CAPTCHA_VERIFY_URL: &str = "CAPTCHA_VERIFY_URL";
+const CAPTCHA_SECRET: &str = "CAPTCHA_SECRET";
+
+const DEFAULT_USERNAME_BLOCKLIST: &str =
+    "admin,administrator,root,system,support,security,postmaster,webmaster,hostmaster,abuse";
+
+/// Secreto de servidor que se pasa a Argon2 como `secret`. El id queda en
+/// el hash (`keyid=`) para poder verificar con versiones anteriores.
+#[derive(Clone)]
+pub struct Pepper {
+    pub id: String,
+    pub secret: Vec<u8>,
+}
+
+impl std::fmt::Debug for Pepper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pepper").field("id", &self.id).finish()
+    }
+}
 
 pub struct PasswordHashingConfig {
+    pub variant: Variant,
     pub hash_length: u32,
     pub mem_cost: u32,
     pub time_cost: u32,
     pub lanes: u32,
+    pub peppers: Vec<Pepper>,
+    /// Pepper para los hashes nuevos; `None` si no hay archivo de peppers.
+    pub current_pepper: Option<String>,
+}
+
+impl PasswordHashingConfig {
+    pub fn pepper(&self, id: &str) -> Option<&Pepper> {
+        self.peppers.iter().find(|p| p.id == id)
+    }
+}
+
+/// Límite de hashes Argon2 simultáneos. Cada uno ocupa `mem_cost` KiB y un
+/// hilo durante toda la operación.
+pub struct HashingPoolConfig {
+    pub concurrency: u32,
+    pub queue_timeout_ms: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+}
+
+impl CharacterClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CharacterClass::Lowercase => "lower",
+            CharacterClass::Uppercase => "upper",
+            CharacterClass::Digit => "digit",
+            CharacterClass::Symbol => "symbol",
+        }
+    }
+
+    pub fn matches(&self, c: char) -> bool {
+        match self {
+            CharacterClass::Lowercase => c.is_lowercase(),
+            CharacterClass::Uppercase => c.is_uppercase(),
+            CharacterClass::Digit => c.is_ascii_digit(),
+            CharacterClass::Symbol => !c.is_alphanumeric(),
+        }
+    }
+}
+
+/// Reglas que deben cumplir las contraseñas nuevas.
+#[derive(Debug, Clone)]
+pub struct PasswordPolicyConfig {
+    pub min_length: u32,
+    pub max_length: u32,
+    pub required_classes: Vec<CharacterClass>,
+    /// Rechaza contraseñas que contienen el nombre de usuario o el email.
+    pub forbid_user_info: bool,
+    /// Máximo de caracteres iguales consecutivos; 0 desactiva la regla.
+    pub max_repeated_chars: u32,
+    /// Puntaje mínimo de `password_strength`, de 0 a 4; 0 desactiva la regla.
+    pub min_strength: u32,
+    /// Directorio por prefijos de HIBP o filtro generado con `breach-filter`.
+    pub breached_passwords_path: Option<String>,
+    /// Contraseñas anteriores que no se pueden reutilizar, además de la actual.
+    pub history_size: u32,
+    /// Días de vigencia de una contraseña; 0 desactiva el vencimiento.
+    pub max_age_days: u32,
 }
 
 pub struct AuthConfig {
     pub secret: String,
+    /// Clave RSA en PEM; si está, los tokens se firman con RS256 en lugar de HS256.
+    pub private_key_pem: Option<String>,
+    pub key_id: String,
 }
 
 pub struct DbConfig {
     pub database_url: String,
 }
 
+pub enum MailerKind {
+    Stdout,
+    File(String),
+}
+
+pub struct MailerConfig {
+    pub kind: MailerKind,
+    pub from: String,
+}
+
+pub struct MagicLinkConfig {
+    pub url: String,
+    pub ttl_minutes: u32,
+    pub rate_limit: u32,
+    pub rate_limit_window_seconds: u32,
+}
+
+pub struct InvitationConfig {
+    /// Página del frontend que recibe `?token=` y llama a
+    /// `POST /api/users/invitations/accept`.
+    pub url: String,
+    pub ttl_hours: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct OidcProviderConfig {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub scopes: String,
+    /// Algoritmos aceptados para el id_token. Vacío: los asimétricos que
+    /// anuncie el discovery. HS* solo se aceptan si se listan acá.
+    pub algorithms: Vec<Algorithm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthBackendKind {
+    Password,
+    Ldap,
+}
+
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    pub url: String,
+    pub bind_dn: String,
+    pub bind_password: String,
+    pub search_base: String,
+    /// Filtro de búsqueda del usuario; `{email}` se reemplaza por el email escapado.
+    pub user_filter: String,
+    pub username_attribute: String,
+    pub email_attribute: String,
+    pub group_attribute: String,
+    /// Si se define, los grupos se buscan aquí con `group_filter` en lugar de
+    /// leerse del atributo `group_attribute` del usuario.
+    pub group_search_base: Option<String>,
+    /// Filtro de grupos; `{dn}` se reemplaza por el DN escapado del usuario.
+    pub group_filter: String,
+    pub group_permissions: Vec<(String, Permissions)>,
+    /// Vincula una entrada nueva del directorio con la cuenta local activa que
+    /// tenga el mismo email. Desactivado por defecto: el directorio pasaría a
+    /// decidir quién entra a cuentas creadas fuera de él.
+    pub link_by_email: bool,
+}
+
+pub struct UserPurgeConfig {
+    /// Días que un usuario eliminado se puede restaurar antes de borrarse
+    /// definitivamente; 0 deshabilita el purgado automático.
+    pub after_days: u32,
+    pub interval_minutes: u32,
+    /// Días entre que el usuario pide la baja y se anonimizan sus datos;
+    /// mientras tanto puede cancelarla. Con 0 se anonimiza en el momento.
+    pub erasure_grace_days: u32,
+}
+
+pub struct ScimConfig {
+    /// Token dedicado al aprovisionamiento SCIM; sin él la API SCIM queda deshabilitada.
+    pub bearer_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionMode {
+    /// El JWT se devuelve en el cuerpo del login.
+    Token,
+    /// El JWT viaja en una cookie `HttpOnly`; las rutas que modifican estado
+    /// exigen además el token CSRF.
+    Cookie,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    pub mode: SessionMode,
+    pub cookie_secure: bool,
+    pub cookie_same_site: String,
+    pub cookie_domain: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignupMode {
+    /// Solo se crean usuarios con `CREATE_USERS` o por invitación.
+    Disabled,
+    Open,
+    /// Solo emails de los dominios listados (y sus subdominios).
+    Domains(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptchaConfig {
+    /// Endpoint `siteverify` compatible con reCAPTCHA, hCaptcha y Turnstile.
+    pub verify_url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignupConfig {
+    pub mode: SignupMode,
+    /// Usernames reservados, en minúsculas.
+    pub username_blocklist: Vec<String>,
+    /// Sin secreto configurado no se exige CAPTCHA.
+    pub captcha: Option<CaptchaConfig>,
+}
+
 pub struct AppConfig {
     pub password: PasswordHashingConfig,
+    pub hashing_pool: HashingPoolConfig,
+    pub password_policy: PasswordPolicyConfig,
     pub auth: AuthConfig,
     pub db: DbConfig,
+    pub mailer: MailerConfig,
+    pub magic_link: MagicLinkConfig,
+    pub invitation: InvitationConfig,
+    pub oidc_providers: Vec<OidcProviderConfig>,
+    pub auth_backends: Vec<AuthBackendKind>,
+    pub ldap: Option<LdapConfig>,
+    pub scim: ScimConfig,
+    pub user_purge: UserPurgeConfig,
+    pub session: SessionConfig,
+    pub signup: SignupConfig,
     pub environment: Environment,
 }
 
@@ -45,22 +322,72 @@ pub fn init_config() -> Result<(), Box<dyn std::error::Error>> {
     let time_cost = get_env_number(PASSWORD_HASH_TIME_COST);
     let lanes = get_env_number(PASSWORD_HASH_LANES);
     let hash_length = get_env_number(PASSWORD_HASH_LENGTH);
+    let peppers = get_peppers();
     let jwt_secret = get_env(JWT_SECRET);
     let database_url = get_env(DATABASE_URL);
     let environment = match get_env(RUST_ENVIRONMENT).as_str() {
         "production" => Environment::Production,
         _ => Environment::Development,
     };
+    let auth_backends = get_auth_backends();
+    let ldap = if auth_backends.contains(&AuthBackendKind::Ldap) {
+        Some(get_ldap_config())
+    } else {
+        None
+    };
+    let mailer_kind = match get_env_or(MAILER, "stdout").as_str() {
+        "file" => MailerKind::File(get_env(MAILER_FILE_PATH)),
+        _ => MailerKind::Stdout,
+    };
 
     let config = AppConfig {
         password: PasswordHashingConfig {
+            variant: get_password_hash_variant(),
             hash_length,
             mem_cost,
             time_cost,
             lanes,
+            current_pepper: get_current_pepper(&peppers),
+            peppers,
+        },
+        hashing_pool: HashingPoolConfig {
+            concurrency: get_env_number_or(PASSWORD_HASH_CONCURRENCY, default_concurrency()),
+            queue_timeout_ms: get_env_number_or(PASSWORD_HASH_QUEUE_TIMEOUT_MS, 5000),
+        },
+        password_policy: get_password_policy(),
+        auth: AuthConfig {
+            secret: jwt_secret,
+            private_key_pem: get_private_key_pem(),
+            key_id: get_env_or(JWT_KEY_ID, "r-auth"),
         },
-        auth: AuthConfig { secret: jwt_secret },
         db: DbConfig { database_url },
+        mailer: MailerConfig {
+            kind: mailer_kind,
+            from: get_env_or(MAILER_FROM, "no-reply@r-auth.local"),
+        },
+        magic_link: MagicLinkConfig {
+            url: get_env_or(MAGIC_LINK_URL, "http://localhost:3032/magic-link"),
+            ttl_minutes: get_env_number_or(MAGIC_LINK_TTL_MINUTES, 15),
+            rate_limit: get_env_number_or(MAGIC_LINK_RATE_LIMIT, 3),
+            rate_limit_window_seconds: get_env_number_or(MAGIC_LINK_RATE_LIMIT_WINDOW_SECONDS, 900),
+        },
+        invitation: InvitationConfig {
+            url: get_env_or(INVITATION_URL, "http://localhost:3032/invitations/accept"),
+            ttl_hours: get_env_number_or(INVITATION_TTL_HOURS, 72).max(1),
+        },
+        oidc_providers: get_oidc_providers(),
+        auth_backends,
+        ldap,
+        scim: ScimConfig {
+            bearer_token: Some(get_env_or(SCIM_BEARER_TOKEN, "")).filter(|t| !t.is_empty()),
+        },
+        user_purge: UserPurgeConfig {
+            after_days: get_env_number_or(USER_PURGE_AFTER_DAYS, 30),
+            interval_minutes: get_env_number_or(USER_PURGE_INTERVAL_MINUTES, 60).max(1),
+            erasure_grace_days: get_env_number_or(USER_ERASURE_GRACE_DAYS, 14),
+        },
+        session: get_session_config(),
+        signup: get_signup_config(),
         environment,
     };
 
@@ -96,3 +423,378 @@ fn get_env_number(key: &str) -> u32 {
 
     variable
 }
+
+fn get_env_or(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+fn get_env_number_or(key: &str, default: u32) -> u32 {
+    match std::env::var(key) {
+        Ok(_) => get_env_number(key),
+        Err(_) => default,
+    }
+}
+
+fn get_password_hash_variant() -> Variant {
+    let name = get_env_or(PASSWORD_HASH_VARIANT, "argon2id");
+    Variant::from_str(&name).unwrap_or_else(|_| {
+        eprintln!(
+            "{}",
+            format!("Unknown {}: {}", PASSWORD_HASH_VARIANT, name.yellow()).red()
+        );
+        exit(1);
+    })
+}
+
+fn default_concurrency() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(4)
+}
+
+pub fn pepper_file_path() -> Option<String> {
+    Some(get_env_or(PASSWORD_PEPPER_FILE, "")).filter(|p| !p.is_empty())
+}
+
+/// Archivo con una línea `id=secreto_en_base64` por pepper. Las líneas vacías
+/// o que empiezan con `#` se ignoran.
+pub fn parse_peppers(content: &str) -> Result<Vec<Pepper>, String> {
+    let mut peppers: Vec<Pepper> = vec![];
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (id, secret) = line
+            .split_once('=')
+            .ok_or_else(|| format!("línea {}: se esperaba id=secreto", number + 1))?;
+        let id = id.trim();
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("línea {}: id de pepper inválido", number + 1));
+        }
+        if peppers.iter().any(|p| p.id == id) {
+            return Err(format!(
+                "línea {}: id de pepper repetido {}",
+                number + 1,
+                id
+            ));
+        }
+        let secret = STANDARD
+            .decode(secret.trim())
+            .map_err(|_| format!("línea {}: el secreto no es base64 válido", number + 1))?;
+        if secret.len() < 16 {
+            return Err(format!(
+                "línea {}: el secreto debe tener al menos 16 bytes",
+                number + 1
+            ));
+        }
+        peppers.push(Pepper {
+            id: id.to_string(),
+            secret,
+        });
+    }
+    Ok(peppers)
+}
+
+fn get_peppers() -> Vec<Pepper> {
+    let Some(path) = pepper_file_path() else {
+        return vec![];
+    };
+    let peppers = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| parse_peppers(&content));
+    peppers.unwrap_or_else(|e| {
+        eprintln!(
+            "{}",
+            format!(
+                "Error reading {} ({}): {}",
+                PASSWORD_PEPPER_FILE.yellow(),
+                path,
+                e
+            )
+            .red()
+        );
+        exit(1);
+    })
+}
+
+/// `PASSWORD_PEPPER_ID` o, si no está, el último pepper del archivo.
+fn get_current_pepper(peppers: &[Pepper]) -> Option<String> {
+    let id = get_env_or(PASSWORD_PEPPER_ID, "");
+    if id.is_empty() {
+        return peppers.last().map(|p| p.id.clone());
+    }
+    if !peppers.iter().any(|p| p.id == id) {
+        eprintln!(
+            "{}",
+            format!("Unknown {}: {}", PASSWORD_PEPPER_ID, id.yellow()).red()
+        );
+        exit(1);
+    }
+    Some(id)
+}
+
+fn get_password_policy() -> PasswordPolicyConfig {
+    let required_classes = get_env_or(PASSWORD_REQUIRED_CLASSES, "lower,upper,digit,symbol")
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .map(|name| match name.as_str() {
+            "lower" => CharacterClass::Lowercase,
+            "upper" => CharacterClass::Uppercase,
+            "digit" => CharacterClass::Digit,
+            "symbol" => CharacterClass::Symbol,
+            _ => {
+                eprintln!(
+                    "{}",
+                    format!("Unknown {}: {}", PASSWORD_REQUIRED_CLASSES, name.yellow()).red()
+                );
+                exit(1);
+            }
+        })
+        .collect();
+
+    let policy = PasswordPolicyConfig {
+        min_length: get_env_number_or(PASSWORD_MIN_LENGTH, 8),
+        max_length: get_env_number_or(PASSWORD_MAX_LENGTH, 64),
+        required_classes,
+        forbid_user_info: get_env_or(PASSWORD_FORBID_USER_INFO, "true") != "false",
+        max_repeated_chars: get_env_number_or(PASSWORD_MAX_REPEATED_CHARS, 0),
+        min_strength: get_env_number_or(PASSWORD_MIN_STRENGTH, 0),
+        breached_passwords_path: Some(get_env_or(BREACHED_PASSWORDS_PATH, ""))
+            .filter(|p| !p.is_empty()),
+        history_size: get_env_number_or(PASSWORD_HISTORY_SIZE, 5),
+        max_age_days: get_env_number_or(PASSWORD_MAX_AGE_DAYS, 0),
+    };
+    // 1024 es el tope que acepta `LoginRequest`.
+    if policy.min_length > policy.max_length || policy.max_length > 1024 || policy.min_strength > 4
+    {
+        eprintln!(
+            "{}",
+            format!(
+                "Invalid password policy: {} must not exceed {} (at most 1024) and {} must be between 0 and 4",
+                PASSWORD_MIN_LENGTH.yellow(),
+                PASSWORD_MAX_LENGTH.yellow(),
+                PASSWORD_MIN_STRENGTH.yellow()
+            )
+            .red()
+        );
+        exit(1);
+    }
+    policy
+}
+
+fn get_session_config() -> SessionConfig {
+    let mode = match get_env_or(SESSION_MODE, "token").as_str() {
+        "token" => SessionMode::Token,
+        "cookie" => SessionMode::Cookie,
+        other => {
+            eprintln!(
+                "{}",
+                format!("Unknown {}: {}", SESSION_MODE, other.yellow()).red()
+            );
+            exit(1);
+        }
+    };
+    let cookie_same_site = match get_env_or(SESSION_COOKIE_SAME_SITE, "Strict").as_str() {
+        same_site @ ("Strict" | "Lax" | "None") => same_site.to_string(),
+        other => {
+            eprintln!(
+                "{}",
+                format!("Unknown {}: {}", SESSION_COOKIE_SAME_SITE, other.yellow()).red()
+            );
+            exit(1);
+        }
+    };
+
+    SessionConfig {
+        mode,
+        cookie_secure: get_env_or(SESSION_COOKIE_SECURE, "true") != "false",
+        cookie_same_site,
+        cookie_domain: Some(get_env_or(SESSION_COOKIE_DOMAIN, "")).filter(|d| !d.is_empty()),
+    }
+}
+
+fn get_signup_config() -> SignupConfig {
+    let mode = match get_env_or(SIGNUP_MODE, "disabled").as_str() {
+        "disabled" => SignupMode::Disabled,
+        "open" => SignupMode::Open,
+        "domains" => {
+            let domains = split_list(&get_env(SIGNUP_ALLOWED_DOMAINS));
+            if domains.is_empty() {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "The env variable {} is empty",
+                        SIGNUP_ALLOWED_DOMAINS.yellow()
+                    )
+                    .red()
+                );
+                exit(1);
+            }
+            SignupMode::Domains(domains)
+        }
+        other => {
+            eprintln!(
+                "{}",
+                format!("Unknown {}: {}", SIGNUP_MODE, other.yellow()).red()
+            );
+            exit(1);
+        }
+    };
+    let captcha = Some(get_env_or(CAPTCHA_SECRET, ""))
+        .filter(|s| !s.is_empty())
+        .map(|secret| CaptchaConfig {
+            verify_url: get_env_or(
+                CAPTCHA_VERIFY_URL,
+                "https://www.google.com/recaptcha/api/siteverify",
+            ),
+            secret,
+        });
+
+    SignupConfig {
+        mode,
+        username_blocklist: split_list(&get_env_or(
+            SIGNUP_USERNAME_BLOCKLIST,
+            DEFAULT_USERNAME_BLOCKLIST,
+        )),
+        captcha,
+    }
+}
+
+/// Lista separada por comas, normalizada a minúsculas y sin vacíos.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Lee los proveedores listados en `OIDC_PROVIDERS` (separados por comas). Cada
+/// proveedor `<name>` se configura con variables `OIDC_<NAME>_*`.
+fn get_oidc_providers() -> Vec<OidcProviderConfig> {
+    get_env_or(OIDC_PROVIDERS, "")
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let prefix = format!("OIDC_{}", name.to_uppercase());
+            OidcProviderConfig {
+                issuer: get_env(&format!("{}_ISSUER", prefix)),
+                client_id: get_env(&format!("{}_CLIENT_ID", prefix)),
+                client_secret: get_env(&format!("{}_CLIENT_SECRET", prefix)),
+                redirect_uri: get_env(&format!("{}_REDIRECT_URI", prefix)),
+                scopes: get_env_or(&format!("{}_SCOPES", prefix), "openid email profile"),
+                algorithms: get_oidc_algorithms(&format!("{}_ALGORITHMS", prefix)),
+                name,
+            }
+        })
+        .collect()
+}
+
+fn get_oidc_algorithms(variable: &str) -> Vec<Algorithm> {
+    get_env_or(variable, "")
+        .split(',')
+        .map(str::trim)
+        .filter(|alg| !alg.is_empty())
+        .map(|alg| {
+            alg.to_uppercase().parse().unwrap_or_else(|_| {
+                eprintln!(
+                    "{}",
+                    format!("Unknown algorithm in {}: {}", variable, alg.yellow()).red()
+                );
+                exit(1);
+            })
+        })
+        .collect()
+}
+
+fn get_private_key_pem() -> Option<String> {
+    let path = get_env_or(JWT_PRIVATE_KEY_PATH, "");
+    if path.is_empty() {
+        return None;
+    }
+    let pem = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!(
+            "{}",
+            format!(
+                "Error reading {} ({}): {}",
+                JWT_PRIVATE_KEY_PATH.yellow(),
+                path,
+                e
+            )
+            .red()
+        );
+        exit(1);
+    });
+    Some(pem)
+}
+
+fn get_auth_backends() -> Vec<AuthBackendKind> {
+    get_env_or(AUTH_BACKENDS, "password")
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .map(|name| match name.as_str() {
+            "password" => AuthBackendKind::Password,
+            "ldap" => AuthBackendKind::Ldap,
+            _ => {
+                eprintln!(
+                    "{}",
+                    format!("Unknown authentication backend {}", name.yellow()).red()
+                );
+                exit(1);
+            }
+        })
+        .collect()
+}
+
+fn get_ldap_config() -> LdapConfig {
+    let group_search_base = get_env_or(LDAP_GROUP_SEARCH_BASE, "");
+
+    LdapConfig {
+        url: get_env(LDAP_URL),
+        bind_dn: get_env_or(LDAP_BIND_DN, ""),
+        bind_password: get_env_or(LDAP_BIND_PASSWORD, ""),
+        search_base: get_env(LDAP_SEARCH_BASE),
+        user_filter: get_env_or(LDAP_USER_FILTER, "(&(objectClass=person)(mail={email}))"),
+        username_attribute: get_env_or(LDAP_USERNAME_ATTRIBUTE, "uid"),
+        email_attribute: get_env_or(LDAP_EMAIL_ATTRIBUTE, "mail"),
+        group_attribute: get_env_or(LDAP_GROUP_ATTRIBUTE, "memberOf"),
+        group_search_base: (!group_search_base.is_empty()).then_some(group_search_base),
+        group_filter: get_env_or(LDAP_GROUP_FILTER, "(member={dn})"),
+        group_permissions: get_group_permissions(),
+        link_by_email: get_env_or(LDAP_LINK_BY_EMAIL, "false") == "true",
+    }
+}
+
+/// Formato: `<dn del grupo>:<PERMISO>|<PERMISO>;<dn del grupo>:<PERMISO>`.
+fn get_group_permissions() -> Vec<(String, Permissions)> {
+    get_env_or(LDAP_GROUP_PERMISSIONS, "")
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let parsed = entry.rsplit_once(':').and_then(|(group, perms)| {
+                parse_permission_names(perms).map(|p| (group.trim().to_lowercase(), p))
+            });
+            parsed.unwrap_or_else(|| {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Invalid entry in {}: {}",
+                        LDAP_GROUP_PERMISSIONS.yellow(),
+                        entry
+                    )
+                    .red()
+                );
+                exit(1);
+            })
+        })
+        .collect()
+}
@@ -35,12 +35,9 @@ pub async fn initialize_global_db_pool(database_url: &str) -> Result<(), DbError
     let pool = create_pool(database_url).await?;
     if let Err(e) = GLOBAL_DB_POOL.set(pool) {
         eprintln!(
-            "{}",
-            format!(
-                "{}: {:?}",
-                "Ocurrió un error al conectar con la base de datos".red(),
-                e
-            )
+            "{}: {:?}",
+            "Ocurrió un error al conectar con la base de datos".red(),
+            e
         );
         exit(1);
     };
@@ -1,17 +1,38 @@
 use axum::{Json, http::StatusCode};
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::{
     database::models::entities::user::User,
     utils::{Permissions, errors::HttpError},
 };
 
+/// Scope de los tokens que solo permiten cambiar la contraseña.
+pub const PASSWORD_CHANGE_SCOPE: &str = "password_change";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub user_id: String,
     pub exp: usize,
     pub iat: usize,
+
+    /// Permisos al emitir el token; los verifican servicios sin acceso a la base.
+    #[serde(default)]
+    pub perms: i64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Restringe el token; hoy solo existe `PASSWORD_CHANGE_SCOPE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// Atributos personalizados marcados con `includeInToken`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<Map<String, Value>>,
+
+    #[serde(skip)]
     user: Option<User>,
 }
 
@@ -25,10 +46,18 @@ impl Claims {
             user_id,
             exp: exp.timestamp() as usize,
             iat: iat.timestamp() as usize,
+            perms: 0,
+            username: None,
+            scope: None,
+            attrs: None,
             user: None,
         }
     }
 
+    pub fn is_password_change_only(&self) -> bool {
+        self.scope.as_deref() == Some(PASSWORD_CHANGE_SCOPE)
+    }
+
     pub fn set_user(&mut self, user: User) {
         self.user = Some(user);
     }
@@ -37,6 +66,11 @@ impl Claims {
         self.user.as_ref()
     }
 
+    /// Permisos actuales del usuario cargado por el extractor.
+    pub fn user_permissions(&self) -> Permissions {
+        Permissions::from_bits_retain(self.get_user().map_or(0, |u| u.permissions))
+    }
+
     pub fn require_permission(
         &self,
         perm: Permissions,
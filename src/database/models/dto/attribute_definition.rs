@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::database::models::entities::attribute_definition::AttributeType;
+
+/// Cuerpo de `PUT /users/attributes/{name}`; el nombre va en la ruta.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct AttributeDefinitionDto {
+    #[serde(rename = "type")]
+    pub kind: AttributeType,
+
+    #[serde(default)]
+    pub required: bool,
+
+    #[serde(rename = "includeInToken", default)]
+    pub include_in_token: bool,
+
+    #[serde(rename = "userEditable", default)]
+    pub user_editable: bool,
+
+    #[validate(length(max = 255, message = "The description must be at most 255 characters"))]
+    pub description: Option<String>,
+}
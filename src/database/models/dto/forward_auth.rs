@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct VerifyQuery {
+    /// Permisos requeridos, separados por `,` o `|` (ej. `READ_USERS,UPDATE_USERS`).
+    pub permissions: Option<String>,
+}
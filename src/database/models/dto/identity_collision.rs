@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::database::models::entities::user_status::UserStatus;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollidingUser {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+    pub status: UserStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Usuarios cuyo email o username coinciden una vez normalizados. Hay que
+/// fusionarlos (o renombrar los sobrantes) antes de crear los índices únicos.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IdentityCollision {
+    /// `email` o `username`.
+    pub field: &'static str,
+    /// Valor normalizado compartido (el username, en minúsculas).
+    pub value: String,
+    /// Ordenados por antigüedad: el primero suele ser la cuenta a conservar.
+    pub users: Vec<CollidingUser>,
+}
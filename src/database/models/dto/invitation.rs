@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use super::user_dto::USERNAME_RE;
+use crate::utils::{normalize_email, normalize_username};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+pub struct CreateInvitationDto {
+    #[validate(
+        email(message = "El correo electrónico es obligatorio"),
+        length(
+            max = 100,
+            message = "La longitud máxima del email es de 100 caracteres"
+        )
+    )]
+    pub email: String,
+
+    /// Permisos del usuario al aceptar; por defecto los de un usuario común.
+    pub permissions: Option<i64>,
+
+    /// Ids de los grupos a los que se suma al aceptar.
+    #[serde(default)]
+    pub groups: Vec<i64>,
+}
+
+impl CreateInvitationDto {
+    pub fn normalized(self) -> Self {
+        CreateInvitationDto {
+            email: normalize_email(&self.email),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+pub struct AcceptInvitationDto {
+    #[validate(length(min = 1, message = "El token es obligatorio"))]
+    pub token: String,
+
+    #[validate(
+        length(
+            min = 3,
+            max = 100,
+            message = "The username must be between 3 and 100 characters"
+        ),
+        regex(
+            path = "*USERNAME_RE",
+            message = "Username contains invalid characters"
+        )
+    )]
+    pub username: String,
+
+    /// El largo y el resto de las reglas los define la política de contraseñas.
+    pub password: String,
+
+    /// Solo los atributos que el usuario puede editar; los obligatorios deben venir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub attributes: Option<Map<String, Value>>,
+}
+
+impl AcceptInvitationDto {
+    pub fn normalized(self) -> Self {
+        AcceptInvitationDto {
+            username: normalize_username(&self.username),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema, IntoParams)]
+pub struct InvitationListQuery {
+    /// `pending`, `accepted`, `revoked` o `expired`; sin filtro devuelve todas.
+    pub status: Option<String>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct MagicLinkRequest {
+    #[validate(
+        email(message = "El email es obligatorio"),
+        length(
+            max = 100,
+            message = "La longitud máxima del email es de 100 caracteres"
+        )
+    )]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct RedeemMagicLinkRequest {
+    #[validate(length(min = 1, message = "El token es obligatorio"))]
+    pub token: String,
+}
@@ -1,5 +1,29 @@
+mod attribute_definition;
+mod forward_auth;
+mod identity_collision;
+mod invitation;
 mod login;
+mod magic_link;
+mod oidc;
+mod password_policy;
+mod password_report;
+mod privacy;
+mod registration;
+mod scim;
 mod user_dto;
+mod user_transfer;
 
+pub use attribute_definition::*;
+pub use forward_auth::*;
+pub use identity_collision::*;
+pub use invitation::*;
 pub use login::*;
+pub use magic_link::*;
+pub use oidc::*;
+pub use password_policy::*;
+pub use password_report::*;
+pub use privacy::*;
+pub use registration::*;
+pub use scim::*;
 pub use user_dto::*;
+pub use user_transfer::*;
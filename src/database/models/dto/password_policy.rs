@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::PasswordPolicyConfig;
+
+/// Política de contraseñas vigente, para que los frontends la muestren y
+/// validen antes de enviar el formulario.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordPolicyResponse {
+    pub min_length: u32,
+    pub max_length: u32,
+    /// Clases obligatorias: `lower`, `upper`, `digit` y `symbol`.
+    pub required_classes: Vec<String>,
+    pub forbid_user_info: bool,
+    /// `None` si no hay límite.
+    pub max_repeated_chars: Option<u32>,
+    /// Puntaje mínimo de fortaleza de 0 a 4, estilo zxcvbn.
+    pub min_strength: u32,
+    /// Se rechazan contraseñas que aparecen en filtraciones conocidas.
+    pub breached_check: bool,
+    /// Contraseñas anteriores que no se pueden reutilizar.
+    pub history_size: u32,
+    /// `None` si las contraseñas no vencen.
+    pub max_age_days: Option<u32>,
+}
+
+impl From<&PasswordPolicyConfig> for PasswordPolicyResponse {
+    fn from(policy: &PasswordPolicyConfig) -> Self {
+        PasswordPolicyResponse {
+            min_length: policy.min_length,
+            max_length: policy.max_length,
+            required_classes: policy
+                .required_classes
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+            forbid_user_info: policy.forbid_user_info,
+            max_repeated_chars: Some(policy.max_repeated_chars).filter(|n| *n > 0),
+            min_strength: policy.min_strength,
+            breached_check: policy.breached_passwords_path.is_some(),
+            history_size: policy.history_size,
+            max_age_days: Some(policy.max_age_days).filter(|d| *d > 0),
+        }
+    }
+}
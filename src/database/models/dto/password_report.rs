@@ -0,0 +1,20 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Usuarios que comparten los mismos parámetros de hash.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PasswordHashGroup {
+    /// `None` si el hash no tiene un formato Argon2 reconocible.
+    pub params: Option<String>,
+    pub users: i64,
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PasswordHashReport {
+    pub current_params: String,
+    pub total: i64,
+    pub current: i64,
+    pub outdated: i64,
+    pub groups: Vec<PasswordHashGroup>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database::models::entities::{
+    audit_event::AuditEvent, identity::UserIdentity, user::User,
+};
+
+/// Copia de los datos personales del usuario (`GET /api/users/me/export`).
+/// Los tokens son JWT sin estado, así que el historial de sesiones son los
+/// eventos `login` de `audit_events` y los enlaces de acceso emitidos.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDataExport {
+    pub exported_at: DateTime<Utc>,
+    pub user: User,
+    pub identities: Vec<UserIdentity>,
+    pub groups: Vec<String>,
+    pub magic_links: Vec<MagicLinkExport>,
+    pub audit_events: Vec<AuditEvent>,
+}
+
+/// Enlace de acceso emitido; el token nunca se guarda en claro.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MagicLinkExport {
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ErasureResponse {
+    /// Momento en que se anonimizan los datos; hasta entonces se puede cancelar.
+    pub scheduled_at: DateTime<Utc>,
+}
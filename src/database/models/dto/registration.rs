@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::user_dto::USERNAME_RE;
+use crate::utils::{normalize_email, normalize_username};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+pub struct RegisterDto {
+    #[validate(
+        length(
+            min = 3,
+            max = 100,
+            message = "The username must be between 3 and 100 characters"
+        ),
+        regex(
+            path = "*USERNAME_RE",
+            message = "Username contains invalid characters"
+        )
+    )]
+    pub username: String,
+
+    #[validate(
+        email(message = "El correo electrónico es obligatorio"),
+        length(
+            max = 100,
+            message = "La longitud máxima del email es de 100 caracteres"
+        )
+    )]
+    pub email: String,
+
+    /// El largo y el resto de las reglas los define la política de contraseñas.
+    pub password: String,
+
+    /// Respuesta del widget de CAPTCHA; obligatoria si el servidor tiene uno
+    /// configurado.
+    #[serde(rename = "captchaToken", default)]
+    pub captcha_token: Option<String>,
+
+    /// Solo los atributos que el usuario puede editar; los obligatorios deben venir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub attributes: Option<Map<String, Value>>,
+}
+
+impl RegisterDto {
+    pub fn normalized(self) -> Self {
+        RegisterDto {
+            username: normalize_username(&self.username),
+            email: normalize_email(&self.email),
+            ..self
+        }
+    }
+}
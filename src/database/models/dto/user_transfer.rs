@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use super::user_dto::{USERNAME_RE, validate_avatar_url, validate_locale, validate_phone_number};
+use crate::{
+    database::models::entities::{
+        user::User,
+        user_status::{UserStatus, serialize_status_name},
+    },
+    utils::{normalize_email, normalize_username},
+};
+
+/// Formatos de la importación y exportación masiva.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferFormat {
+    /// Con encabezado; `attributes` va como objeto JSON en su celda.
+    Csv,
+    /// Un objeto JSON por línea.
+    Ndjson,
+}
+
+impl TransferFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "text/csv; charset=utf-8",
+            TransferFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "csv",
+            TransferFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Un usuario de la importación o la exportación. Las columnas del CSV se
+/// llaman igual que los campos JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserRecord {
+    #[validate(
+        length(
+            min = 3,
+            max = 100,
+            message = "The username must be between 3 and 100 characters"
+        ),
+        regex(
+            path = "*USERNAME_RE",
+            message = "Username contains invalid characters"
+        )
+    )]
+    pub username: String,
+
+    #[validate(email(message = "El correo electrónico es obligatorio"))]
+    pub email: String,
+
+    /// Contraseña en claro: se valida con la política y se hashea. Sin
+    /// `password` ni `passwordHash` el usuario se crea sin contraseña (solo
+    /// entra por OIDC o enlace mágico) y su línea se lista en
+    /// `withoutPassword` del reporte.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// Hash Argon2 o bcrypt ya calculado; no se combina con `password`. Los
+    /// bcrypt se reemplazan por Argon2 en el primer login.
+    #[serde(
+        rename = "passwordHash",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub password_hash: Option<String>,
+
+    /// Por defecto los permisos de un usuario nuevo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<i64>,
+
+    /// Por defecto `active`. Se exporta por nombre; al importar se acepta
+    /// también el número.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_status_name"
+    )]
+    #[schema(value_type = Option<String>)]
+    pub status: Option<UserStatus>,
+
+    #[serde(
+        rename = "displayName",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[validate(length(max = 100, message = "The display name must be at most 100 characters"))]
+    pub display_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 64, message = "The timezone must be at most 64 characters"))]
+    pub timezone: Option<String>,
+
+    #[serde(rename = "avatarUrl", default, skip_serializing_if = "Option::is_none")]
+    #[validate(
+        length(max = 512, message = "The avatar URL must be at most 512 characters"),
+        custom(function = "validate_avatar_url")
+    )]
+    pub avatar_url: Option<String>,
+
+    #[serde(
+        rename = "phoneNumber",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[validate(custom(function = "validate_phone_number"))]
+    pub phone_number: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub attributes: Option<Map<String, Value>>,
+}
+
+impl UserRecord {
+    /// Igual que en el alta; los campos de perfil vacíos se omiten.
+    pub fn normalized(self) -> Self {
+        let trim = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        UserRecord {
+            username: normalize_username(&self.username),
+            email: normalize_email(&self.email),
+            display_name: trim(self.display_name),
+            locale: trim(self.locale),
+            timezone: trim(self.timezone),
+            avatar_url: trim(self.avatar_url),
+            phone_number: trim(self.phone_number),
+            ..self
+        }
+    }
+}
+
+impl From<User> for UserRecord {
+    /// La exportación no incluye contraseñas ni hashes.
+    fn from(user: User) -> Self {
+        UserRecord {
+            username: user.username,
+            email: user.email,
+            password: None,
+            password_hash: None,
+            permissions: Some(user.permissions),
+            status: Some(user.status),
+            display_name: user.display_name,
+            locale: user.locale,
+            timezone: user.timezone,
+            avatar_url: user.avatar_url,
+            phone_number: user.phone_number,
+            attributes: (!user.attributes.is_empty()).then_some(user.attributes),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQuery {
+    pub format: TransferFormat,
+
+    /// Valida todo y arma el reporte sin crear usuarios.
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQuery {
+    pub format: TransferFormat,
+
+    /// Sin filtro se exportan todos menos los eliminados.
+    pub status: Option<UserStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+
+    /// Filas leídas, sin contar el encabezado ni las líneas vacías.
+    pub total: usize,
+
+    /// Usuarios creados; en `dryRun`, los que se crearían.
+    pub imported: usize,
+
+    pub failed: usize,
+
+    /// Líneas importadas sin `password` ni `passwordHash`; en `dryRun`, las
+    /// que se importarían así.
+    #[serde(rename = "withoutPassword")]
+    pub without_password: Vec<u64>,
+
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Error de una fila; `errors` tiene la misma forma que en `HttpError`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowError {
+    /// Línea del archivo, empezando en 1.
+    pub line: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub username: Option<String>,
+
+    pub errors: HashMap<String, Vec<String>>,
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Tipo de valor de un atributo personalizado. Se guarda como texto en
+/// `user_attribute_definitions.type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    Number,
+    Boolean,
+}
+
+impl AttributeType {
+    /// Coincide con el resultado de `jsonb_typeof` para valores del tipo.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Boolean => "boolean",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "string" => Some(AttributeType::String),
+            "number" => Some(AttributeType::Number),
+            "boolean" => Some(AttributeType::Boolean),
+            _ => None,
+        }
+    }
+
+    pub fn matches(self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (AttributeType::String, Value::String(_))
+                | (AttributeType::Number, Value::Number(_))
+                | (AttributeType::Boolean, Value::Bool(_))
+        )
+    }
+}
+
+/// Atributo personalizado que los usuarios pueden tener en `attributes`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttributeDefinition {
+    pub name: String,
+
+    #[serde(rename = "type")]
+    pub kind: AttributeType,
+
+    /// Se exige en las altas (API, registro, invitación, SCIM e importación)
+    /// y al modificar los atributos de un usuario. El aprovisionamiento por
+    /// LDAP u OIDC no puede completarlo y no lo exige.
+    pub required: bool,
+
+    /// Se copia en el claim `attrs` de los tokens de login.
+    #[serde(rename = "includeInToken")]
+    pub include_in_token: bool,
+
+    /// El propio usuario puede fijarlo; si no, solo un administrador.
+    #[serde(rename = "userEditable")]
+    pub user_editable: bool,
+
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttributeDefinition {
+    pub fn from_row(row: &tokio_postgres::Row) -> Result<Self, Box<dyn std::error::Error>> {
+        let kind: String = row.try_get("type")?;
+        Ok(Self {
+            name: row.try_get("name")?,
+            kind: AttributeType::from_name(&kind)
+                .ok_or_else(|| format!("tipo de atributo desconocido: {}", kind))?,
+            required: row.try_get("required")?,
+            include_in_token: row.try_get("include_in_token")?,
+            user_editable: row.try_get("user_editable")?,
+            description: row.try_get("description")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
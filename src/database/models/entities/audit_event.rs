@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Tipos de evento que se auditan. Se guardan como texto en `audit_events.event`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEventKind {
+    Login,
+    PasswordChanged,
+    StatusChanged,
+    ErasureRequested,
+    ErasureCancelled,
+    Erased,
+}
+
+impl AuditEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditEventKind::Login => "login",
+            AuditEventKind::PasswordChanged => "password_changed",
+            AuditEventKind::StatusChanged => "status_changed",
+            AuditEventKind::ErasureRequested => "erasure_requested",
+            AuditEventKind::ErasureCancelled => "erasure_cancelled",
+            AuditEventKind::Erased => "erased",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub event: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub fn from_row(row: &tokio_postgres::Row) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            event: row.try_get("event")?,
+            details: row.try_get("details")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
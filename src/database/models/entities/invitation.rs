@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Revoked,
+    Expired,
+}
+
+impl InvitationStatus {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pending" => Some(InvitationStatus::Pending),
+            "accepted" => Some(InvitationStatus::Accepted),
+            "revoked" => Some(InvitationStatus::Revoked),
+            "expired" => Some(InvitationStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Condición SQL sobre la tabla `invitations` equivalente al estado.
+    pub fn sql_condition(self) -> &'static str {
+        match self {
+            InvitationStatus::Pending => {
+                "accepted_at IS NULL AND revoked_at IS NULL AND expires_at > now()"
+            }
+            InvitationStatus::Accepted => "accepted_at IS NOT NULL",
+            InvitationStatus::Revoked => "revoked_at IS NOT NULL",
+            InvitationStatus::Expired => {
+                "accepted_at IS NULL AND revoked_at IS NULL AND expires_at <= now()"
+            }
+        }
+    }
+}
+
+/// Invitación enviada por un administrador. El token solo viaja en el email;
+/// en la base se guarda su hash.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Invitation {
+    pub id: i64,
+    pub email: String,
+    pub permissions: i64,
+    pub groups: Vec<i64>,
+    pub status: InvitationStatus,
+    pub invited_by: Option<i64>,
+    /// Usuario creado al aceptar.
+    pub user_id: Option<i64>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    pub fn from_row(row: &tokio_postgres::Row) -> Result<Self, Box<dyn std::error::Error>> {
+        let expires_at: DateTime<Utc> = row.try_get("expires_at")?;
+        let accepted_at: Option<DateTime<Utc>> = row.try_get("accepted_at")?;
+        let revoked_at: Option<DateTime<Utc>> = row.try_get("revoked_at")?;
+        let status = if accepted_at.is_some() {
+            InvitationStatus::Accepted
+        } else if revoked_at.is_some() {
+            InvitationStatus::Revoked
+        } else if expires_at <= Utc::now() {
+            InvitationStatus::Expired
+        } else {
+            InvitationStatus::Pending
+        };
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            email: row.try_get("email")?,
+            permissions: row.try_get("permissions")?,
+            groups: row.try_get("group_ids")?,
+            status,
+            invited_by: row.try_get("invited_by")?,
+            user_id: row.try_get("user_id")?,
+            expires_at,
+            accepted_at,
+            revoked_at,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
@@ -1 +1,6 @@
+pub mod attribute_definition;
+pub mod audit_event;
+pub mod identity;
+pub mod invitation;
 pub mod user;
+pub mod user_status;
@@ -0,0 +1 @@
+pub use r_auth_types::{UserStatus, serialize_status_name};
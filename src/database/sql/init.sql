@@ -7,4 +7,184 @@ create table if not exists users (
     permissions bigint,
     created_at timestamptz default now(),
     updated_at timestamptz default now()
-);
\ No newline at end of file
+);
+
+create table if not exists magic_links (
+    id bigserial primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    token_hash varchar(64) not null unique,
+    expires_at timestamptz not null,
+    used_at timestamptz,
+    created_at timestamptz default now()
+);
+
+create table if not exists user_identities (
+    id bigserial primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    provider varchar(50) not null,
+    subject varchar(255) not null,
+    email varchar(100),
+    created_at timestamptz default now(),
+    unique (provider, subject)
+);
+-- true si la identidad creó el usuario local; solo entonces el origen externo
+-- gobierna sus permisos.
+alter table user_identities add column if not exists provisioned boolean not null default false;
+
+create table if not exists oidc_auth_requests (
+    state_hash varchar(64) primary key,
+    provider varchar(50) not null,
+    nonce varchar(64) not null,
+    code_verifier varchar(64) not null,
+    expires_at timestamptz not null,
+    created_at timestamptz default now()
+);
+
+alter table users add column if not exists external_id varchar(255);
+
+create table if not exists groups (
+    id bigserial primary key,
+    display_name varchar(255) not null unique,
+    external_id varchar(255),
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+create table if not exists group_members (
+    group_id bigint not null references groups(id) on delete cascade,
+    user_id bigint not null references users(id) on delete cascade,
+    primary key (group_id, user_id)
+);
+
+alter table users add column if not exists password_changed_at timestamptz default now();
+
+create table if not exists password_history (
+    id bigserial primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    password varchar(512) not null,
+    created_at timestamptz default now()
+);
+
+create index if not exists password_history_user_idx on password_history (user_id, id desc);
+
+alter table users add column if not exists must_change_password boolean not null default false;
+
+alter table users add column if not exists deleted_at timestamptz;
+update users set deleted_at = updated_at where status = 3 and deleted_at is null;
+create index if not exists users_deleted_at_idx on users (deleted_at) where status = 3;
+
+alter table users add column if not exists erasure_scheduled_at timestamptz;
+
+create table if not exists audit_events (
+    id bigserial primary key,
+    user_id bigint references users(id) on delete set null,
+    event varchar(50) not null,
+    details varchar(255),
+    created_at timestamptz default now()
+);
+
+create index if not exists audit_events_user_idx on audit_events (user_id, id);
+
+create table if not exists invitations (
+    id bigserial primary key,
+    email varchar(100) not null,
+    permissions bigint not null,
+    group_ids bigint[] not null default '{}',
+    token_hash varchar(64) not null unique,
+    invited_by bigint references users(id) on delete set null,
+    user_id bigint references users(id) on delete set null,
+    expires_at timestamptz not null,
+    accepted_at timestamptz,
+    revoked_at timestamptz,
+    created_at timestamptz default now()
+);
+
+create index if not exists invitations_email_idx on invitations (lower(email));
+
+-- La paginación por cursor recorre (created_at, id).
+update users set created_at = now() where created_at is null;
+alter table users alter column created_at set not null;
+create index if not exists users_created_at_id_idx on users (created_at, id);
+
+-- Búsqueda libre (`q`) sobre username y email con pg_trgm.
+create extension if not exists pg_trgm;
+create index if not exists users_username_trgm_idx on users using gin (username gin_trgm_ops);
+create index if not exists users_email_trgm_idx on users using gin (email gin_trgm_ops);
+
+-- Unicidad de email y username sin distinguir mayúsculas. Los emails se
+-- guardan normalizados (sin espacios, NFKC y en minúsculas) y los usernames
+-- sin espacios y en NFKC; los existentes se normalizan igual salvo que choquen
+-- con otro usuario. `normalize` exige una base UTF8: con otra codificación solo
+-- se quitan espacios y mayúsculas, y `r-auth-api identity-collisions` detecta
+-- las diferencias que resuelve NFKC. Si quedan colisiones los índices no se
+-- crean: ese mismo comando las lista para fusionarlas.
+do $$
+begin
+    if current_setting('server_encoding') = 'UTF8' then
+        update users u set email = lower(normalize(btrim(u.email), NFKC))
+        where u.email <> lower(normalize(btrim(u.email), NFKC))
+          and not exists (
+              select 1 from users o
+              where o.id <> u.id
+                and lower(normalize(btrim(o.email), NFKC)) = lower(normalize(btrim(u.email), NFKC))
+          );
+        update users u set username = normalize(btrim(u.username), NFKC)
+        where u.username <> normalize(btrim(u.username), NFKC)
+          and not exists (
+              select 1 from users o
+              where o.id <> u.id
+                and lower(normalize(btrim(o.username), NFKC)) = lower(normalize(btrim(u.username), NFKC))
+          );
+    else
+        update users u set email = lower(btrim(u.email))
+        where u.email <> lower(btrim(u.email))
+          and not exists (
+              select 1 from users o
+              where o.id <> u.id and lower(btrim(o.email)) = lower(btrim(u.email))
+          );
+        update users u set username = btrim(u.username)
+        where u.username <> btrim(u.username)
+          and not exists (
+              select 1 from users o
+              where o.id <> u.id and lower(btrim(o.username)) = lower(btrim(u.username))
+          );
+    end if;
+end
+$$;
+
+do $$
+begin
+    if exists (select 1 from users group by lower(email) having count(*) > 1) then
+        raise warning 'Hay emails repetidos sin distinguir mayúsculas; ejecute r-auth-api identity-collisions';
+    else
+        create unique index if not exists users_email_lower_key on users (lower(email));
+    end if;
+    if exists (select 1 from users group by lower(username) having count(*) > 1) then
+        raise warning 'Hay usernames repetidos sin distinguir mayúsculas; ejecute r-auth-api identity-collisions';
+    else
+        create unique index if not exists users_username_lower_key on users (lower(username));
+    end if;
+end
+$$;
+
+-- Perfil extensible: campos fijos más atributos personalizados en JSONB,
+-- validados contra las definiciones que mantiene un administrador.
+alter table users add column if not exists display_name varchar(100);
+alter table users add column if not exists locale varchar(35);
+alter table users add column if not exists timezone varchar(64);
+alter table users add column if not exists avatar_url varchar(512);
+alter table users add column if not exists phone_number varchar(32);
+alter table users add column if not exists attributes jsonb not null default '{}';
+create index if not exists users_attributes_idx on users using gin (attributes jsonb_path_ops);
+
+create table if not exists user_attribute_definitions (
+    name varchar(64) primary key,
+    type varchar(16) not null,
+    required boolean not null default false,
+    include_in_token boolean not null default false,
+    description varchar(255),
+    created_at timestamptz not null default now()
+);
+-- Si el propio usuario puede fijarlo (perfil, registro, invitación); el
+-- resto solo lo modifican administradores y el aprovisionamiento.
+alter table user_attribute_definitions add column if not exists user_editable boolean not null default false;
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::AuthenticatedClaims,
+    database::models::{
+        FindResult, OneResult, dto::AttributeDefinitionDto,
+        entities::attribute_definition::AttributeDefinition,
+    },
+    services::AttributeService,
+    utils::{ApiResult, MessageResponse, Permissions, errors::HttpError},
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+};
+
+pub fn attribute_routes(state: AppState) -> Router {
+    let service = state.attribute_service.clone();
+    Router::new()
+        .route("/", get(list_attributes))
+        .route("/{name}", put(upsert_attribute).delete(delete_attribute))
+        .with_state(service)
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/attributes",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Definiciones de atributos personalizados", body = FindResult<AttributeDefinition>),
+        (status = 403, description = "Permisos insuficientes", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn list_attributes(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<AttributeService>>,
+) -> ApiResult<FindResult<AttributeDefinition>> {
+    claims.require_permission(Permissions::READ_USERS)?;
+    let results = service.list().await?;
+    Ok((
+        StatusCode::OK,
+        Json(FindResult {
+            total: Some(results.len() as u64),
+            results,
+            next_cursor: None,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/attributes/{name}",
+    tag = "Users",
+    params(
+        ("name" = String, Path, description = "Nombre del atributo")
+    ),
+    request_body = AttributeDefinitionDto,
+    responses(
+        (status = 200, description = "Definición guardada", body = OneResult<AttributeDefinition>),
+        (status = 400, description = "Nombre o datos inválidos", body = HttpError),
+        (status = 403, description = "Permisos insuficientes", body = HttpError),
+        (status = 409, description = "Hay usuarios con valores de otro tipo", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn upsert_attribute(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<AttributeService>>,
+    Path(name): Path<String>,
+    Json(payload): Json<AttributeDefinitionDto>,
+) -> ApiResult<OneResult<AttributeDefinition>> {
+    claims.require_permission(Permissions::ADMIN)?;
+    let definition = service.upsert(&name, payload).await?;
+    Ok((StatusCode::OK, Json(OneResult { result: definition })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/attributes/{name}",
+    tag = "Users",
+    params(
+        ("name" = String, Path, description = "Nombre del atributo")
+    ),
+    responses(
+        (status = 200, description = "Definición y valores borrados", body = MessageResponse),
+        (status = 403, description = "Permisos insuficientes", body = HttpError),
+        (status = 404, description = "Atributo no encontrado", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn delete_attribute(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<AttributeService>>,
+    Path(name): Path<String>,
+) -> ApiResult<MessageResponse> {
+    claims.require_permission(Permissions::ADMIN)?;
+    service.delete(&name).await?;
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse {
+            message: "Atributo borrado correctamente".to_string(),
+        }),
+    ))
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::{AuthenticatedClaims, login_response, public_jwks},
+    config::get_config,
+    database::models::dto::{LoginResponse, OidcCallbackQuery, OidcProvidersResponse, VerifyQuery},
+    services::OidcService,
+    utils::{ApiError, ApiResult, Permissions, errors::HttpError, parse_permission_names},
+};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{Redirect, Response},
+    routing::get,
+};
+
+pub fn auth_routes(state: AppState) -> Router {
+    let service = state.oidc_service.clone();
+    Router::new()
+        .route("/oidc/providers", get(oidc_providers))
+        .route("/oidc/{provider}/authorize", get(oidc_authorize))
+        .route("/oidc/{provider}/callback", get(oidc_callback))
+        .route("/verify", get(verify))
+        .with_state(service)
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/providers",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Proveedores de identidad configurados", body = OidcProvidersResponse)
+    )
+)]
+pub async fn oidc_providers(
+    State(service): State<Arc<OidcService>>,
+) -> ApiResult<OidcProvidersResponse> {
+    Ok((
+        StatusCode::OK,
+        Json(OidcProvidersResponse {
+            providers: service.provider_names(),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/{provider}/authorize",
+    tag = "Auth",
+    params(
+        ("provider" = String, Path, description = "Nombre del proveedor de identidad")
+    ),
+    responses(
+        (status = 303, description = "Redirección al proveedor de identidad"),
+        (status = 404, description = "Proveedor no configurado", body = HttpError),
+        (status = 502, description = "Error contactando al proveedor", body = HttpError)
+    )
+)]
+pub async fn oidc_authorize(
+    State(service): State<Arc<OidcService>>,
+    Path(provider): Path<String>,
+) -> Result<Redirect, ApiError> {
+    let url = service.authorize_url(&provider).await?;
+    Ok(Redirect::to(&url))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/{provider}/callback",
+    tag = "Auth",
+    params(
+        ("provider" = String, Path, description = "Nombre del proveedor de identidad"),
+        OidcCallbackQuery
+    ),
+    responses(
+        (status = 200, description = "Login exitoso", body = LoginResponse),
+        (status = 401, description = "Login rechazado", body = HttpError),
+        (status = 403, description = "Email no verificado o usuario inactivo", body = HttpError)
+    )
+)]
+pub async fn oidc_callback(
+    State(service): State<Arc<OidcService>>,
+    Path(provider): Path<String>,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<Response, ApiError> {
+    let token = service.callback(&provider, query).await?;
+    Ok(login_response(token, &get_config().session))
+}
+
+/// Endpoint para `auth_request` de nginx y ForwardAuth de Traefik. Ambos solo
+/// distinguen 2xx, 401 y 403, así que cualquier fallo de autenticación que no
+/// sea un usuario inactivo o un error interno se responde como 401.
+#[utoipa::path(
+    get,
+    path = "/auth/verify",
+    tag = "Auth",
+    params(VerifyQuery),
+    responses(
+        (status = 200, description = "Token válido", headers(
+            ("X-Auth-User-Id" = String, description = "Id del usuario"),
+            ("X-Auth-Username" = String, description = "Nombre de usuario"),
+            ("X-Auth-Permissions" = String, description = "Permisos del usuario separados por comas")
+        )),
+        (status = 400, description = "Permiso desconocido en la query", body = HttpError),
+        (status = 401, description = "Token faltante o inválido", body = HttpError),
+        (status = 403, description = "Usuario inactivo o permisos insuficientes", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn verify(
+    claims: Result<AuthenticatedClaims, ApiError>,
+    Query(query): Query<VerifyQuery>,
+) -> Result<(StatusCode, HeaderMap), ApiError> {
+    let AuthenticatedClaims(claims) = claims.map_err(|(status, body)| match status {
+        StatusCode::FORBIDDEN | StatusCode::INTERNAL_SERVER_ERROR => (status, body),
+        _ => HttpError::unauthorized("Token faltante o inválido"),
+    })?;
+
+    if let Some(names) = query.permissions.as_deref() {
+        let required = parse_permission_names(names)
+            .ok_or_else(|| HttpError::bad_request(&format!("{}: Permiso inválido", names)))?;
+        claims.require_permission(required)?;
+    }
+
+    let user = claims
+        .get_user()
+        .ok_or_else(|| HttpError::unauthorized("Usuario no encontrado"))?;
+    let permissions = Permissions::from_bits_retain(user.permissions)
+        .iter_names()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        ("x-auth-user-id", user.id.to_string()),
+        ("x-auth-username", user.username.clone()),
+        ("x-auth-permissions", permissions),
+    ] {
+        let value =
+            HeaderValue::from_str(&value).map_err(|_| HttpError::internal_server_error())?;
+        headers.insert(name, value);
+    }
+
+    Ok((StatusCode::OK, headers))
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Claves públicas para verificar los tokens (vacío con HS256)")
+    )
+)]
+pub async fn jwks() -> Json<serde_json::Value> {
+    Json(public_jwks())
+}
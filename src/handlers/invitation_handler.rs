@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::AuthenticatedClaims,
+    database::models::{
+        FindResult, OneResult,
+        dto::{AcceptInvitationDto, CreateInvitationDto, InvitationListQuery},
+        entities::{invitation::Invitation, user::User},
+    },
+    services::InvitationService,
+    utils::{ApiResult, MessageResponse, Permissions, errors::HttpError},
+};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+
+pub fn invitation_routes(state: AppState) -> Router {
+    let service = state.invitation_service.clone();
+    Router::new()
+        .route("/", post(create_invitation))
+        .route("/", get(list_invitations))
+        .route("/accept", post(accept_invitation))
+        .route("/{id}/resend", post(resend_invitation))
+        .route("/{id}", delete(revoke_invitation))
+        .with_state(service)
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/invitations",
+    tag = "Users",
+    request_body = CreateInvitationDto,
+    responses(
+        (status = 201, description = "Invitación creada y enviada", body = OneResult<Invitation>),
+        (status = 400, description = "Datos inválidos", body = HttpError),
+        (status = 403, description = "Permisos insuficientes", body = HttpError),
+        (status = 409, description = "El email ya tiene cuenta o una invitación pendiente", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn create_invitation(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<InvitationService>>,
+    Json(payload): Json<CreateInvitationDto>,
+) -> ApiResult<OneResult<Invitation>> {
+    claims.require_permission(Permissions::CREATE_USERS)?;
+    let id: i64 = claims
+        .user_id
+        .parse()
+        .map_err(|_| HttpError::bad_request("Id de usuario inválido"))?;
+    let invitation = service
+        .create(id, claims.user_permissions(), payload)
+        .await?;
+    Ok((StatusCode::CREATED, Json(OneResult { result: invitation })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/invitations",
+    tag = "Users",
+    params(InvitationListQuery),
+    responses(
+        (status = 200, description = "Invitaciones", body = FindResult<Invitation>),
+        (status = 400, description = "Estado inválido", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn list_invitations(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<InvitationService>>,
+    Query(query): Query<InvitationListQuery>,
+) -> ApiResult<FindResult<Invitation>> {
+    claims.require_permission(Permissions::READ_USERS)?;
+    let result = service.list(query).await?;
+    Ok((StatusCode::OK, Json(result)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/invitations/{id}/resend",
+    tag = "Users",
+    params(
+        ("id" = i64, Path, description = "ID de la invitación")
+    ),
+    responses(
+        (status = 200, description = "Invitación reenviada con un enlace nuevo", body = OneResult<Invitation>),
+        (status = 404, description = "Invitación no encontrada", body = HttpError),
+        (status = 409, description = "La invitación ya fue aceptada o revocada", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn resend_invitation(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<InvitationService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<OneResult<Invitation>> {
+    claims.require_permission(Permissions::CREATE_USERS)?;
+    let invitation = service.resend(id).await?;
+    Ok((StatusCode::OK, Json(OneResult { result: invitation })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/invitations/{id}",
+    tag = "Users",
+    params(
+        ("id" = i64, Path, description = "ID de la invitación")
+    ),
+    responses(
+        (status = 200, description = "Invitación revocada", body = MessageResponse),
+        (status = 404, description = "Invitación no encontrada", body = HttpError),
+        (status = 409, description = "La invitación ya fue aceptada o revocada", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn revoke_invitation(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<InvitationService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<MessageResponse> {
+    claims.require_permission(Permissions::CREATE_USERS)?;
+    service.revoke(id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse {
+            message: "Invitación revocada correctamente".to_string(),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/invitations/accept",
+    tag = "Users",
+    request_body = AcceptInvitationDto,
+    responses(
+        (status = 201, description = "Cuenta creada", body = OneResult<User>),
+        (status = 400, description = "Datos inválidos o contraseña débil", body = HttpError),
+        (status = 401, description = "Invitación inválida o expirada", body = HttpError),
+        (status = 409, description = "El username ya está en uso", body = HttpError)
+    )
+)]
+pub async fn accept_invitation(
+    State(service): State<Arc<InvitationService>>,
+    Json(payload): Json<AcceptInvitationDto>,
+) -> ApiResult<OneResult<User>> {
+    let user = service.accept(payload).await?;
+    Ok((StatusCode::CREATED, Json(OneResult { result: user })))
+}
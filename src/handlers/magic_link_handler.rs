@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::login_response,
+    config::get_config,
+    database::models::dto::{LoginResponse, MagicLinkRequest, RedeemMagicLinkRequest},
+    services::MagicLinkService,
+    utils::{ApiError, ApiResult, MessageResponse, errors::HttpError},
+};
+use axum::{Json, Router, extract::State, http::StatusCode, response::Response, routing::post};
+
+pub fn magic_link_routes(state: AppState) -> Router {
+    let service = state.magic_link_service.clone();
+    Router::new()
+        .route("/", post(request_magic_link))
+        .route("/redeem", post(redeem_magic_link))
+        .with_state(service)
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/login/magic-link",
+    tag = "Users",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 200, description = "Si el email existe se envió un enlace de acceso", body = MessageResponse),
+        (status = 400, description = "Datos inválidos", body = HttpError),
+        (status = 429, description = "Demasiadas solicitudes para este email", body = HttpError)
+    )
+)]
+pub async fn request_magic_link(
+    State(service): State<Arc<MagicLinkService>>,
+    Json(payload): Json<MagicLinkRequest>,
+) -> ApiResult<MessageResponse> {
+    service.request(payload).await?;
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse {
+            message: "Si el email está registrado recibirás un enlace de acceso".to_string(),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/login/magic-link/redeem",
+    tag = "Users",
+    request_body = RedeemMagicLinkRequest,
+    responses(
+        (status = 200, description = "Login exitoso", body = LoginResponse),
+        (status = 401, description = "Enlace inválido o expirado", body = HttpError)
+    )
+)]
+pub async fn redeem_magic_link(
+    State(service): State<Arc<MagicLinkService>>,
+    Json(payload): Json<RedeemMagicLinkRequest>,
+) -> Result<Response, ApiError> {
+    let token = service.redeem(payload).await?;
+    Ok(login_response(token, &get_config().session))
+}
@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+use axum::http::header;
+use axum::response::IntoResponse;
+
+use crate::auth::{HASHING_POOL, HashingPoolMetrics};
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "Metrics",
+    responses(
+        (status = 200, description = "Métricas en formato de texto de Prometheus", content_type = "text/plain")
+    )
+)]
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render_prometheus(&HASHING_POOL.metrics()),
+    )
+}
+
+/// Formato de exposición de texto de Prometheus.
+pub fn render_prometheus(metrics: &HashingPoolMetrics) -> String {
+    let series: [(&str, &str, &str, String); 7] = [
+        (
+            "r_auth_password_hash_concurrency_limit",
+            "gauge",
+            "Máximo de hashes de contraseña simultáneos",
+            metrics.concurrency.to_string(),
+        ),
+        (
+            "r_auth_password_hash_queue_depth",
+            "gauge",
+            "Trabajos de hashing esperando un lugar en el pool",
+            metrics.queue_depth.to_string(),
+        ),
+        (
+            "r_auth_password_hash_in_flight",
+            "gauge",
+            "Trabajos de hashing en ejecución",
+            metrics.in_flight.to_string(),
+        ),
+        (
+            "r_auth_password_hash_completed_total",
+            "counter",
+            "Trabajos de hashing terminados",
+            metrics.completed.to_string(),
+        ),
+        (
+            "r_auth_password_hash_rejected_total",
+            "counter",
+            "Trabajos rechazados con 503 por superar el timeout de cola",
+            metrics.rejected.to_string(),
+        ),
+        (
+            "r_auth_password_hash_wait_seconds_total",
+            "counter",
+            "Tiempo total esperado en la cola",
+            metrics.wait_seconds.to_string(),
+        ),
+        (
+            "r_auth_password_hash_run_seconds_total",
+            "counter",
+            "Tiempo total de CPU dedicado a hashear",
+            metrics.run_seconds.to_string(),
+        ),
+    ];
+
+    let mut out = String::new();
+    for (name, kind, help, value) in series {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+    out
+}
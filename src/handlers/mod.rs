@@ -1,9 +1,43 @@
+pub mod attribute_handler;
+pub mod auth_handler;
+pub mod invitation_handler;
+pub mod magic_link_handler;
+pub mod metrics_handler;
+pub mod password_policy_handler;
+pub mod privacy_handler;
+pub mod registration_handler;
+pub mod scim_handler;
+pub mod user_transfer_handler;
 pub mod users_handler;
 
-use axum::Router;
+use axum::{Router, routing::get};
 
 use crate::AppState;
 
 pub fn api_routes(state: AppState) -> Router {
-    Router::new().nest("/users", users_handler::users_routes(state))
+    Router::new()
+        .route(
+            "/password-policy",
+            get(password_policy_handler::password_policy),
+        )
+        .nest("/auth", auth_handler::auth_routes(state.clone()))
+        .nest(
+            "/users/attributes",
+            attribute_handler::attribute_routes(state.clone()),
+        )
+        .nest(
+            "/users/invitations",
+            invitation_handler::invitation_routes(state.clone()),
+        )
+        .nest(
+            "/users/login/magic-link",
+            magic_link_handler::magic_link_routes(state.clone()),
+        )
+        .nest(
+            "/users",
+            users_handler::users_routes(state.clone())
+                .merge(privacy_handler::privacy_routes(state.clone()))
+                .merge(registration_handler::registration_routes(state.clone()))
+                .merge(user_transfer_handler::transfer_routes(state)),
+        )
 }
@@ -0,0 +1,15 @@
+use axum::Json;
+
+use crate::{config::get_config, database::models::dto::PasswordPolicyResponse};
+
+#[utoipa::path(
+    get,
+    path = "/password-policy",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Reglas que deben cumplir las contraseñas nuevas", body = PasswordPolicyResponse)
+    )
+)]
+pub async fn password_policy() -> Json<PasswordPolicyResponse> {
+    Json(PasswordPolicyResponse::from(&get_config().password_policy))
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::AuthenticatedClaims,
+    database::models::dto::{ErasureResponse, UserDataExport},
+    services::PrivacyService,
+    utils::{ApiError, ApiResult, MessageResponse, Permissions, errors::HttpError},
+};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+
+/// Rutas bajo `/users/me`; se combinan con las de usuarios.
+pub fn privacy_routes(state: AppState) -> Router {
+    let service = state.privacy_service.clone();
+    Router::new()
+        .route("/me/export", get(export_myself))
+        .route("/me/erasure", post(request_erasure).delete(cancel_erasure))
+        .with_state(service)
+}
+
+fn user_id(claims_user_id: &str) -> Result<i64, ApiError> {
+    claims_user_id
+        .parse()
+        .map_err(|_| HttpError::bad_request("Id de usuario inválido"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/export",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Datos personales del usuario autenticado", body = UserDataExport),
+        (status = 400, description = "ID de usuario inválido", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn export_myself(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<PrivacyService>>,
+) -> Result<Response, ApiError> {
+    claims.require_permission(Permissions::READ_MYSELF)?;
+    let id = user_id(&claims.user_id)?;
+    let export = service.export(id).await?;
+    let disposition = format!("attachment; filename=\"r-auth-export-{}.json\"", id);
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_DISPOSITION, disposition)],
+        Json(export),
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/erasure",
+    tag = "Users",
+    responses(
+        (status = 202, description = "Baja programada; se puede cancelar hasta la fecha indicada", body = ErasureResponse),
+        (status = 409, description = "Ya hay una baja pendiente", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn request_erasure(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<PrivacyService>>,
+) -> ApiResult<ErasureResponse> {
+    claims.require_permission(Permissions::DELETE_MYSELF)?;
+    let id = user_id(&claims.user_id)?;
+    let response = service.request_erasure(id).await?;
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/me/erasure",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Baja cancelada", body = MessageResponse),
+        (status = 404, description = "No hay una baja pendiente", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn cancel_erasure(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<PrivacyService>>,
+) -> ApiResult<MessageResponse> {
+    claims.require_permission(Permissions::DELETE_MYSELF)?;
+    let id = user_id(&claims.user_id)?;
+    service.cancel_erasure(id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse {
+            message: "Baja cancelada correctamente".to_string(),
+        }),
+    ))
+}
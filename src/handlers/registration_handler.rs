@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    database::models::{OneResult, dto::RegisterDto, entities::user::User},
+    services::RegistrationService,
+    utils::{ApiResult, errors::HttpError},
+};
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+
+/// Ruta pública bajo `/users`; se combina con las de usuarios.
+pub fn registration_routes(state: AppState) -> Router {
+    let service = state.registration_service.clone();
+    Router::new()
+        .route("/register", post(register))
+        .with_state(service)
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/register",
+    tag = "Users",
+    request_body = RegisterDto,
+    responses(
+        (status = 201, description = "Cuenta creada con los permisos por defecto", body = OneResult<User>),
+        (status = 400, description = "Datos inválidos, username reservado o CAPTCHA inválido", body = HttpError),
+        (status = 403, description = "Registro deshabilitado o dominio no permitido", body = HttpError),
+        (status = 409, description = "El username o el email ya están en uso", body = HttpError),
+        (status = 502, description = "No se pudo verificar el CAPTCHA", body = HttpError)
+    )
+)]
+pub async fn register(
+    State(service): State<Arc<RegistrationService>>,
+    Json(payload): Json<RegisterDto>,
+) -> ApiResult<OneResult<User>> {
+    let user = service.register(payload).await?;
+    Ok((StatusCode::CREATED, Json(OneResult { result: user })))
+}
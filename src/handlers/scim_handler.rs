@@ -0,0 +1,331 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    database::models::dto::{
+        ScimGroup, ScimListQuery, ScimListResponse, ScimPatchRequest, ScimUser,
+    },
+    scim::{SCHEMA_SERVICE_PROVIDER_CONFIG, ScimError, ScimJson, ScimResult},
+    services::{SCIM_MAX_RESULTS, ScimService},
+};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::Response,
+    routing::get,
+};
+use serde_json::{Value, json};
+
+pub fn scim_routes(state: AppState) -> Router {
+    let service = state.scim_service.clone();
+    Router::new()
+        .route("/ServiceProviderConfig", get(service_provider_config))
+        .route("/Users", get(list_users).post(create_user))
+        .route(
+            "/Users/{id}",
+            get(get_user)
+                .put(replace_user)
+                .patch(patch_user)
+                .delete(delete_user),
+        )
+        .route("/Groups", get(list_groups).post(create_group))
+        .route(
+            "/Groups/{id}",
+            get(get_group)
+                .put(replace_group)
+                .patch(patch_group)
+                .delete(delete_group),
+        )
+        .layer(middleware::from_fn_with_state(service.clone(), scim_auth))
+        .with_state(service)
+}
+
+/// Todas las rutas SCIM exigen el token de aprovisionamiento.
+async fn scim_auth(
+    State(service): State<Arc<ScimService>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ScimError> {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok());
+    service.authorize(authorization)?;
+    Ok(next.run(request).await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/ServiceProviderConfig",
+    tag = "SCIM",
+    responses(
+        (status = 200, description = "Capacidades SCIM soportadas"),
+        (status = 401, description = "Token de aprovisionamiento inválido")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn service_provider_config() -> ScimJson<Value> {
+    ScimJson(
+        StatusCode::OK,
+        json!({
+            "schemas": [SCHEMA_SERVICE_PROVIDER_CONFIG],
+            "patch": { "supported": true },
+            "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+            "filter": { "supported": true, "maxResults": SCIM_MAX_RESULTS },
+            "changePassword": { "supported": true },
+            "sort": { "supported": false },
+            "etag": { "supported": false },
+            "authenticationSchemes": [{
+                "type": "oauthbearertoken",
+                "name": "Bearer token",
+                "description": "Token dedicado al aprovisionamiento (SCIM_BEARER_TOKEN)"
+            }]
+        }),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users",
+    tag = "SCIM",
+    params(ScimListQuery),
+    responses(
+        (status = 200, description = "Usuarios que cumplen el filtro", body = ScimListResponse<ScimUser>),
+        (status = 400, description = "Filtro inválido"),
+        (status = 401, description = "Token de aprovisionamiento inválido")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn list_users(
+    State(service): State<Arc<ScimService>>,
+    Query(query): Query<ScimListQuery>,
+) -> ScimResult<ScimListResponse<ScimUser>> {
+    let result = service.list_users(query).await?;
+    Ok(ScimJson(StatusCode::OK, result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Users",
+    tag = "SCIM",
+    request_body = ScimUser,
+    responses(
+        (status = 201, description = "Usuario aprovisionado", body = ScimUser),
+        (status = 400, description = "Datos inválidos"),
+        (status = 409, description = "El usuario ya existe")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn create_user(
+    State(service): State<Arc<ScimService>>,
+    Json(payload): Json<ScimUser>,
+) -> ScimResult<ScimUser> {
+    let user = service.create_user(payload).await?;
+    Ok(ScimJson(StatusCode::CREATED, user))
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del usuario")),
+    responses(
+        (status = 200, description = "Usuario", body = ScimUser),
+        (status = 404, description = "Usuario no encontrado")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn get_user(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+) -> ScimResult<ScimUser> {
+    let user = service.get_user(&id).await?;
+    Ok(ScimJson(StatusCode::OK, user))
+}
+
+#[utoipa::path(
+    put,
+    path = "/scim/v2/Users/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del usuario")),
+    request_body = ScimUser,
+    responses(
+        (status = 200, description = "Usuario reemplazado", body = ScimUser),
+        (status = 404, description = "Usuario no encontrado"),
+        (status = 409, description = "userName o email en uso")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn replace_user(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<ScimUser>,
+) -> ScimResult<ScimUser> {
+    let user = service.replace_user(&id, payload).await?;
+    Ok(ScimJson(StatusCode::OK, user))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Users/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del usuario")),
+    request_body = ScimPatchRequest,
+    responses(
+        (status = 200, description = "Usuario modificado", body = ScimUser),
+        (status = 400, description = "Operación inválida"),
+        (status = 404, description = "Usuario no encontrado")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn patch_user(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<ScimPatchRequest>,
+) -> ScimResult<ScimUser> {
+    let user = service.patch_user(&id, payload).await?;
+    Ok(ScimJson(StatusCode::OK, user))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Users/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del usuario")),
+    responses(
+        (status = 204, description = "Usuario eliminado"),
+        (status = 404, description = "Usuario no encontrado")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn delete_user(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    service.delete_user(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups",
+    tag = "SCIM",
+    params(ScimListQuery),
+    responses(
+        (status = 200, description = "Grupos que cumplen el filtro", body = ScimListResponse<ScimGroup>),
+        (status = 400, description = "Filtro inválido"),
+        (status = 401, description = "Token de aprovisionamiento inválido")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn list_groups(
+    State(service): State<Arc<ScimService>>,
+    Query(query): Query<ScimListQuery>,
+) -> ScimResult<ScimListResponse<ScimGroup>> {
+    let result = service.list_groups(query).await?;
+    Ok(ScimJson(StatusCode::OK, result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Groups",
+    tag = "SCIM",
+    request_body = ScimGroup,
+    responses(
+        (status = 201, description = "Grupo creado", body = ScimGroup),
+        (status = 400, description = "Datos inválidos"),
+        (status = 409, description = "El grupo ya existe")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn create_group(
+    State(service): State<Arc<ScimService>>,
+    Json(payload): Json<ScimGroup>,
+) -> ScimResult<ScimGroup> {
+    let group = service.create_group(payload).await?;
+    Ok(ScimJson(StatusCode::CREATED, group))
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del grupo")),
+    responses(
+        (status = 200, description = "Grupo", body = ScimGroup),
+        (status = 404, description = "Grupo no encontrado")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn get_group(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+) -> ScimResult<ScimGroup> {
+    let group = service.get_group(&id).await?;
+    Ok(ScimJson(StatusCode::OK, group))
+}
+
+#[utoipa::path(
+    put,
+    path = "/scim/v2/Groups/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del grupo")),
+    request_body = ScimGroup,
+    responses(
+        (status = 200, description = "Grupo reemplazado", body = ScimGroup),
+        (status = 404, description = "Grupo no encontrado"),
+        (status = 409, description = "displayName en uso")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn replace_group(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<ScimGroup>,
+) -> ScimResult<ScimGroup> {
+    let group = service.replace_group(&id, payload).await?;
+    Ok(ScimJson(StatusCode::OK, group))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Groups/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del grupo")),
+    request_body = ScimPatchRequest,
+    responses(
+        (status = 200, description = "Grupo modificado", body = ScimGroup),
+        (status = 400, description = "Operación inválida"),
+        (status = 404, description = "Grupo no encontrado")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn patch_group(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<ScimPatchRequest>,
+) -> ScimResult<ScimGroup> {
+    let group = service.patch_group(&id, payload).await?;
+    Ok(ScimJson(StatusCode::OK, group))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Groups/{id}",
+    tag = "SCIM",
+    params(("id" = String, Path, description = "Id del grupo")),
+    responses(
+        (status = 204, description = "Grupo eliminado"),
+        (status = 404, description = "Grupo no encontrado")
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn delete_group(
+    State(service): State<Arc<ScimService>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    service.delete_group(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::AuthenticatedClaims,
+    database::models::dto::{ExportQuery, ImportQuery, ImportReport},
+    services::UserTransferService,
+    utils::{ApiError, ApiResult, Permissions, errors::HttpError},
+};
+use axum::{
+    Json, Router,
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+
+/// Un archivo de decenas de miles de usuarios supera el límite por defecto
+/// de axum (2 MB).
+const IMPORT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Rutas bajo `/users`; se combinan con las de usuarios.
+pub fn transfer_routes(state: AppState) -> Router {
+    let service = state.user_transfer_service.clone();
+    Router::new()
+        .route(
+            "/import",
+            post(import_users).layer(DefaultBodyLimit::max(IMPORT_MAX_BYTES)),
+        )
+        .route("/export", get(export_users))
+        .with_state(service)
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/import",
+    tag = "Users",
+    params(ImportQuery),
+    request_body(content = String, description = "Archivo CSV con encabezado o NDJSON", content_type = "text/csv"),
+    responses(
+        (status = 200, description = "Reporte con los errores por línea", body = ImportReport),
+        (status = 400, description = "Formato o encabezado inválido", body = HttpError),
+        (status = 403, description = "Permisos insuficientes", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn import_users(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<UserTransferService>>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> ApiResult<ImportReport> {
+    claims.require_permission(Permissions::ADMIN)?;
+    let report = service.import(&body, query.format, query.dry_run).await?;
+    Ok((StatusCode::OK, Json(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/export",
+    tag = "Users",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Usuarios en CSV o NDJSON, sin contraseñas", content_type = "text/csv"),
+        (status = 403, description = "Permisos insuficientes", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn export_users(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<UserTransferService>>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    claims.require_permission(Permissions::ADMIN)?;
+    let stream = service.export(query.format, query.status).await?;
+    let disposition = format!(
+        "attachment; filename=\"r-auth-users.{}\"",
+        query.format.extension()
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
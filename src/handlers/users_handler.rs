@@ -2,7 +2,8 @@ use std::sync::Arc;
 
 use crate::{
     AppState,
-    auth::AuthenticatedClaims,
+    auth::{AuthenticatedClaims, PasswordChangeClaims, login_response, logout_response},
+    config::get_config,
     database::models::{
         FindQuery, FindResult, OneResult,
         dto::{ChangePasswordDto, CreateUserDto, LoginRequest, LoginResponse, UpdateUserDto},
@@ -15,6 +16,7 @@ use axum::{
     Json, Router,
     extract::{Path, Query, State},
     http::StatusCode,
+    response::Response,
     routing::{delete, get, patch, post, put},
 };
 
@@ -26,11 +28,14 @@ pub fn users_routes(state: AppState) -> Router {
         .route("/me", get(get_myinfo))
         .route("/{id}", get(get_user))
         .route("/login", post(login))
+        .route("/logout", post(logout))
         .route("/me", patch(update_myself))
         .route("/{id}", patch(update_user))
         .route("/change-password", put(change_password))
         .route("/inactive/me", put(inactive_myself))
         .route("/inactive/{id}", put(inactive_user))
+        .route("/reactivate/{id}", put(reactivate_user))
+        .route("/restore/{id}", put(restore_user))
         .route("/me", delete(delete_myself))
         .route("/{id}", delete(delete_user))
         .with_state(service)
@@ -42,16 +47,29 @@ pub fn users_routes(state: AppState) -> Router {
     tag = "Users",
     request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login exitoso", body = LoginResponse),
-        (status = 401, description = "Credenciales inválidas", body = HttpError)
+        (status = 200, description = "Login exitoso; en modo cookie el JWT va en la cookie de sesión", body = LoginResponse),
+        (status = 401, description = "Credenciales inválidas", body = HttpError),
+        (status = 403, description = "Usuario inactivo", body = HttpError)
     )
 )]
 pub async fn login(
     State(service): State<Arc<UsersService>>,
     Json(payload): Json<LoginRequest>,
-) -> ApiResult<LoginResponse> {
+) -> Result<Response, ApiError> {
     let token = service.login(payload).await?;
-    Ok((StatusCode::OK, Json(LoginResponse { token })))
+    Ok(login_response(token, &get_config().session))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/logout",
+    tag = "Users",
+    responses(
+        (status = 204, description = "Cookies de sesión eliminadas")
+    )
+)]
+pub async fn logout() -> Response {
+    logout_response(&get_config().session)
 }
 
 #[utoipa::path(
@@ -177,7 +195,8 @@ pub async fn update_user(
     request_body = UpdateUserDto,
     responses(
         (status = 200, description = "Usuario autenticado actualizado", body = OneResult<User>),
-        (status = 400, description = "Datos inválidos", body = HttpError)
+        (status = 400, description = "Datos inválidos", body = HttpError),
+        (status = 403, description = "Atributo que solo modifica un administrador", body = HttpError)
     ),
     security(("bearerAuth" = []))
 )]
@@ -187,15 +206,22 @@ pub async fn update_myself(
     Json(mut payload): Json<UpdateUserDto>,
 ) -> ApiResult<OneResult<User>> {
     claims.require_permission(Permissions::UPDATE_MYSELF)?;
+    if payload.must_change_password.is_some() {
+        return Err(HttpError::forbbiden(
+            "Solo un administrador puede exigir el cambio de contraseña",
+        ));
+    }
     let id: i64 = claims
         .user_id
         .parse()
         .map_err(|_| HttpError::bad_request("Id de usuario inválido"))?;
     payload.id = Some(id);
-    let user = service.update(payload).await?;
+    let user = service.update_myself(payload).await?;
     Ok((StatusCode::OK, Json(OneResult { result: user })))
 }
 
+/// Es la única ruta que aceptan los usuarios con la contraseña vencida o con
+/// `must_change_password`; al cambiarla se limpia la marca.
 #[utoipa::path(
     put,
     path = "/users/change-password",
@@ -208,7 +234,7 @@ pub async fn update_myself(
     security(("bearerAuth" = []))
 )]
 pub async fn change_password(
-    AuthenticatedClaims(claims): AuthenticatedClaims,
+    PasswordChangeClaims(claims): PasswordChangeClaims,
     State(service): State<Arc<UsersService>>,
     Json(payload): Json<ChangePasswordDto>,
 ) -> ApiResult<MessageResponse> {
@@ -278,6 +304,64 @@ pub async fn inactive_myself(
     ))
 }
 
+#[utoipa::path(
+    put,
+    path = "/users/reactivate/{id}",
+    tag = "Users",
+    params(
+        ("id" = i64, Path, description = "ID del usuario inactivo")
+    ),
+    responses(
+        (status = 200, description = "Usuario reactivado", body = MessageResponse),
+        (status = 404, description = "Usuario no encontrado", body = HttpError),
+        (status = 409, description = "El usuario no está inactivo", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn reactivate_user(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<UsersService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<MessageResponse> {
+    claims.require_permission(Permissions::UPDATE_USERS)?;
+    service.reactivate(id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse {
+            message: "Usuario reactivado correctamente".to_string(),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/restore/{id}",
+    tag = "Users",
+    params(
+        ("id" = i64, Path, description = "ID del usuario eliminado")
+    ),
+    responses(
+        (status = 200, description = "Usuario restaurado", body = MessageResponse),
+        (status = 404, description = "Usuario no encontrado o ya purgado", body = HttpError),
+        (status = 409, description = "El usuario no está eliminado", body = HttpError)
+    ),
+    security(("bearerAuth" = []))
+)]
+pub async fn restore_user(
+    AuthenticatedClaims(claims): AuthenticatedClaims,
+    State(service): State<Arc<UsersService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<MessageResponse> {
+    claims.require_permission(Permissions::DELETE_USERS)?;
+    service.restore(id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse {
+            message: "Usuario restaurado correctamente".to_string(),
+        }),
+    ))
+}
+
 #[utoipa::path(
     delete,
     path = "/users/{id}",
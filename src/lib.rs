@@ -1,7 +1,11 @@
 pub mod auth;
+pub mod captcha;
+pub mod cli;
 pub mod config;
 pub mod database;
 pub mod handlers;
+pub mod mailer;
+pub mod scim;
 pub mod services;
 pub mod swagger;
 pub mod utils;
@@ -19,13 +23,72 @@ use utoipa::{OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    database::connection::{GLOBAL_DB_POOL, initialize_global_db_pool},
-    services::UsersService,
+    auth::backends::backends_from_config,
+    captcha::captcha_verifier_from_config,
+    database::connection::{GLOBAL_DB_POOL, PgPool, initialize_global_db_pool},
+    mailer::mailer_from_config,
+    services::{
+        AttributeService, InvitationService, MagicLinkService, OidcService, PrivacyService,
+        RegistrationService, ScimService, UserTransferService, UsersService, spawn_user_purge,
+    },
 };
 
 #[derive(Clone)]
 pub struct AppState {
     pub users_service: Arc<UsersService>,
+    pub magic_link_service: Arc<MagicLinkService>,
+    pub oidc_service: Arc<OidcService>,
+    pub scim_service: Arc<ScimService>,
+    pub privacy_service: Arc<PrivacyService>,
+    pub invitation_service: Arc<InvitationService>,
+    pub registration_service: Arc<RegistrationService>,
+    pub attribute_service: Arc<AttributeService>,
+    pub user_transfer_service: Arc<UserTransferService>,
+}
+
+impl AppState {
+    /// Construye los servicios según la configuración cargada.
+    pub fn new(pool: &PgPool) -> Self {
+        let cfg = config::get_config();
+        let mailer = mailer_from_config();
+
+        AppState {
+            users_service: Arc::new(UsersService::with_backends(
+                pool,
+                backends_from_config(pool),
+            )),
+            magic_link_service: Arc::new(MagicLinkService::new(pool, mailer.clone())),
+            invitation_service: Arc::new(InvitationService::new(pool, mailer)),
+            oidc_service: Arc::new(OidcService::new(pool, cfg.oidc_providers.clone())),
+            scim_service: Arc::new(ScimService::new(pool, cfg.scim.bearer_token.clone())),
+            privacy_service: Arc::new(PrivacyService::new(pool)),
+            registration_service: Arc::new(RegistrationService::new(
+                pool,
+                cfg.signup.clone(),
+                captcha_verifier_from_config(),
+            )),
+            attribute_service: Arc::new(AttributeService::new(pool)),
+            user_transfer_service: Arc::new(UserTransferService::new(pool)),
+        }
+    }
+}
+
+/// Router completo de la aplicación. Los extractores autenticados usan
+/// `GLOBAL_DB_POOL`, que debe estar inicializado antes de servirlo.
+pub fn build_router(state: AppState) -> Router {
+    let openapi = swagger::ApiDoc::openapi();
+
+    Router::new()
+        .route("/", get(root))
+        .route("/.well-known/jwks.json", get(handlers::auth_handler::jwks))
+        .route("/metrics", get(handlers::metrics_handler::metrics))
+        .layer(TraceLayer::new_for_http())
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", openapi))
+        .nest(
+            "/scim/v2",
+            handlers::scim_handler::scim_routes(state.clone()),
+        )
+        .nest("/api", handlers::api_routes(state))
 }
 
 pub async fn run_app() -> Result<(), Box<dyn std::error::Error>> {
@@ -40,7 +103,7 @@ pub async fn run_app() -> Result<(), Box<dyn std::error::Error>> {
     let database_url = &cfg.db.database_url;
 
     println!("{}", "Conectando a la base de datos...".yellow());
-    initialize_global_db_pool(&database_url).await?;
+    initialize_global_db_pool(database_url).await?;
     println!("{}", "Conectado a la base de datos.".green());
 
     let pool = match GLOBAL_DB_POOL.get() {
@@ -51,16 +114,13 @@ pub async fn run_app() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let users_service = Arc::new(UsersService::new(&pool));
-
-    let state = AppState { users_service };
-    let openapi = swagger::ApiDoc::openapi();
-
-    let app = Router::new()
-        .route("/", get(root))
-        .layer(TraceLayer::new_for_http())
-        .merge(SwaggerUi::new("/docs").url("/openapi.json", openapi.clone()))
-        .nest("/api", handlers::api_routes(state));
+    let state = AppState::new(pool);
+    spawn_user_purge(
+        state.users_service.clone(),
+        state.privacy_service.clone(),
+        &cfg.user_purge,
+    );
+    let app = build_router(state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3032").await.unwrap();
     println!(
@@ -68,7 +128,8 @@ pub async fn run_app() -> Result<(), Box<dyn std::error::Error>> {
         format!("Servidor corriendo en el puerto {}", "3032".yellow()).green()
     );
 
-    Ok(axum::serve(listener, app).await.unwrap())
+    axum::serve(listener, app).await.unwrap();
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -0,0 +1,85 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::config::{MailerKind, get_config};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Email {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailerError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// Punto de extensión para el envío de correos. Cualquier transporte (SMTP,
+/// API de un proveedor, etc.) puede implementarlo.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), MailerError>;
+}
+
+/// Imprime los correos por la salida estándar. Útil en desarrollo.
+pub struct StdoutMailer;
+
+#[async_trait]
+impl Mailer for StdoutMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        println!(
+            "From: {}\nTo: {}\nSubject: {}\n\n{}\n",
+            email.from, email.to, email.subject, email.body
+        );
+        Ok(())
+    }
+}
+
+/// Agrega cada correo como una línea JSON al archivo configurado.
+pub struct FileMailer {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileMailer {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileMailer {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        let line = serde_json::to_string(&email)?;
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+}
+
+pub fn mailer_from_config() -> Arc<dyn Mailer> {
+    let config = get_config();
+    match &config.mailer.kind {
+        MailerKind::Stdout => Arc::new(StdoutMailer),
+        MailerKind::File(path) => Arc::new(FileMailer::new(path)),
+    }
+}
@@ -1,6 +1,10 @@
-use r_auth_api::run_app;
+use r_auth_api::{cli, run_app};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    run_app().await
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None | Some("serve") => run_app().await,
+        Some(_) => cli::run(&args).await,
+    }
 }
@@ -0,0 +1,526 @@
+//! Filtros SCIM (RFC 7644, sección 3.4.2.2) y su traducción a SQL sobre un
+//! conjunto cerrado de columnas.
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::types::ToSql;
+
+use super::ScimError;
+use crate::{database::models::entities::user_status::UserStatus, utils::escape_like};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScimValue {
+    String(String),
+    Bool(bool),
+    Number(f64),
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScimFilter {
+    And(Box<ScimFilter>, Box<ScimFilter>),
+    Or(Box<ScimFilter>, Box<ScimFilter>),
+    Not(Box<ScimFilter>),
+    Present(String),
+    Compare {
+        attribute: String,
+        op: CompareOp,
+        value: ScimValue,
+    },
+    /// `emails[type eq "work"]`: el filtro interno se aplica a los subatributos.
+    ValuePath {
+        attribute: String,
+        filter: Box<ScimFilter>,
+    },
+}
+
+/// Ruta de un PATCH: `members`, `name.givenName` o `members[value eq "2"]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScimPath {
+    pub attribute: String,
+    pub filter: Option<ScimFilter>,
+    pub sub_attribute: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Word(String),
+    Str(String),
+}
+
+const SCHEMA_PREFIXES: [&str; 2] = [
+    "urn:ietf:params:scim:schemas:core:2.0:user:",
+    "urn:ietf:params:scim:schemas:core:2.0:group:",
+];
+
+/// Normaliza un nombre de atributo: minúsculas y sin el prefijo del esquema.
+pub fn normalize_attribute(attribute: &str) -> String {
+    let lower = attribute.to_lowercase();
+    SCHEMA_PREFIXES
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .map(str::to_string)
+        .unwrap_or(lower)
+}
+
+impl ScimFilter {
+    pub fn parse(input: &str) -> Result<ScimFilter, ScimError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let filter = parser.expression()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(ScimError::invalid_filter(
+                "Contenido inesperado al final del filtro",
+            ));
+        }
+        Ok(filter)
+    }
+}
+
+impl ScimPath {
+    pub fn parse(input: &str) -> Result<ScimPath, ScimError> {
+        let input = input.trim();
+        let (head, tail) = match input.find('[') {
+            Some(start) => {
+                let end = input
+                    .rfind(']')
+                    .filter(|end| *end > start)
+                    .ok_or_else(|| ScimError::invalid_path("Filtro de valor sin cerrar"))?;
+                let filter = ScimFilter::parse(&input[start + 1..end])?;
+                let sub = input[end + 1..].trim_start_matches('.');
+                (
+                    (&input[..start], Some(filter)),
+                    (!sub.is_empty()).then(|| normalize_attribute(sub)),
+                )
+            }
+            None => ((input, None), None),
+        };
+        let ((attribute, filter), sub_attribute) = (head, tail);
+        let attribute = normalize_attribute(attribute);
+
+        // `name.givenName` sin filtro: separa el subatributo.
+        let (attribute, sub_attribute) = match (filter.is_none(), attribute.split_once('.')) {
+            (true, Some((attr, sub))) => (attr.to_string(), Some(sub.to_string())),
+            _ => (attribute, sub_attribute),
+        };
+
+        if attribute.is_empty() {
+            return Err(ScimError::invalid_path("Ruta vacía"));
+        }
+        Ok(ScimPath {
+            attribute,
+            filter,
+            sub_attribute,
+        })
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ScimError> {
+        match self.next() {
+            Some(t) if t == expected => Ok(()),
+            _ => Err(ScimError::invalid_filter("Paréntesis desbalanceados")),
+        }
+    }
+
+    fn expression(&mut self) -> Result<ScimFilter, ScimError> {
+        let mut left = self.term()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let right = self.term()?;
+            left = ScimFilter::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<ScimFilter, ScimError> {
+        let mut left = self.factor()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let right = self.factor()?;
+            left = ScimFilter::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<ScimFilter, ScimError> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            self.expect(Token::Open)?;
+            let inner = self.expression()?;
+            self.expect(Token::Close)?;
+            return Ok(ScimFilter::Not(Box::new(inner)));
+        }
+
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.expression()?;
+                self.expect(Token::Close)?;
+                Ok(inner)
+            }
+            Some(Token::Word(attribute)) => {
+                let attribute = normalize_attribute(&attribute);
+                if self.peek() == Some(&Token::OpenBracket) {
+                    self.pos += 1;
+                    let inner = self.expression()?;
+                    self.expect(Token::CloseBracket)?;
+                    return Ok(ScimFilter::ValuePath {
+                        attribute,
+                        filter: Box::new(inner),
+                    });
+                }
+
+                let op = match self.next() {
+                    Some(Token::Word(op)) => op.to_lowercase(),
+                    _ => return Err(ScimError::invalid_filter("Se esperaba un operador")),
+                };
+                let op = match op.as_str() {
+                    "pr" => return Ok(ScimFilter::Present(attribute)),
+                    "eq" => CompareOp::Eq,
+                    "ne" => CompareOp::Ne,
+                    "co" => CompareOp::Co,
+                    "sw" => CompareOp::Sw,
+                    "ew" => CompareOp::Ew,
+                    "gt" => CompareOp::Gt,
+                    "ge" => CompareOp::Ge,
+                    "lt" => CompareOp::Lt,
+                    "le" => CompareOp::Le,
+                    other => {
+                        return Err(ScimError::invalid_filter(&format!(
+                            "Operador desconocido {}",
+                            other
+                        )));
+                    }
+                };
+
+                let value = match self.next() {
+                    Some(Token::Str(s)) => ScimValue::String(s),
+                    Some(Token::Word(w)) => match w.to_lowercase().as_str() {
+                        "true" => ScimValue::Bool(true),
+                        "false" => ScimValue::Bool(false),
+                        "null" => ScimValue::Null,
+                        _ => ScimValue::Number(w.parse().map_err(|_| {
+                            ScimError::invalid_filter(&format!("Valor inválido {}", w))
+                        })?),
+                    },
+                    _ => {
+                        return Err(ScimError::invalid_filter(
+                            "Se esperaba un valor de comparación",
+                        ));
+                    }
+                };
+
+                Ok(ScimFilter::Compare {
+                    attribute,
+                    op,
+                    value,
+                })
+            }
+            _ => Err(ScimError::invalid_filter("Se esperaba un atributo")),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ScimError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '[' => {
+                chars.next();
+                tokens.push(Token::OpenBracket);
+            }
+            ']' => {
+                chars.next();
+                tokens.push(Token::CloseBracket);
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => value.push(escaped),
+                            None => return Err(ScimError::invalid_filter("Cadena sin terminar")),
+                        },
+                        Some(ch) => value.push(ch),
+                        None => return Err(ScimError::invalid_filter("Cadena sin terminar")),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || matches!(ch, '(' | ')' | '[' | ']' | '"') {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Tipo de una columna filtrable.
+#[derive(Debug, Clone, Copy)]
+pub enum ColumnKind {
+    /// Texto comparado sin distinguir mayúsculas.
+    Text,
+    /// Texto comparado de forma exacta.
+    ExactText,
+    Id,
+    Timestamp,
+    /// `active`, derivado de `status = 1`.
+    Active,
+    /// Pertenencia a un grupo: `EXISTS` sobre `group_members`.
+    Member,
+    /// Subatributo sin columna propia (ej. `emails.type`): siempre verdadero.
+    Ignored,
+}
+
+pub type SqlParams = Vec<Box<dyn ToSql + Sync + Send>>;
+
+/// Traduce el filtro a una expresión SQL. `column` resuelve cada atributo
+/// normalizado a su expresión SQL y tipo; los atributos no soportados
+/// devuelven `None` y producen un error `invalidFilter`.
+pub fn to_sql<F>(
+    filter: &ScimFilter,
+    column: &F,
+    params: &mut SqlParams,
+) -> Result<String, ScimError>
+where
+    F: Fn(&str) -> Option<(&'static str, ColumnKind)>,
+{
+    let resolve = |attribute: &str| {
+        column(attribute).ok_or_else(|| {
+            ScimError::invalid_filter(&format!("Atributo de filtro no soportado: {}", attribute))
+        })
+    };
+
+    match filter {
+        ScimFilter::And(a, b) => Ok(format!(
+            "({} AND {})",
+            to_sql(a, column, params)?,
+            to_sql(b, column, params)?
+        )),
+        ScimFilter::Or(a, b) => Ok(format!(
+            "({} OR {})",
+            to_sql(a, column, params)?,
+            to_sql(b, column, params)?
+        )),
+        ScimFilter::Not(a) => Ok(format!("(NOT {})", to_sql(a, column, params)?)),
+        ScimFilter::ValuePath { attribute, filter } => {
+            let prefixed = prefix_attributes(filter, attribute);
+            to_sql(&prefixed, column, params)
+        }
+        ScimFilter::Present(attribute) => {
+            let (expr, kind) = resolve(attribute)?;
+            Ok(match kind {
+                ColumnKind::Text | ColumnKind::ExactText => {
+                    format!("({} IS NOT NULL AND {} <> '')", expr, expr)
+                }
+                ColumnKind::Member => {
+                    format!("EXISTS ({})", expr.replace("= $USER", "IS NOT NULL"))
+                }
+                ColumnKind::Ignored | ColumnKind::Active => "TRUE".to_string(),
+                _ => format!("{} IS NOT NULL", expr),
+            })
+        }
+        ScimFilter::Compare {
+            attribute,
+            op,
+            value,
+        } => {
+            let (expr, kind) = resolve(attribute)?;
+            compare_sql(expr, kind, *op, value, params)
+        }
+    }
+}
+
+fn prefix_attributes(filter: &ScimFilter, parent: &str) -> ScimFilter {
+    let prefix = |a: &str| format!("{}.{}", parent, a);
+    match filter {
+        ScimFilter::And(a, b) => ScimFilter::And(
+            Box::new(prefix_attributes(a, parent)),
+            Box::new(prefix_attributes(b, parent)),
+        ),
+        ScimFilter::Or(a, b) => ScimFilter::Or(
+            Box::new(prefix_attributes(a, parent)),
+            Box::new(prefix_attributes(b, parent)),
+        ),
+        ScimFilter::Not(a) => ScimFilter::Not(Box::new(prefix_attributes(a, parent))),
+        ScimFilter::Present(a) => ScimFilter::Present(prefix(a)),
+        ScimFilter::Compare {
+            attribute,
+            op,
+            value,
+        } => ScimFilter::Compare {
+            attribute: prefix(attribute),
+            op: *op,
+            value: value.clone(),
+        },
+        ScimFilter::ValuePath { .. } => filter.clone(),
+    }
+}
+
+fn compare_sql(
+    expr: &str,
+    kind: ColumnKind,
+    op: CompareOp,
+    value: &ScimValue,
+    params: &mut SqlParams,
+) -> Result<String, ScimError> {
+    let invalid = || ScimError::invalid_filter(&format!("Comparación no soportada sobre {}", expr));
+
+    match kind {
+        ColumnKind::Ignored => Ok("TRUE".to_string()),
+        ColumnKind::Active => {
+            let active = match value {
+                ScimValue::Bool(b) => *b,
+                _ => return Err(invalid()),
+            };
+            let active = match op {
+                CompareOp::Eq => active,
+                CompareOp::Ne => !active,
+                _ => return Err(invalid()),
+            };
+            let op = if active { "=" } else { "<>" };
+            Ok(format!("(status {} {})", op, UserStatus::Active.code()))
+        }
+        ColumnKind::Id | ColumnKind::Member => {
+            let id: i64 = match value {
+                ScimValue::String(s) => s.parse().map_err(|_| invalid())?,
+                ScimValue::Number(n) => *n as i64,
+                _ => return Err(invalid()),
+            };
+            params.push(Box::new(id));
+            let placeholder = format!("${}", params.len());
+            if let ColumnKind::Member = kind {
+                return match op {
+                    CompareOp::Eq => {
+                        Ok(format!("EXISTS ({})", expr.replace("$USER", &placeholder)))
+                    }
+                    _ => Err(invalid()),
+                };
+            }
+            Ok(format!(
+                "{} {} {}",
+                expr,
+                ordering_operator(op).ok_or_else(invalid)?,
+                placeholder
+            ))
+        }
+        ColumnKind::Timestamp => {
+            let ts: DateTime<Utc> = match value {
+                ScimValue::String(s) => DateTime::parse_from_rfc3339(s)
+                    .map_err(|_| invalid())?
+                    .with_timezone(&Utc),
+                _ => return Err(invalid()),
+            };
+            params.push(Box::new(ts));
+            Ok(format!(
+                "{} {} ${}",
+                expr,
+                ordering_operator(op).ok_or_else(invalid)?,
+                params.len()
+            ))
+        }
+        ColumnKind::Text | ColumnKind::ExactText => {
+            let text = match value {
+                ScimValue::String(s) => s.clone(),
+                ScimValue::Null => {
+                    return match op {
+                        CompareOp::Eq => Ok(format!("{} IS NULL", expr)),
+                        CompareOp::Ne => Ok(format!("{} IS NOT NULL", expr)),
+                        _ => Err(invalid()),
+                    };
+                }
+                _ => return Err(invalid()),
+            };
+            let insensitive = matches!(kind, ColumnKind::Text);
+            let like = if insensitive { "ILIKE" } else { "LIKE" };
+
+            let (sql_op, param) = match op {
+                CompareOp::Co => (like, format!("%{}%", escape_like(&text))),
+                CompareOp::Sw => (like, format!("{}%", escape_like(&text))),
+                CompareOp::Ew => (like, format!("%{}", escape_like(&text))),
+                _ => (ordering_operator(op).ok_or_else(invalid)?, text),
+            };
+            params.push(Box::new(param));
+            let placeholder = format!("${}", params.len());
+
+            Ok(match (insensitive, op) {
+                (_, CompareOp::Co | CompareOp::Sw | CompareOp::Ew) => {
+                    format!("{} {} {}", expr, sql_op, placeholder)
+                }
+                (true, _) => format!("lower({}) {} lower({})", expr, sql_op, placeholder),
+                (false, _) => format!("{} {} {}", expr, sql_op, placeholder),
+            })
+        }
+    }
+}
+
+fn ordering_operator(op: CompareOp) -> Option<&'static str> {
+    match op {
+        CompareOp::Eq => Some("="),
+        CompareOp::Ne => Some("<>"),
+        CompareOp::Gt => Some(">"),
+        CompareOp::Ge => Some(">="),
+        CompareOp::Lt => Some("<"),
+        CompareOp::Le => Some("<="),
+        _ => None,
+    }
+}
@@ -0,0 +1,124 @@
+//! Soporte del protocolo SCIM 2.0 (RFC 7643/7644): errores, respuestas con
+//! `application/scim+json` y filtros.
+
+pub mod filter;
+
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::utils::ApiError;
+
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+/// Extensión propia con los atributos personalizados del usuario.
+pub const SCHEMA_USER_ATTRIBUTES: &str = "urn:r-auth:params:scim:schemas:extension:2.0:User";
+pub const SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCHEMA_LIST_RESPONSE: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCHEMA_PATCH_OP: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const SCHEMA_ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCHEMA_SERVICE_PROVIDER_CONFIG: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+
+pub const CONTENT_TYPE: &str = "application/scim+json";
+
+/// Error con el formato que exige SCIM (`urn:...:Error`) en lugar de `HttpError`.
+#[derive(Debug)]
+pub struct ScimError {
+    pub status: StatusCode,
+    pub scim_type: Option<&'static str>,
+    pub detail: String,
+}
+
+impl ScimError {
+    pub fn new(status: StatusCode, scim_type: Option<&'static str>, detail: &str) -> Self {
+        ScimError {
+            status,
+            scim_type,
+            detail: detail.to_string(),
+        }
+    }
+
+    pub fn invalid_filter(detail: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidFilter"), detail)
+    }
+
+    pub fn invalid_path(detail: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidPath"), detail)
+    }
+
+    pub fn invalid_value(detail: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidValue"), detail)
+    }
+
+    pub fn invalid_syntax(detail: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidSyntax"), detail)
+    }
+
+    pub fn uniqueness(detail: &str) -> Self {
+        Self::new(StatusCode::CONFLICT, Some("uniqueness"), detail)
+    }
+
+    pub fn not_found(detail: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, None, detail)
+    }
+
+    pub fn unauthorized(detail: &str) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, None, detail)
+    }
+}
+
+impl From<ApiError> for ScimError {
+    fn from((status, Json(error)): ApiError) -> Self {
+        let detail = error
+            .errors
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("; ");
+        let scim_type = match status {
+            StatusCode::CONFLICT => Some("uniqueness"),
+            StatusCode::BAD_REQUEST => Some("invalidValue"),
+            _ => None,
+        };
+        ScimError {
+            status,
+            scim_type,
+            detail,
+        }
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let mut body = json!({
+            "schemas": [SCHEMA_ERROR],
+            "status": self.status.as_u16().to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = json!(scim_type);
+        }
+        ScimJson(self.status, body).into_response()
+    }
+}
+
+/// Respuesta JSON con `Content-Type: application/scim+json`.
+pub struct ScimJson<T>(pub StatusCode, pub T);
+
+impl<T: Serialize> IntoResponse for ScimJson<T> {
+    fn into_response(self) -> Response {
+        let mut response = (self.0, Json(self.1)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(CONTENT_TYPE),
+        );
+        response
+    }
+}
+
+pub type ScimResult<T> = Result<ScimJson<T>, ScimError>;
@@ -0,0 +1,293 @@
+use deadpool_postgres::GenericClient;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+use tokio_postgres::types::Json;
+
+use crate::{
+    database::{
+        connection::PgPool,
+        models::{
+            dto::AttributeDefinitionDto, entities::attribute_definition::AttributeDefinition,
+        },
+    },
+    utils::{
+        ApiError, commit_transaction, errors::HttpError, get_pg_client, get_transaction,
+        map_db_error, validate_dto,
+    },
+};
+
+static ATTRIBUTE_NAME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-z][a-z0-9_]{0,63}$").unwrap());
+
+const DEFINITION_COLUMNS: &str =
+    "name, type, required, include_in_token, user_editable, description, created_at";
+
+/// Quién modifica los atributos: el propio usuario solo puede tocar los
+/// marcados como `user_editable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeEditor {
+    Admin,
+    Owner,
+}
+
+/// Esquema de los atributos personalizados de usuario. Solo se aceptan en
+/// `users.attributes` las claves definidas aquí y con su tipo.
+pub struct AttributeService {
+    pool: PgPool,
+}
+
+impl AttributeService {
+    pub fn new(pool: &PgPool) -> Self {
+        AttributeService { pool: pool.clone() }
+    }
+
+    pub async fn list(&self) -> Result<Vec<AttributeDefinition>, ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        load_definitions(&client).await
+    }
+
+    /// Crea o reemplaza la definición. No se puede cambiar el tipo mientras
+    /// haya usuarios con un valor del tipo anterior.
+    pub async fn upsert(
+        &self,
+        name: &str,
+        dto: AttributeDefinitionDto,
+    ) -> Result<AttributeDefinition, ApiError> {
+        validate_dto(&dto)?;
+        if !ATTRIBUTE_NAME_RE.is_match(name) {
+            return Err(HttpError::bad_request(
+                "El nombre del atributo debe empezar con una letra minúscula y contener solo minúsculas, dígitos y _",
+            ));
+        }
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let conflicting = tx
+            .query_opt(
+                r#"
+                    SELECT 1 FROM users
+                    WHERE attributes ? $1 AND jsonb_typeof(attributes -> $1) <> $2
+                    LIMIT 1
+                "#,
+                &[&name, &dto.kind.as_str()],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando los valores del atributo", e))?;
+        if conflicting.is_some() {
+            return Err(HttpError::conflict(
+                "Hay usuarios con valores de otro tipo para este atributo",
+            ));
+        }
+
+        let row = tx
+            .query_one(
+                &format!(
+                    r#"
+                        INSERT INTO user_attribute_definitions
+                            (name, type, required, include_in_token, user_editable, description)
+                        VALUES ($1, $2, $3, $4, $5, $6)
+                        ON CONFLICT (name) DO UPDATE SET
+                            type = EXCLUDED.type,
+                            required = EXCLUDED.required,
+                            include_in_token = EXCLUDED.include_in_token,
+                            user_editable = EXCLUDED.user_editable,
+                            description = EXCLUDED.description
+                        RETURNING {}
+                    "#,
+                    DEFINITION_COLUMNS
+                ),
+                &[
+                    &name,
+                    &dto.kind.as_str(),
+                    &dto.required,
+                    &dto.include_in_token,
+                    &dto.user_editable,
+                    &dto.description,
+                ],
+            )
+            .await
+            .map_err(|e| map_db_error("Error guardando la definición del atributo", e))?;
+
+        commit_transaction(tx, "Error haciendo commit de la definición del atributo").await?;
+        definition_from_row(&row)
+    }
+
+    /// Borra la definición y el valor del atributo en todos los usuarios.
+    pub async fn delete(&self, name: &str) -> Result<(), ApiError> {
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let deleted = tx
+            .execute(
+                "DELETE FROM user_attribute_definitions WHERE name = $1",
+                &[&name],
+            )
+            .await
+            .map_err(|e| map_db_error("Error borrando la definición del atributo", e))?;
+        if deleted == 0 {
+            return Err(HttpError::not_found("Atributo no encontrado"));
+        }
+
+        tx.execute(
+            "UPDATE users SET attributes = attributes - $1 WHERE attributes ? $1",
+            &[&name],
+        )
+        .await
+        .map_err(|e| map_db_error("Error borrando el atributo de los usuarios", e))?;
+
+        commit_transaction(tx, "Error haciendo commit del borrado del atributo").await
+    }
+}
+
+pub(crate) async fn load_definitions(
+    client: &impl GenericClient,
+) -> Result<Vec<AttributeDefinition>, ApiError> {
+    client
+        .query(
+            &format!(
+                "SELECT {} FROM user_attribute_definitions ORDER BY name",
+                DEFINITION_COLUMNS
+            ),
+            &[],
+        )
+        .await
+        .map_err(|e| map_db_error("Error consultando las definiciones de atributos", e))?
+        .iter()
+        .map(definition_from_row)
+        .collect()
+}
+
+/// Aplica `patch` sobre los atributos actuales del usuario dentro de la
+/// transacción; ver `apply_attributes`.
+pub(crate) async fn merge_attributes(
+    client: &impl GenericClient,
+    user_id: i64,
+    patch: &Map<String, Value>,
+    editor: AttributeEditor,
+) -> Result<Map<String, Value>, ApiError> {
+    let definitions = load_definitions(client).await?;
+    let mut attributes = client
+        .query_one(
+            "SELECT attributes FROM users WHERE id = $1 FOR UPDATE",
+            &[&user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error consultando los atributos del usuario", e))?
+        .get::<_, Json<Map<String, Value>>>("attributes")
+        .0;
+    apply_attributes(&definitions, &mut attributes, patch, editor)?;
+    Ok(attributes)
+}
+
+/// Atributos de un usuario nuevo: `values` sobre un objeto vacío con las
+/// reglas de `apply_attributes`, y además deben venir todos los obligatorios.
+/// Lo mismo se exige en todas las altas con datos del cliente (alta, registro,
+/// invitación, SCIM e importación); el aprovisionamiento federado (OIDC y
+/// LDAP) no tiene de dónde sacarlos y crea el usuario sin atributos.
+pub(crate) async fn new_user_attributes(
+    client: &impl GenericClient,
+    values: Option<&Map<String, Value>>,
+    editor: AttributeEditor,
+) -> Result<Value, ApiError> {
+    let definitions = load_definitions(client).await?;
+    let mut attributes = Map::new();
+    apply_attributes(
+        &definitions,
+        &mut attributes,
+        values.unwrap_or(&Map::new()),
+        editor,
+    )?;
+    check_required_attributes(&definitions, &attributes)?;
+    Ok(Value::Object(attributes))
+}
+
+/// `null` borra la clave y el resto debe estar definido y ser del tipo
+/// correcto. Un atributo obligatorio no se puede borrar, pero los que ya
+/// faltaban (definidos como obligatorios después del alta) no impiden
+/// editar el resto.
+pub(crate) fn apply_attributes(
+    definitions: &[AttributeDefinition],
+    attributes: &mut Map<String, Value>,
+    patch: &Map<String, Value>,
+    editor: AttributeEditor,
+) -> Result<(), ApiError> {
+    for (key, value) in patch {
+        let definition = definitions.iter().find(|d| &d.name == key);
+        if editor == AttributeEditor::Owner && definition.is_some_and(|d| !d.user_editable) {
+            return Err(HttpError::forbbiden(&format!(
+                "El atributo {} solo lo puede modificar un administrador",
+                key
+            )));
+        }
+        if value.is_null() {
+            if definition.is_some_and(|d| d.required) {
+                return Err(required_error(key));
+            }
+            attributes.remove(key);
+            continue;
+        }
+        let definition = definition
+            .ok_or_else(|| HttpError::bad_request(&format!("Atributo desconocido: {}", key)))?;
+        if !definition.kind.matches(value) {
+            return Err(HttpError::bad_request(&format!(
+                "El atributo {} debe ser de tipo {}",
+                key,
+                definition.kind.as_str()
+            )));
+        }
+        attributes.insert(key.clone(), value.clone());
+    }
+    Ok(())
+}
+
+/// Solo se exige al crear usuarios; ver `apply_attributes`.
+pub(crate) fn check_required_attributes(
+    definitions: &[AttributeDefinition],
+    attributes: &Map<String, Value>,
+) -> Result<(), ApiError> {
+    match definitions
+        .iter()
+        .find(|d| d.required && !attributes.contains_key(&d.name))
+    {
+        Some(missing) => Err(required_error(&missing.name)),
+        None => Ok(()),
+    }
+}
+
+fn required_error(name: &str) -> ApiError {
+    HttpError::bad_request(&format!("El atributo {} es obligatorio", name))
+}
+
+/// Atributos del usuario que se copian en el token de login.
+pub(crate) async fn token_attributes(
+    client: &impl GenericClient,
+    user_id: i64,
+) -> Result<Map<String, Value>, ApiError> {
+    let rows = client
+        .query(
+            r#"
+                SELECT d.name, u.attributes -> d.name AS value
+                FROM users u
+                JOIN user_attribute_definitions d
+                    ON d.include_in_token AND u.attributes ? d.name
+                WHERE u.id = $1
+            "#,
+            &[&user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error consultando los atributos del token", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let value: Json<Value> = row.get("value");
+            (row.get("name"), value.0)
+        })
+        .collect())
+}
+
+fn definition_from_row(row: &tokio_postgres::Row) -> Result<AttributeDefinition, ApiError> {
+    AttributeDefinition::from_row(row)
+        .map_err(|e| map_db_error("Error mapeando la definición del atributo", e.as_ref()))
+}
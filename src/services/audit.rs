@@ -0,0 +1,23 @@
+use deadpool_postgres::GenericClient;
+use tracing::error;
+
+use crate::database::models::entities::audit_event::AuditEventKind;
+
+/// Registra un evento de auditoría. Un fallo solo se registra en el log: la
+/// auditoría no debe impedir la operación que la origina.
+pub(crate) async fn record_audit_event(
+    client: &impl GenericClient,
+    user_id: i64,
+    kind: AuditEventKind,
+    details: Option<&str>,
+) {
+    if let Err(e) = client
+        .execute(
+            "INSERT INTO audit_events (user_id, event, details) VALUES ($1, $2, $3)",
+            &[&user_id, &kind.as_str(), &details],
+        )
+        .await
+    {
+        error!(error = %e, event = kind.as_str(), "Error registrando el evento de auditoría");
+    }
+}
@@ -0,0 +1,367 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::{
+    auth::{generate_opaque_token, hash_opaque_token, hash_password_pooled, validate_password},
+    config::get_config,
+    database::{
+        connection::PgPool,
+        models::{
+            FindResult,
+            dto::{AcceptInvitationDto, CreateInvitationDto, InvitationListQuery},
+            entities::{
+                invitation::{Invitation, InvitationStatus},
+                user::User,
+                user_status::UserStatus,
+            },
+        },
+    },
+    mailer::{Email, Mailer},
+    services::{AttributeEditor, UsersService, new_user_attributes},
+    utils::{
+        ApiError, Permissions, USER_PERMISSIONS, commit_transaction, errors::HttpError,
+        get_pg_client, get_transaction, map_db_error, validate_dto,
+    },
+};
+
+const INVITATION_COLUMNS: &str = "id, email, permissions, group_ids, invited_by, user_id, \
+     expires_at, accepted_at, revoked_at, created_at";
+
+/// Alta de usuarios por invitación: el administrador elige email, permisos y
+/// grupos, y el invitado elige su username y contraseña.
+pub struct InvitationService {
+    pool: PgPool,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl InvitationService {
+    pub fn new(pool: &PgPool, mailer: Arc<dyn Mailer>) -> Self {
+        InvitationService {
+            pool: pool.clone(),
+            mailer,
+        }
+    }
+
+    /// Crea la invitación y envía el enlace. Quien invita no puede otorgar
+    /// permisos que no tiene, salvo que sea administrador.
+    pub async fn create(
+        &self,
+        invited_by: i64,
+        granter: Permissions,
+        dto: CreateInvitationDto,
+    ) -> Result<Invitation, ApiError> {
+        let dto = dto.normalized();
+        validate_dto(&dto)?;
+        let email = dto.email.clone();
+        let permissions = match dto.permissions {
+            Some(bits) => Permissions::from_bits(bits)
+                .ok_or_else(|| HttpError::bad_request("Permisos inválidos"))?,
+            None => USER_PERMISSIONS,
+        };
+        if !granter.contains(Permissions::ADMIN) && !granter.contains(permissions) {
+            return Err(HttpError::forbbiden(
+                "No puedes otorgar permisos que no tienes",
+            ));
+        }
+
+        let client = get_pg_client(&self.pool).await?;
+        let existing_user = client
+            .query_opt("SELECT 1 FROM users WHERE lower(email) = $1", &[&email])
+            .await
+            .map_err(|e| map_db_error("Error consultando el usuario por email", e))?;
+        if existing_user.is_some() {
+            return Err(HttpError::conflict("Ya existe un usuario con ese email"));
+        }
+
+        let pending = client
+            .query_opt(
+                &format!(
+                    "SELECT 1 FROM invitations WHERE lower(email) = lower($1) AND {}",
+                    InvitationStatus::Pending.sql_condition()
+                ),
+                &[&email],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando invitaciones pendientes", e))?;
+        if pending.is_some() {
+            return Err(HttpError::conflict(
+                "Ya hay una invitación pendiente para ese email; puedes reenviarla",
+            ));
+        }
+
+        let mut groups = dto.groups.clone();
+        groups.sort_unstable();
+        groups.dedup();
+        let found: i64 = client
+            .query_one("SELECT COUNT(*) FROM groups WHERE id = ANY($1)", &[&groups])
+            .await
+            .map_err(|e| map_db_error("Error verificando los grupos", e))?
+            .get(0);
+        if found != groups.len() as i64 {
+            return Err(HttpError::bad_request("Alguno de los grupos no existe"));
+        }
+
+        let token = generate_opaque_token();
+        let ttl_hours = get_config().invitation.ttl_hours as i32;
+        let row = client
+            .query_one(
+                &format!(
+                    r#"
+                        INSERT INTO invitations
+                            (email, permissions, group_ids, token_hash, invited_by, expires_at)
+                        VALUES ($1, $2, $3, $4, $5, now() + make_interval(hours => $6))
+                        RETURNING {}
+                    "#,
+                    INVITATION_COLUMNS
+                ),
+                &[
+                    &email,
+                    &permissions.bits(),
+                    &groups,
+                    &hash_opaque_token(&token),
+                    &invited_by,
+                    &ttl_hours,
+                ],
+            )
+            .await
+            .map_err(|e| map_db_error("Error guardando la invitación", e))?;
+        let invitation = invitation_from_row(&row)?;
+
+        self.send(&invitation.email, &token).await?;
+        Ok(invitation)
+    }
+
+    pub async fn list(
+        &self,
+        query: InvitationListQuery,
+    ) -> Result<FindResult<Invitation>, ApiError> {
+        let condition = match query.status.as_deref() {
+            Some(name) => InvitationStatus::from_name(name)
+                .ok_or_else(|| HttpError::bad_request("Estado de invitación inválido"))?
+                .sql_condition(),
+            None => "TRUE",
+        };
+
+        let client = get_pg_client(&self.pool).await?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {} FROM invitations WHERE {} ORDER BY id DESC",
+                    INVITATION_COLUMNS, condition
+                ),
+                &[],
+            )
+            .await
+            .map_err(|e| map_db_error("Error listando invitaciones", e))?;
+
+        let results = rows
+            .iter()
+            .map(invitation_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(FindResult {
+            total: Some(results.len() as u64),
+            results,
+            next_cursor: None,
+        })
+    }
+
+    /// Genera un token nuevo, renueva el vencimiento y vuelve a enviar el
+    /// email. El enlace anterior deja de funcionar. Sirve también para
+    /// invitaciones vencidas.
+    pub async fn resend(&self, id: i64) -> Result<Invitation, ApiError> {
+        let token = generate_opaque_token();
+        let ttl_hours = get_config().invitation.ttl_hours as i32;
+
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                &format!(
+                    r#"
+                        UPDATE invitations
+                        SET token_hash = $2, expires_at = now() + make_interval(hours => $3)
+                        WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL
+                        RETURNING {}
+                    "#,
+                    INVITATION_COLUMNS
+                ),
+                &[&id, &hash_opaque_token(&token), &ttl_hours],
+            )
+            .await
+            .map_err(|e| map_db_error("Error renovando la invitación", e))?;
+        let row = match row {
+            Some(r) => r,
+            None => return Err(self.not_pending(&client, id).await),
+        };
+        let invitation = invitation_from_row(&row)?;
+
+        self.send(&invitation.email, &token).await?;
+        Ok(invitation)
+    }
+
+    pub async fn revoke(&self, id: i64) -> Result<(), ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        let updated = client
+            .execute(
+                r#"
+                    UPDATE invitations SET revoked_at = now()
+                    WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL
+                "#,
+                &[&id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error revocando la invitación", e))?;
+        if updated == 0 {
+            return Err(self.not_pending(&client, id).await);
+        }
+        Ok(())
+    }
+
+    /// Crea el usuario con los permisos y grupos de la invitación. El token
+    /// se consume en la misma transacción, así que solo se puede usar una vez.
+    pub async fn accept(&self, dto: AcceptInvitationDto) -> Result<User, ApiError> {
+        let dto = dto.normalized();
+        validate_dto(&dto)?;
+        let token_hash = hash_opaque_token(&dto.token);
+        let invalid = || HttpError::unauthorized("Invitación inválida o expirada");
+
+        let client = get_pg_client(&self.pool).await?;
+        let email: String = client
+            .query_opt(
+                &format!(
+                    "SELECT email FROM invitations WHERE token_hash = $1 AND {}",
+                    InvitationStatus::Pending.sql_condition()
+                ),
+                &[&token_hash],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando la invitación", e))?
+            .ok_or_else(invalid)?
+            .get("email");
+        drop(client);
+
+        validate_password(&dto.password, &[&dto.username, &email]).await?;
+        // Igual que en el alta, se hashea sin retener una conexión.
+        let password_hash = hash_password_pooled(&dto.password).await?;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let row = tx
+            .query_opt(
+                &format!(
+                    r#"
+                        UPDATE invitations SET accepted_at = now()
+                        WHERE token_hash = $1 AND {}
+                        RETURNING id, email, permissions, group_ids
+                    "#,
+                    InvitationStatus::Pending.sql_condition()
+                ),
+                &[&token_hash],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consumiendo la invitación", e))?
+            .ok_or_else(invalid)?;
+        let invitation_id: i64 = row.get("id");
+        let email: String = row.get("email");
+        let permissions: i64 = row.get("permissions");
+        let groups: Vec<i64> = row.get("group_ids");
+
+        let exists = tx
+            .query_opt(
+                "SELECT 1 FROM users WHERE lower(username) = lower($1) OR lower(email) = lower($2) LIMIT 1",
+                &[&dto.username, &email],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando la existencia previa del usuario", e))?;
+        if exists.is_some() {
+            return Err(HttpError::conflict(
+                "Ya existe un usuario con ese nombre de usuario o email",
+            ));
+        }
+
+        let attributes =
+            new_user_attributes(&tx, dto.attributes.as_ref(), AttributeEditor::Owner).await?;
+
+        let user_id: i64 = tx
+            .query_one(
+                r#"
+                    INSERT INTO users (username, email, password, permissions, status, attributes)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING id
+                "#,
+                &[
+                    &dto.username,
+                    &email,
+                    &password_hash,
+                    &permissions,
+                    &UserStatus::Active,
+                    &attributes,
+                ],
+            )
+            .await
+            .map_err(|e| map_db_error("Error creando el usuario invitado", e))?
+            .get("id");
+
+        // Los grupos borrados desde que se envió la invitación se ignoran.
+        tx.execute(
+            r#"
+                INSERT INTO group_members (group_id, user_id)
+                SELECT id, $2 FROM groups WHERE id = ANY($1)
+            "#,
+            &[&groups, &user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error agregando el usuario a sus grupos", e))?;
+
+        tx.execute(
+            "UPDATE invitations SET user_id = $2 WHERE id = $1",
+            &[&invitation_id, &user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error vinculando la invitación", e))?;
+
+        commit_transaction(tx, "Error haciendo commit de la invitación").await?;
+        UsersService::new(&self.pool).find_by_id(user_id).await
+    }
+
+    /// 404 si la invitación no existe; 409 si ya fue aceptada o revocada.
+    async fn not_pending(&self, client: &deadpool_postgres::Client, id: i64) -> ApiError {
+        match client
+            .query_opt("SELECT 1 FROM invitations WHERE id = $1", &[&id])
+            .await
+        {
+            Ok(Some(_)) => HttpError::conflict("La invitación ya fue aceptada o revocada"),
+            Ok(None) => HttpError::not_found("Invitación no encontrada"),
+            Err(e) => map_db_error("Error consultando la invitación", e),
+        }
+    }
+
+    async fn send(&self, to: &str, token: &str) -> Result<(), ApiError> {
+        let config = get_config();
+        let separator = if config.invitation.url.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        let link = format!("{}{}token={}", config.invitation.url, separator, token);
+
+        let email = Email {
+            from: config.mailer.from.clone(),
+            to: to.to_string(),
+            subject: "Te invitaron a crear una cuenta".to_string(),
+            body: format!(
+                "Usa el siguiente enlace para elegir tu usuario y contraseña. Expira en {} horas y solo puede usarse una vez.\n\n{}",
+                config.invitation.ttl_hours, link
+            ),
+        };
+        self.mailer.send(email).await.map_err(|e| {
+            error!(error = %e, "Error enviando la invitación");
+            HttpError::internal_server_error()
+        })
+    }
+}
+
+fn invitation_from_row(row: &tokio_postgres::Row) -> Result<Invitation, ApiError> {
+    Invitation::from_row(row).map_err(|e| map_db_error("Error mapeando la invitación", e.as_ref()))
+}
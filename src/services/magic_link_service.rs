@@ -0,0 +1,150 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::error;
+
+use crate::{
+    auth::{generate_opaque_token, hash_opaque_token, issue_login_token},
+    config::get_config,
+    database::{
+        connection::PgPool,
+        models::{
+            dto::{MagicLinkRequest, RedeemMagicLinkRequest},
+            entities::user_status::UserStatus,
+        },
+    },
+    mailer::{Email, Mailer},
+    utils::{
+        ApiError, errors::HttpError, get_pg_client, map_db_error, normalize_email,
+        rate_limit::RateLimiter, validate_dto,
+    },
+};
+
+pub struct MagicLinkService {
+    pool: PgPool,
+    mailer: Arc<dyn Mailer>,
+    limiter: RateLimiter,
+}
+
+impl MagicLinkService {
+    pub fn new(pool: &PgPool, mailer: Arc<dyn Mailer>) -> Self {
+        let config = &get_config().magic_link;
+        MagicLinkService {
+            pool: pool.clone(),
+            mailer,
+            limiter: RateLimiter::new(
+                config.rate_limit,
+                Duration::from_secs(config.rate_limit_window_seconds as u64),
+            ),
+        }
+    }
+
+    /// Envía un enlace de acceso al email indicado. Para no revelar qué emails
+    /// están registrados, responde igual exista o no el usuario.
+    pub async fn request(&self, dto: MagicLinkRequest) -> Result<(), ApiError> {
+        validate_dto(&dto)?;
+        let config = get_config();
+
+        let email = normalize_email(&dto.email);
+        if !self.limiter.check(&email) {
+            return Err(HttpError::too_many_requests(
+                "Demasiadas solicitudes para este email, intenta más tarde",
+            ));
+        }
+
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                "SELECT id, email, status FROM users WHERE lower(email) = $1",
+                &[&email],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando el usuario por email", e))?;
+
+        let row = match row {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+        let status: UserStatus = row.get("status");
+        if status != UserStatus::Active {
+            return Ok(());
+        }
+        let user_id: i64 = row.get("id");
+        let user_email: String = row.get("email");
+
+        let token = generate_opaque_token();
+        let ttl_minutes = config.magic_link.ttl_minutes as i32;
+        client
+            .execute(
+                r#"
+                    INSERT INTO magic_links (user_id, token_hash, expires_at)
+                    VALUES ($1, $2, now() + make_interval(mins => $3))
+                "#,
+                &[&user_id, &hash_opaque_token(&token), &ttl_minutes],
+            )
+            .await
+            .map_err(|e| map_db_error("Error guardando el enlace de acceso", e))?;
+
+        let separator = if config.magic_link.url.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        let link = format!("{}{}token={}", config.magic_link.url, separator, token);
+
+        let email = Email {
+            from: config.mailer.from.clone(),
+            to: user_email,
+            subject: "Tu enlace de acceso".to_string(),
+            body: format!(
+                "Usa el siguiente enlace para iniciar sesión. Expira en {} minutos y solo puede usarse una vez.\n\n{}",
+                ttl_minutes, link
+            ),
+        };
+        self.mailer.send(email).await.map_err(|e| {
+            error!(error = %e, "Error enviando el enlace de acceso");
+            HttpError::internal_server_error()
+        })?;
+
+        Ok(())
+    }
+
+    /// Consume el enlace y devuelve un JWT de sesión.
+    pub async fn redeem(&self, dto: RedeemMagicLinkRequest) -> Result<String, ApiError> {
+        validate_dto(&dto)?;
+
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                r#"
+                    UPDATE magic_links
+                    SET used_at = now()
+                    WHERE token_hash = $1 AND used_at IS NULL AND expires_at > now()
+                    RETURNING user_id
+                "#,
+                &[&hash_opaque_token(&dto.token)],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consumiendo el enlace de acceso", e))?;
+
+        let user_id: i64 = match row {
+            Some(r) => r.get("user_id"),
+            None => return Err(HttpError::unauthorized("Enlace inválido o expirado")),
+        };
+
+        let status_row = client
+            .query_opt("SELECT status FROM users WHERE id = $1", &[&user_id])
+            .await
+            .map_err(|e| map_db_error("Error consultando el status del usuario", e))?;
+        match status_row {
+            Some(r) if r.get::<_, UserStatus>("status") == UserStatus::Active => {}
+            _ => return Err(HttpError::unauthorized("Enlace inválido o expirado")),
+        }
+
+        issue_login_token(
+            &self.pool,
+            user_id,
+            get_config().password_policy.max_age_days,
+        )
+        .await
+    }
+}
@@ -1,3 +1,23 @@
+mod attribute_service;
+mod audit;
+mod invitation_service;
+mod magic_link_service;
+mod oidc_service;
+mod privacy_service;
+mod registration_service;
+mod scim_service;
+mod user_purge;
+mod user_transfer_service;
 mod users_service;
 
+pub use attribute_service::*;
+pub(crate) use audit::*;
+pub use invitation_service::*;
+pub use magic_link_service::*;
+pub use oidc_service::*;
+pub use privacy_service::*;
+pub use registration_service::*;
+pub use scim_service::*;
+pub use user_purge::*;
+pub use user_transfer_service::*;
 pub use users_service::*;
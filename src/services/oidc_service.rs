@@ -0,0 +1,459 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use serde::{Deserialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+use tracing::error;
+use url::Url;
+
+use crate::{
+    auth::{generate_opaque_token, hash_opaque_token, issue_login_token},
+    config::{OidcProviderConfig, get_config},
+    database::{
+        connection::PgPool,
+        models::{dto::OidcCallbackQuery, entities::user_status::UserStatus},
+    },
+    utils::{
+        ApiError, HTTP_CLIENT, USER_PERMISSIONS, available_username, commit_transaction,
+        errors::HttpError, get_pg_client, get_transaction, map_db_error, normalize_email,
+    },
+};
+
+const AUTH_REQUEST_TTL_MINUTES: i32 = 10;
+const METADATA_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    #[serde(default)]
+    id_token_signing_alg_values_supported: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    nonce: Option<String>,
+}
+
+struct CachedMetadata {
+    metadata: ProviderMetadata,
+    jwks: Option<JwkSet>,
+    fetched_at: Instant,
+}
+
+/// Login federado contra proveedores OIDC externos (flujo authorization code con
+/// state, nonce y PKCE).
+pub struct OidcService {
+    pool: PgPool,
+    providers: Vec<OidcProviderConfig>,
+    cache: RwLock<HashMap<String, CachedMetadata>>,
+}
+
+impl OidcService {
+    pub fn new(pool: &PgPool, providers: Vec<OidcProviderConfig>) -> Self {
+        OidcService {
+            pool: pool.clone(),
+            providers,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn provider_names(&self) -> Vec<String> {
+        self.providers.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Genera la URL de autorización del proveedor y persiste el state/nonce.
+    pub async fn authorize_url(&self, provider: &str) -> Result<String, ApiError> {
+        let provider = self.provider(provider)?;
+        let metadata = self.metadata(provider).await?;
+
+        let state = generate_opaque_token();
+        let nonce = generate_opaque_token();
+        let code_verifier = generate_opaque_token();
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        let client = get_pg_client(&self.pool).await?;
+        client
+            .execute(
+                r#"
+                    INSERT INTO oidc_auth_requests (state_hash, provider, nonce, code_verifier, expires_at)
+                    VALUES ($1, $2, $3, $4, now() + make_interval(mins => $5))
+                "#,
+                &[
+                    &hash_opaque_token(&state),
+                    &provider.name,
+                    &nonce,
+                    &code_verifier,
+                    &AUTH_REQUEST_TTL_MINUTES,
+                ],
+            )
+            .await
+            .map_err(|e| map_db_error("Error guardando la solicitud OIDC", e))?;
+
+        let mut url = Url::parse(&metadata.authorization_endpoint).map_err(|e| {
+            error!(error = %e, "authorization_endpoint inválido");
+            HttpError::bad_gateway("Configuración inválida del proveedor de identidad")
+        })?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &provider.client_id)
+            .append_pair("redirect_uri", &provider.redirect_uri)
+            .append_pair("scope", &provider.scopes)
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &code_challenge)
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(url.to_string())
+    }
+
+    /// Completa el flujo: canjea el código, valida el id_token y devuelve un JWT
+    /// del usuario vinculado (o aprovisionado) a la identidad externa.
+    pub async fn callback(
+        &self,
+        provider: &str,
+        query: OidcCallbackQuery,
+    ) -> Result<String, ApiError> {
+        let provider = self.provider(provider)?;
+
+        if let Some(e) = query.error {
+            error!(provider = %provider.name, error = %e, "El proveedor OIDC devolvió un error");
+            return Err(HttpError::unauthorized(
+                "El proveedor de identidad rechazó el login",
+            ));
+        }
+        let (code, state) = match (query.code, query.state) {
+            (Some(c), Some(s)) => (c, s),
+            _ => return Err(HttpError::bad_request("Faltan los parámetros code o state")),
+        };
+
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                r#"
+                    DELETE FROM oidc_auth_requests
+                    WHERE state_hash = $1 AND provider = $2 AND expires_at > now()
+                    RETURNING nonce, code_verifier
+                "#,
+                &[&hash_opaque_token(&state), &provider.name],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando la solicitud OIDC", e))?;
+        let row = match row {
+            Some(r) => r,
+            None => return Err(HttpError::unauthorized("Estado OIDC inválido o expirado")),
+        };
+        let nonce: String = row.get("nonce");
+        let code_verifier: String = row.get("code_verifier");
+
+        let metadata = self.metadata(provider).await?;
+        let response = HTTP_CLIENT
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", &code),
+                ("redirect_uri", &provider.redirect_uri),
+                ("client_id", &provider.client_id),
+                ("client_secret", &provider.client_secret),
+                ("code_verifier", &code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Error canjeando el código OIDC");
+                HttpError::bad_gateway("No se pudo contactar al proveedor de identidad")
+            })?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            error!(
+                status = %status,
+                body = %body,
+                "El proveedor OIDC rechazó el canje del código"
+            );
+            return Err(HttpError::unauthorized(
+                "El proveedor de identidad rechazó el login",
+            ));
+        }
+        let tokens: TokenResponse = response.json().await.map_err(|e| {
+            error!(error = %e, "Respuesta inválida del token endpoint");
+            HttpError::bad_gateway("Respuesta inválida del proveedor de identidad")
+        })?;
+
+        let claims = self
+            .validate_id_token(provider, &metadata, &tokens.id_token)
+            .await?;
+        if claims.nonce.as_deref() != Some(nonce.as_str()) {
+            return Err(HttpError::unauthorized("Nonce OIDC inválido"));
+        }
+
+        let user_id = self.resolve_user(provider, &claims).await?;
+        issue_login_token(
+            &self.pool,
+            user_id,
+            get_config().password_policy.max_age_days,
+        )
+        .await
+    }
+
+    fn provider(&self, name: &str) -> Result<&OidcProviderConfig, ApiError> {
+        self.providers
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| HttpError::not_found("Proveedor de identidad no configurado"))
+    }
+
+    async fn metadata(&self, provider: &OidcProviderConfig) -> Result<ProviderMetadata, ApiError> {
+        if let Some(cached) = self.cache.read().await.get(&provider.name)
+            && cached.fetched_at.elapsed() < METADATA_TTL
+        {
+            return Ok(cached.metadata.clone());
+        }
+
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            provider.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = fetch_json(&url).await.map_err(|e| {
+            error!(error = %e, provider = %provider.name, "Error obteniendo el discovery OIDC");
+            HttpError::bad_gateway("No se pudo contactar al proveedor de identidad")
+        })?;
+
+        self.cache.write().await.insert(
+            provider.name.clone(),
+            CachedMetadata {
+                metadata: metadata.clone(),
+                jwks: None,
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(metadata)
+    }
+
+    async fn decoding_key(
+        &self,
+        provider: &OidcProviderConfig,
+        metadata: &ProviderMetadata,
+        kid: Option<&str>,
+    ) -> Result<DecodingKey, ApiError> {
+        let find = |jwks: &JwkSet| match kid {
+            Some(kid) => jwks.find(kid).cloned(),
+            None => jwks.keys.first().cloned(),
+        };
+
+        let cached = self
+            .cache
+            .read()
+            .await
+            .get(&provider.name)
+            .and_then(|c| c.jwks.as_ref().and_then(find));
+
+        let jwk = match cached {
+            Some(jwk) => jwk,
+            None => {
+                // Clave desconocida: puede haber rotado, se vuelve a descargar el JWKS.
+                let jwks: JwkSet = fetch_json(&metadata.jwks_uri).await.map_err(|e| {
+                    error!(error = %e, provider = %provider.name, "Error obteniendo el JWKS");
+                    HttpError::bad_gateway("No se pudo contactar al proveedor de identidad")
+                })?;
+                let jwk = find(&jwks);
+                if let Some(cached) = self.cache.write().await.get_mut(&provider.name) {
+                    cached.jwks = Some(jwks);
+                }
+                jwk.ok_or_else(|| {
+                    HttpError::unauthorized("id_token firmado con una clave desconocida")
+                })?
+            }
+        };
+
+        DecodingKey::from_jwk(&jwk).map_err(|e| {
+            error!(error = %e, "JWK inválida");
+            HttpError::bad_gateway("Clave inválida del proveedor de identidad")
+        })
+    }
+
+    async fn validate_id_token(
+        &self,
+        provider: &OidcProviderConfig,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+    ) -> Result<IdTokenClaims, ApiError> {
+        let header = decode_header(id_token).map_err(|e| {
+            error!(error = %e, "Cabecera de id_token inválida");
+            HttpError::unauthorized("id_token inválido")
+        })?;
+
+        // El algoritmo lo fija la configuración o el discovery, nunca la
+        // cabecera: si no, un token HS256 firmado con el client_secret (que
+        // conocen otros) o con una clave pública pasaría como válido.
+        let algorithms = allowed_algorithms(provider, metadata);
+        if !algorithms.contains(&header.alg) {
+            error!(provider = %provider.name, alg = ?header.alg, "Algoritmo de id_token no permitido");
+            return Err(HttpError::unauthorized("id_token inválido"));
+        }
+
+        let key = if is_symmetric(header.alg) {
+            DecodingKey::from_secret(provider.client_secret.as_bytes())
+        } else {
+            self.decoding_key(provider, metadata, header.kid.as_deref())
+                .await?
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.algorithms = algorithms;
+        validation.set_audience(&[&provider.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+
+        decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| {
+                error!(error = %e, provider = %provider.name, "id_token rechazado");
+                HttpError::unauthorized("id_token inválido")
+            })
+    }
+
+    /// Busca la identidad vinculada; si no existe la vincula por email verificado
+    /// o aprovisiona un usuario nuevo con los permisos por defecto. El
+    /// proveedor no trae atributos personalizados, así que el usuario
+    /// aprovisionado queda exento de los obligatorios: los completa un
+    /// administrador después (ver `new_user_attributes`).
+    async fn resolve_user(
+        &self,
+        provider: &OidcProviderConfig,
+        claims: &IdTokenClaims,
+    ) -> Result<i64, ApiError> {
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let linked = tx
+            .query_opt(
+                r#"
+                    SELECT u.id, u.status FROM user_identities i
+                    JOIN users u ON u.id = i.user_id
+                    WHERE i.provider = $1 AND i.subject = $2
+                "#,
+                &[&provider.name, &claims.sub],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando la identidad externa", e))?;
+
+        let (user_id, status): (i64, UserStatus) = match linked {
+            Some(row) => (row.get("id"), row.get("status")),
+            None => {
+                let email = match (&claims.email, claims.email_verified) {
+                    (Some(email), true) => normalize_email(email),
+                    _ => {
+                        return Err(HttpError::forbbiden(
+                            "El proveedor de identidad no verificó el email",
+                        ));
+                    }
+                };
+
+                let existing = tx
+                    .query_opt(
+                        "SELECT id, status FROM users WHERE lower(email) = $1",
+                        &[&email],
+                    )
+                    .await
+                    .map_err(|e| map_db_error("Error consultando el usuario por email", e))?;
+
+                let (user_id, status, provisioned) = match existing {
+                    Some(row) => (row.get("id"), row.get("status"), false),
+                    None => {
+                        let local = email.split('@').next().unwrap_or_default();
+                        let username = available_username(&tx, local).await?;
+                        let row = tx
+                            .query_one(
+                                r#"
+                                    INSERT INTO users (username, email, password, permissions, status)
+                                    VALUES ($1, $2, NULL, $3, $4)
+                                    RETURNING id, status
+                                "#,
+                                &[
+                                    &username,
+                                    &email,
+                                    &USER_PERMISSIONS.bits(),
+                                    &UserStatus::Active,
+                                ],
+                            )
+                            .await
+                            .map_err(|e| map_db_error("Error aprovisionando el usuario", e))?;
+                        (row.get("id"), row.get("status"), true)
+                    }
+                };
+
+                tx.execute(
+                    r#"
+                        INSERT INTO user_identities (user_id, provider, subject, email, provisioned)
+                        VALUES ($1, $2, $3, $4, $5)
+                    "#,
+                    &[&user_id, &provider.name, &claims.sub, &email, &provisioned],
+                )
+                .await
+                .map_err(|e| map_db_error("Error vinculando la identidad externa", e))?;
+
+                (user_id, status)
+            }
+        };
+
+        commit_transaction(tx, "Error haciendo commit de la identidad externa").await?;
+
+        match status {
+            UserStatus::Active => Ok(user_id),
+            UserStatus::Inactive => Err(HttpError::forbbiden("Usuario inactivo")),
+            UserStatus::Deleted => Err(HttpError::unauthorized("Credenciales inválidas")),
+        }
+    }
+}
+
+async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, reqwest::Error> {
+    HTTP_CLIENT
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+}
+
+fn is_symmetric(alg: Algorithm) -> bool {
+    matches!(alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+}
+
+/// Los configurados para el proveedor o, si no hay, los asimétricos que anuncia
+/// el discovery (RS256 si no anuncia ninguno, como exige la especificación).
+fn allowed_algorithms(
+    provider: &OidcProviderConfig,
+    metadata: &ProviderMetadata,
+) -> Vec<Algorithm> {
+    if !provider.algorithms.is_empty() {
+        return provider.algorithms.clone();
+    }
+    let advertised: Vec<Algorithm> = metadata
+        .id_token_signing_alg_values_supported
+        .iter()
+        .filter_map(|alg| alg.parse().ok())
+        .filter(|alg| !is_symmetric(*alg))
+        .collect();
+    if advertised.is_empty() {
+        vec![Algorithm::RS256]
+    } else {
+        advertised
+    }
+}
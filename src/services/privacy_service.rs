@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+use tracing::{error, info};
+
+use crate::{
+    config::get_config,
+    database::{
+        connection::PgPool,
+        models::{
+            dto::{ErasureResponse, MagicLinkExport, UserDataExport},
+            entities::{
+                audit_event::{AuditEvent, AuditEventKind},
+                identity::UserIdentity,
+                user_status::UserStatus,
+            },
+        },
+    },
+    services::{UsersService, record_audit_event},
+    utils::{
+        ApiError, commit_transaction, errors::HttpError, get_pg_client, get_transaction,
+        map_db_error,
+    },
+};
+
+/// Eventos que sobreviven a la anonimización: prueban que la baja se pidió y
+/// se ejecutó, sin datos personales.
+const RETAINED_EVENTS: [AuditEventKind; 2] =
+    [AuditEventKind::ErasureRequested, AuditEventKind::Erased];
+
+/// Exportación de datos personales y derecho al olvido.
+pub struct PrivacyService {
+    pool: PgPool,
+    grace_days: u32,
+}
+
+impl PrivacyService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self::with_grace_days(pool, get_config().user_purge.erasure_grace_days)
+    }
+
+    pub fn with_grace_days(pool: &PgPool, grace_days: u32) -> Self {
+        PrivacyService {
+            pool: pool.clone(),
+            grace_days,
+        }
+    }
+
+    pub async fn export(&self, user_id: i64) -> Result<UserDataExport, ApiError> {
+        let user = UsersService::new(&self.pool).find_by_id(user_id).await?;
+        let client = get_pg_client(&self.pool).await?;
+
+        let identities = client
+            .query(
+                r#"
+                    SELECT id, user_id, provider, subject, email, created_at
+                    FROM user_identities WHERE user_id = $1 ORDER BY id
+                "#,
+                &[&user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando identidades para exportar", e))?
+            .iter()
+            .map(UserIdentity::from_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| map_db_error("Error mapeando identidades", e.as_ref()))?;
+
+        let groups = client
+            .query(
+                r#"
+                    SELECT g.display_name FROM group_members gm
+                    JOIN groups g ON g.id = gm.group_id
+                    WHERE gm.user_id = $1 ORDER BY g.display_name
+                "#,
+                &[&user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando grupos para exportar", e))?
+            .iter()
+            .map(|row| row.get("display_name"))
+            .collect();
+
+        let magic_links = client
+            .query(
+                r#"
+                    SELECT created_at, expires_at, used_at
+                    FROM magic_links WHERE user_id = $1 ORDER BY id
+                "#,
+                &[&user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando enlaces de acceso para exportar", e))?
+            .iter()
+            .map(|row| MagicLinkExport {
+                created_at: row.get("created_at"),
+                expires_at: row.get("expires_at"),
+                used_at: row.get("used_at"),
+            })
+            .collect();
+
+        let audit_events = client
+            .query(
+                r#"
+                    SELECT id, event, details, created_at
+                    FROM audit_events WHERE user_id = $1 ORDER BY id
+                "#,
+                &[&user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando auditoría para exportar", e))?
+            .iter()
+            .map(AuditEvent::from_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| map_db_error("Error mapeando eventos de auditoría", e.as_ref()))?;
+
+        Ok(UserDataExport {
+            exported_at: Utc::now(),
+            user,
+            identities,
+            groups,
+            magic_links,
+            audit_events,
+        })
+    }
+
+    /// Programa la anonimización al cabo del período de gracia. Sin período
+    /// de gracia se anonimiza en el momento.
+    pub async fn request_erasure(&self, user_id: i64) -> Result<ErasureResponse, ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                r#"
+                    UPDATE users
+                    SET erasure_scheduled_at = now() + make_interval(days => $2)
+                    WHERE id = $1 AND status <> $3 AND erasure_scheduled_at IS NULL
+                    RETURNING erasure_scheduled_at
+                "#,
+                &[&user_id, &(self.grace_days as i32), &UserStatus::Deleted],
+            )
+            .await
+            .map_err(|e| map_db_error("Error programando la baja", e))?
+            .ok_or_else(|| HttpError::conflict("Ya hay una baja pendiente para este usuario"))?;
+        let scheduled_at: DateTime<Utc> = row.get("erasure_scheduled_at");
+        record_audit_event(&client, user_id, AuditEventKind::ErasureRequested, None).await;
+        drop(client);
+
+        if self.grace_days == 0 {
+            self.erase(user_id).await?;
+        }
+        Ok(ErasureResponse { scheduled_at })
+    }
+
+    pub async fn cancel_erasure(&self, user_id: i64) -> Result<(), ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        let updated = client
+            .execute(
+                r#"
+                    UPDATE users SET erasure_scheduled_at = NULL
+                    WHERE id = $1 AND erasure_scheduled_at IS NOT NULL
+                "#,
+                &[&user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error cancelando la baja", e))?;
+        if updated == 0 {
+            return Err(HttpError::not_found("No hay una baja pendiente"));
+        }
+        record_audit_event(&client, user_id, AuditEventKind::ErasureCancelled, None).await;
+        Ok(())
+    }
+
+    /// Anonimiza a los usuarios cuyo período de gracia terminó. Devuelve
+    /// cuántos se procesaron.
+    pub async fn erase_due(&self) -> Result<u64, ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        let ids: Vec<i64> = client
+            .query(
+                "SELECT id FROM users WHERE erasure_scheduled_at <= now() ORDER BY id",
+                &[],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando bajas pendientes", e))?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
+        drop(client);
+
+        let mut erased = 0;
+        for id in ids {
+            match self.erase(id).await {
+                Ok(()) => erased += 1,
+                Err((status, _)) => error!(user_id = id, %status, "Error anonimizando usuario"),
+            }
+        }
+        Ok(erased)
+    }
+
+    /// Reemplaza username y email por valores sin datos personales, borra
+    /// perfil, credenciales, identidades externas, enlaces, grupos e invitaciones,
+    /// y deja solo los eventos de auditoría de la baja. El usuario queda
+    /// eliminado y lo borra definitivamente el purgado.
+    pub async fn erase(&self, user_id: i64) -> Result<(), ApiError> {
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        // Las invitaciones guardan el email, así que se borran antes de
+        // reemplazarlo: la aceptada y cualquier otra dirigida a esa dirección.
+        tx.execute(
+            r#"
+                DELETE FROM invitations
+                WHERE user_id = $1
+                   OR lower(email) = (SELECT lower(email) FROM users WHERE id = $1)
+            "#,
+            &[&user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error borrando las invitaciones del usuario", e))?;
+
+        let updated = tx
+            .execute(
+                r#"
+                    UPDATE users
+                    SET username = 'erased_' || id,
+                        email = 'erased_' || id || '@erased.invalid',
+                        password = NULL,
+                        external_id = NULL,
+                        permissions = 0,
+                        must_change_password = false,
+                        display_name = NULL,
+                        locale = NULL,
+                        timezone = NULL,
+                        avatar_url = NULL,
+                        phone_number = NULL,
+                        attributes = '{}',
+                        status = $2,
+                        deleted_at = now(),
+                        erasure_scheduled_at = NULL,
+                        updated_at = now()
+                    WHERE id = $1
+                "#,
+                &[&user_id, &UserStatus::Deleted],
+            )
+            .await
+            .map_err(|e| map_db_error("Error anonimizando el usuario", e))?;
+        if updated == 0 {
+            return Err(HttpError::not_found("Usuario no encontrado"));
+        }
+
+        for table in [
+            "user_identities",
+            "magic_links",
+            "password_history",
+            "group_members",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE user_id = $1", table),
+                &[&user_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error borrando datos del usuario", e))?;
+        }
+
+        let retained: Vec<&str> = RETAINED_EVENTS.iter().map(|k| k.as_str()).collect();
+        tx.execute(
+            "DELETE FROM audit_events WHERE user_id = $1 AND event <> ALL($2)",
+            &[&user_id, &retained],
+        )
+        .await
+        .map_err(|e| map_db_error("Error depurando la auditoría del usuario", e))?;
+        record_audit_event(&tx, user_id, AuditEventKind::Erased, None).await;
+
+        commit_transaction(tx, "Error haciendo commit de la anonimización").await?;
+        info!(user_id, "Usuario anonimizado");
+        Ok(())
+    }
+}
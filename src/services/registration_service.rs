@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use crate::{
+    captcha::CaptchaVerifier,
+    config::{SignupConfig, SignupMode},
+    database::{
+        connection::PgPool,
+        models::{
+            dto::{CreateUserDto, RegisterDto},
+            entities::user::User,
+        },
+    },
+    services::{AttributeEditor, UsersService},
+    utils::{ApiError, errors::HttpError, validate_dto},
+};
+
+/// Registro público sin autenticación. Los usuarios se crean con los
+/// permisos por defecto (`USER_PERMISSIONS`).
+pub struct RegistrationService {
+    pool: PgPool,
+    config: SignupConfig,
+    captcha: Arc<dyn CaptchaVerifier>,
+}
+
+impl RegistrationService {
+    pub fn new(pool: &PgPool, config: SignupConfig, captcha: Arc<dyn CaptchaVerifier>) -> Self {
+        RegistrationService {
+            pool: pool.clone(),
+            config,
+            captcha,
+        }
+    }
+
+    /// Aplica la política de registro y el CAPTCHA antes de crear el usuario;
+    /// la política de contraseñas y los duplicados los valida el alta normal.
+    pub async fn register(&self, dto: RegisterDto) -> Result<User, ApiError> {
+        if self.config.mode == SignupMode::Disabled {
+            return Err(HttpError::forbbiden(
+                "El registro público está deshabilitado",
+            ));
+        }
+        // El dominio y la lista de reservados se comparan ya normalizados.
+        let dto = dto.normalized();
+        validate_dto(&dto)?;
+
+        if let SignupMode::Domains(domains) = &self.config.mode
+            && !email_domain_allowed(&dto.email, domains)
+        {
+            return Err(HttpError::forbbiden(
+                "El dominio del email no está habilitado para registrarse",
+            ));
+        }
+
+        let username = dto.username.to_lowercase();
+        if self.config.username_blocklist.contains(&username) {
+            return Err(HttpError::bad_request(
+                "Ese nombre de usuario no está disponible",
+            ));
+        }
+
+        let token = dto.captcha_token.as_deref().unwrap_or_default();
+        match self.captcha.verify(token).await {
+            Ok(true) => {}
+            Ok(false) => return Err(HttpError::bad_request("La verificación CAPTCHA falló")),
+            Err(e) => {
+                error!(error = %e, "Error verificando el CAPTCHA");
+                return Err(HttpError::bad_gateway("No se pudo verificar el CAPTCHA"));
+            }
+        }
+
+        let user = UsersService::new(&self.pool)
+            .create_as(
+                CreateUserDto {
+                    username: dto.username,
+                    email: dto.email,
+                    password: dto.password,
+                    attributes: dto.attributes,
+                },
+                AttributeEditor::Owner,
+            )
+            .await?;
+        info!(user_id = user.id, "Usuario registrado");
+        Ok(user)
+    }
+}
+
+/// El dominio del email coincide con uno de la lista o es un subdominio suyo.
+fn email_domain_allowed(email: &str, domains: &[String]) -> bool {
+    let Some((_, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+    let domain = domain.to_lowercase();
+    domains.iter().any(|allowed| {
+        domain == *allowed
+            || domain
+                .strip_suffix(allowed.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
@@ -0,0 +1,986 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+use subtle::ConstantTimeEq;
+use tokio_postgres::{
+    Row,
+    types::{Json, ToSql},
+};
+use validator::ValidateEmail;
+
+use crate::{
+    auth::{hash_password_pooled, validate_password},
+    config::get_config,
+    database::{
+        connection::PgPool,
+        models::{
+            dto::{
+                ScimEmail, ScimGroup, ScimListQuery, ScimListResponse, ScimMemberRef, ScimMeta,
+                ScimPatchOperation, ScimPatchRequest, ScimUser,
+            },
+            entities::user_status::UserStatus,
+        },
+    },
+    scim::{
+        SCHEMA_GROUP, SCHEMA_LIST_RESPONSE, SCHEMA_USER, SCHEMA_USER_ATTRIBUTES, ScimError,
+        filter::{ColumnKind, CompareOp, ScimFilter, ScimPath, ScimValue, SqlParams, to_sql},
+    },
+    services::{AttributeEditor, new_user_attributes, replace_password},
+    utils::{
+        USER_PERMISSIONS, commit_transaction, get_pg_client, get_transaction, map_db_error,
+        normalize_email, normalize_username,
+    },
+};
+
+/// Máximo de recursos devueltos por página, anunciado en `ServiceProviderConfig`.
+pub const SCIM_MAX_RESULTS: i64 = 200;
+const SCIM_DEFAULT_COUNT: i64 = 100;
+
+const USER_COLUMNS: &str =
+    "id, username, email, external_id, status, attributes, created_at, updated_at";
+const GROUP_COLUMNS: &str = "g.id, g.display_name, g.external_id, g.created_at, g.updated_at";
+
+pub struct ScimService {
+    pool: PgPool,
+    bearer_token: Option<String>,
+}
+
+/// Estado editable de un usuario SCIM, usado por PUT y PATCH.
+struct UserAttributes {
+    user_name: String,
+    email: String,
+    external_id: Option<String>,
+    active: bool,
+    password: Option<String>,
+    attributes: Option<Map<String, Value>>,
+}
+
+/// Estado editable de un grupo SCIM, usado por PUT y PATCH.
+struct GroupAttributes {
+    display_name: String,
+    external_id: Option<String>,
+    members: Vec<i64>,
+}
+
+impl ScimService {
+    /// `bearer_token` es el token dedicado al aprovisionamiento; sin él todas
+    /// las peticiones SCIM se rechazan.
+    pub fn new(pool: &PgPool, bearer_token: Option<String>) -> Self {
+        ScimService {
+            pool: pool.clone(),
+            bearer_token,
+        }
+    }
+
+    pub fn authorize(&self, authorization: Option<&str>) -> Result<(), ScimError> {
+        let expected = self.bearer_token.as_deref().ok_or_else(|| {
+            ScimError::unauthorized("El aprovisionamiento SCIM no está habilitado")
+        })?;
+        let token = authorization
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .ok_or_else(|| ScimError::unauthorized("Token faltante o inválido"))?;
+
+        if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) {
+            Ok(())
+        } else {
+            Err(ScimError::unauthorized("Token faltante o inválido"))
+        }
+    }
+
+    pub async fn list_users(
+        &self,
+        query: ScimListQuery,
+    ) -> Result<ScimListResponse<ScimUser>, ScimError> {
+        let client = get_pg_client(&self.pool).await?;
+        let (condition, params) = where_clause(query.filter.as_deref(), &user_column)?;
+        let (start_index, count) = pagination(&query);
+        let refs = param_refs(&params);
+
+        let total: i64 = client
+            .query_one(
+                &format!(
+                    "SELECT COUNT(*) FROM users WHERE status <> {} AND {}",
+                    UserStatus::Deleted.code(),
+                    condition
+                ),
+                &refs,
+            )
+            .await
+            .map_err(|e| map_db_error("Error contando usuarios SCIM", e))?
+            .get(0);
+
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {} FROM users WHERE status <> {} AND {} ORDER BY id LIMIT {} OFFSET {}",
+                    USER_COLUMNS,
+                    UserStatus::Deleted.code(),
+                    condition,
+                    count,
+                    start_index - 1
+                ),
+                &refs,
+            )
+            .await
+            .map_err(|e| map_db_error("Error listando usuarios SCIM", e))?;
+
+        let ids: Vec<i64> = rows.iter().map(|r| r.get("id")).collect();
+        let mut groups = self.groups_of_users(&ids).await?;
+        let resources = rows
+            .iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                user_resource(row, groups.remove(&id).unwrap_or_default())
+            })
+            .collect();
+
+        Ok(list_response(total, start_index, resources))
+    }
+
+    pub async fn get_user(&self, id: &str) -> Result<ScimUser, ScimError> {
+        let id = parse_id(id, "Usuario no encontrado")?;
+        self.load_user(id).await
+    }
+
+    pub async fn create_user(&self, user: ScimUser) -> Result<ScimUser, ScimError> {
+        let attrs = UserAttributes::from_resource(user)?.normalized();
+        attrs.validate().await?;
+        let password = attrs.password_hash().await?;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let exists = tx
+            .query_opt(
+                "SELECT 1 FROM users WHERE lower(username) = lower($1) OR lower(email) = $2 LIMIT 1",
+                &[&attrs.user_name, &attrs.email],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando duplicados SCIM", e))?;
+        if exists.is_some() {
+            return Err(ScimError::uniqueness(
+                "Ya existe un usuario con ese nombre de usuario o email",
+            ));
+        }
+
+        let attributes =
+            new_user_attributes(&tx, attrs.attributes.as_ref(), AttributeEditor::Admin).await?;
+
+        let row = tx
+            .query_one(
+                r#"
+                    INSERT INTO users (username, email, password, external_id, permissions, status, attributes)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    RETURNING id
+                "#,
+                &[
+                    &attrs.user_name,
+                    &attrs.email,
+                    &password,
+                    &attrs.external_id,
+                    &USER_PERMISSIONS.bits(),
+                    &attrs.status(),
+                    &attributes,
+                ],
+            )
+            .await
+            .map_err(|e| map_db_error("Error insertando usuario SCIM", e))?;
+
+        commit_transaction(tx, "Error haciendo commit del usuario SCIM").await?;
+        self.load_user(row.get("id")).await
+    }
+
+    pub async fn replace_user(&self, id: &str, user: ScimUser) -> Result<ScimUser, ScimError> {
+        let id = parse_id(id, "Usuario no encontrado")?;
+        self.load_user(id).await?;
+
+        let attrs = UserAttributes::from_resource(user)?;
+        self.write_user(id, attrs).await
+    }
+
+    pub async fn patch_user(
+        &self,
+        id: &str,
+        patch: ScimPatchRequest,
+    ) -> Result<ScimUser, ScimError> {
+        let id = parse_id(id, "Usuario no encontrado")?;
+        let current = self.load_user(id).await?;
+
+        let mut attrs = UserAttributes::from_resource(current)?;
+        // PATCH no modifica los atributos personalizados.
+        attrs.attributes = None;
+        for operation in &patch.operations {
+            apply_operation(operation, |path, value, op| attrs.apply(path, value, op))?;
+        }
+        self.write_user(id, attrs).await
+    }
+
+    /// Marca el usuario como eliminado y lo quita de sus grupos.
+    pub async fn delete_user(&self, id: &str) -> Result<(), ScimError> {
+        let id = parse_id(id, "Usuario no encontrado")?;
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let updated = tx
+            .execute(
+                r#"
+                    UPDATE users SET status = $2, deleted_at = now(), updated_at = now()
+                    WHERE id = $1 AND status <> $2
+                "#,
+                &[&id, &UserStatus::Deleted],
+            )
+            .await
+            .map_err(|e| map_db_error("Error eliminando usuario SCIM", e))?;
+        if updated == 0 {
+            return Err(ScimError::not_found("Usuario no encontrado"));
+        }
+
+        tx.execute("DELETE FROM group_members WHERE user_id = $1", &[&id])
+            .await
+            .map_err(|e| map_db_error("Error quitando usuario de sus grupos", e))?;
+
+        commit_transaction(tx, "Error haciendo commit de la eliminación SCIM").await?;
+        Ok(())
+    }
+
+    pub async fn list_groups(
+        &self,
+        query: ScimListQuery,
+    ) -> Result<ScimListResponse<ScimGroup>, ScimError> {
+        let client = get_pg_client(&self.pool).await?;
+        let (condition, params) = where_clause(query.filter.as_deref(), &group_column)?;
+        let (start_index, count) = pagination(&query);
+        let refs = param_refs(&params);
+
+        let total: i64 = client
+            .query_one(
+                &format!("SELECT COUNT(*) FROM groups g WHERE {}", condition),
+                &refs,
+            )
+            .await
+            .map_err(|e| map_db_error("Error contando grupos SCIM", e))?
+            .get(0);
+
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {} FROM groups g WHERE {} ORDER BY g.id LIMIT {} OFFSET {}",
+                    GROUP_COLUMNS,
+                    condition,
+                    count,
+                    start_index - 1
+                ),
+                &refs,
+            )
+            .await
+            .map_err(|e| map_db_error("Error listando grupos SCIM", e))?;
+
+        let ids: Vec<i64> = rows.iter().map(|r| r.get("id")).collect();
+        let mut members = self.members_of_groups(&ids).await?;
+        let resources = rows
+            .iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                group_resource(row, members.remove(&id).unwrap_or_default())
+            })
+            .collect();
+
+        Ok(list_response(total, start_index, resources))
+    }
+
+    pub async fn get_group(&self, id: &str) -> Result<ScimGroup, ScimError> {
+        let id = parse_id(id, "Grupo no encontrado")?;
+        self.load_group(id).await
+    }
+
+    pub async fn create_group(&self, group: ScimGroup) -> Result<ScimGroup, ScimError> {
+        let attrs = GroupAttributes::from_resource(&group)?;
+        attrs.validate()?;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let exists = tx
+            .query_opt(
+                "SELECT 1 FROM groups WHERE display_name = $1",
+                &[&attrs.display_name],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando duplicados de grupo", e))?;
+        if exists.is_some() {
+            return Err(ScimError::uniqueness("Ya existe un grupo con ese nombre"));
+        }
+
+        let row = tx
+            .query_one(
+                "INSERT INTO groups (display_name, external_id) VALUES ($1, $2) RETURNING id",
+                &[&attrs.display_name, &attrs.external_id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error insertando grupo SCIM", e))?;
+        let id: i64 = row.get("id");
+
+        set_members(&tx, id, &attrs.members).await?;
+        commit_transaction(tx, "Error haciendo commit del grupo SCIM").await?;
+        self.load_group(id).await
+    }
+
+    pub async fn replace_group(&self, id: &str, group: ScimGroup) -> Result<ScimGroup, ScimError> {
+        let id = parse_id(id, "Grupo no encontrado")?;
+        self.load_group(id).await?;
+
+        let attrs = GroupAttributes::from_resource(&group)?;
+        self.write_group(id, attrs).await
+    }
+
+    pub async fn patch_group(
+        &self,
+        id: &str,
+        patch: ScimPatchRequest,
+    ) -> Result<ScimGroup, ScimError> {
+        let id = parse_id(id, "Grupo no encontrado")?;
+        let current = self.load_group(id).await?;
+
+        let mut attrs = GroupAttributes::from_resource(&current)?;
+        for operation in &patch.operations {
+            apply_operation(operation, |path, value, op| attrs.apply(path, value, op))?;
+        }
+        self.write_group(id, attrs).await
+    }
+
+    pub async fn delete_group(&self, id: &str) -> Result<(), ScimError> {
+        let id = parse_id(id, "Grupo no encontrado")?;
+        let client = get_pg_client(&self.pool).await?;
+
+        let deleted = client
+            .execute("DELETE FROM groups WHERE id = $1", &[&id])
+            .await
+            .map_err(|e| map_db_error("Error eliminando grupo SCIM", e))?;
+        if deleted == 0 {
+            return Err(ScimError::not_found("Grupo no encontrado"));
+        }
+        Ok(())
+    }
+
+    async fn load_user(&self, id: i64) -> Result<ScimUser, ScimError> {
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                &format!(
+                    "SELECT {} FROM users WHERE id = $1 AND status <> $2",
+                    USER_COLUMNS
+                ),
+                &[&id, &UserStatus::Deleted],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando usuario SCIM", e))?
+            .ok_or_else(|| ScimError::not_found("Usuario no encontrado"))?;
+
+        let groups = self.groups_of_users(&[id]).await?.remove(&id);
+        Ok(user_resource(&row, groups.unwrap_or_default()))
+    }
+
+    async fn load_group(&self, id: i64) -> Result<ScimGroup, ScimError> {
+        let client = get_pg_client(&self.pool).await?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {} FROM groups g WHERE g.id = $1", GROUP_COLUMNS),
+                &[&id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando grupo SCIM", e))?
+            .ok_or_else(|| ScimError::not_found("Grupo no encontrado"))?;
+
+        let members = self.members_of_groups(&[id]).await?.remove(&id);
+        Ok(group_resource(&row, members.unwrap_or_default()))
+    }
+
+    async fn write_user(&self, id: i64, attrs: UserAttributes) -> Result<ScimUser, ScimError> {
+        let attrs = attrs.normalized();
+        attrs.validate().await?;
+        let password = attrs.password_hash().await?;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let duplicate = tx
+            .query_opt(
+                r#"
+                    SELECT 1 FROM users
+                    WHERE (lower(username) = lower($1) OR lower(email) = $2) AND id <> $3
+                    LIMIT 1
+                "#,
+                &[&attrs.user_name, &attrs.email, &id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando duplicados SCIM", e))?;
+        if duplicate.is_some() {
+            return Err(ScimError::uniqueness(
+                "Ya existe un usuario con ese nombre de usuario o email",
+            ));
+        }
+
+        tx.execute(
+            r#"
+                UPDATE users
+                SET username = $1, email = $2, external_id = $3, status = $4, updated_at = now()
+                WHERE id = $5
+            "#,
+            &[
+                &attrs.user_name,
+                &attrs.email,
+                &attrs.external_id,
+                &attrs.status(),
+                &id,
+            ],
+        )
+        .await
+        .map_err(|e| map_db_error("Error actualizando usuario SCIM", e))?;
+
+        if let Some(values) = &attrs.attributes {
+            let attributes = new_user_attributes(&tx, Some(values), AttributeEditor::Admin).await?;
+            tx.execute(
+                "UPDATE users SET attributes = $1 WHERE id = $2",
+                &[&attributes, &id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error actualizando los atributos SCIM", e))?;
+        }
+
+        if let Some(password) = password {
+            let history_size = get_config().password_policy.history_size;
+            replace_password(&tx, id, &password, history_size).await?;
+        }
+
+        commit_transaction(tx, "Error haciendo commit del usuario SCIM").await?;
+        self.load_user(id).await
+    }
+
+    async fn write_group(&self, id: i64, attrs: GroupAttributes) -> Result<ScimGroup, ScimError> {
+        attrs.validate()?;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let duplicate = tx
+            .query_opt(
+                "SELECT 1 FROM groups WHERE display_name = $1 AND id <> $2",
+                &[&attrs.display_name, &id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando duplicados de grupo", e))?;
+        if duplicate.is_some() {
+            return Err(ScimError::uniqueness("Ya existe un grupo con ese nombre"));
+        }
+
+        tx.execute(
+            r#"
+                UPDATE groups
+                SET display_name = $1, external_id = $2, updated_at = now()
+                WHERE id = $3
+            "#,
+            &[&attrs.display_name, &attrs.external_id, &id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error actualizando grupo SCIM", e))?;
+
+        set_members(&tx, id, &attrs.members).await?;
+        commit_transaction(tx, "Error haciendo commit del grupo SCIM").await?;
+        self.load_group(id).await
+    }
+
+    async fn groups_of_users(
+        &self,
+        user_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<ScimMemberRef>>, ScimError> {
+        let client = get_pg_client(&self.pool).await?;
+        let rows = client
+            .query(
+                r#"
+                    SELECT gm.user_id, g.id, g.display_name
+                    FROM group_members gm
+                    JOIN groups g ON g.id = gm.group_id
+                    WHERE gm.user_id = ANY($1)
+                    ORDER BY g.id
+                "#,
+                &[&user_ids],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando grupos de usuarios", e))?;
+
+        let mut groups: HashMap<i64, Vec<ScimMemberRef>> = HashMap::new();
+        for row in rows {
+            let group_id: i64 = row.get("id");
+            groups
+                .entry(row.get("user_id"))
+                .or_default()
+                .push(ScimMemberRef {
+                    value: group_id.to_string(),
+                    display: row.get("display_name"),
+                    reference: Some(format!("/scim/v2/Groups/{}", group_id)),
+                });
+        }
+        Ok(groups)
+    }
+
+    async fn members_of_groups(
+        &self,
+        group_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<ScimMemberRef>>, ScimError> {
+        let client = get_pg_client(&self.pool).await?;
+        let rows = client
+            .query(
+                r#"
+                    SELECT gm.group_id, u.id, u.username
+                    FROM group_members gm
+                    JOIN users u ON u.id = gm.user_id
+                    WHERE gm.group_id = ANY($1) AND u.status <> $2
+                    ORDER BY u.id
+                "#,
+                &[&group_ids, &UserStatus::Deleted],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando miembros de grupos", e))?;
+
+        let mut members: HashMap<i64, Vec<ScimMemberRef>> = HashMap::new();
+        for row in rows {
+            let user_id: i64 = row.get("id");
+            members
+                .entry(row.get("group_id"))
+                .or_default()
+                .push(ScimMemberRef {
+                    value: user_id.to_string(),
+                    display: row.get("username"),
+                    reference: Some(format!("/scim/v2/Users/{}", user_id)),
+                });
+        }
+        Ok(members)
+    }
+}
+
+impl UserAttributes {
+    fn from_resource(user: ScimUser) -> Result<Self, ScimError> {
+        let email = user
+            .emails
+            .iter()
+            .find(|e| e.primary == Some(true))
+            .or_else(|| user.emails.first())
+            .map(|e| e.value.clone())
+            .unwrap_or_default();
+
+        Ok(UserAttributes {
+            user_name: user.user_name,
+            email,
+            external_id: user.external_id,
+            active: user.active.unwrap_or(true),
+            password: user.password,
+            attributes: user.attributes,
+        })
+    }
+
+    /// Username y email en su forma canónica, igual que en el alta por API.
+    fn normalized(self) -> Self {
+        UserAttributes {
+            user_name: normalize_username(&self.user_name),
+            email: normalize_email(&self.email),
+            ..self
+        }
+    }
+
+    async fn validate(&self) -> Result<(), ScimError> {
+        let user_name = self.user_name.trim();
+        if user_name.is_empty() || user_name.len() > 100 {
+            return Err(ScimError::invalid_value(
+                "userName es obligatorio y debe tener como máximo 100 caracteres",
+            ));
+        }
+        if !self.email.validate_email() || self.email.len() > 100 {
+            return Err(ScimError::invalid_value("Se requiere un email válido"));
+        }
+        if let Some(password) = &self.password {
+            validate_password(password, &[&self.user_name, &self.email]).await?;
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> UserStatus {
+        if self.active {
+            UserStatus::Active
+        } else {
+            UserStatus::Inactive
+        }
+    }
+
+    async fn password_hash(&self) -> Result<Option<String>, ScimError> {
+        match &self.password {
+            Some(password) => Ok(Some(hash_password_pooled(password).await?)),
+            None => Ok(None),
+        }
+    }
+
+    fn apply(&mut self, path: &ScimPath, value: Option<&Value>, op: &str) -> Result<(), ScimError> {
+        let remove = op == "remove";
+        match path.attribute.as_str() {
+            "username" if remove => Err(ScimError::invalid_value("userName es obligatorio")),
+            "username" => {
+                self.user_name = string_value(value)?;
+                Ok(())
+            }
+            "emails" if remove => Err(ScimError::invalid_value("El email es obligatorio")),
+            "emails" => {
+                self.email = email_value(value)?;
+                Ok(())
+            }
+            "externalid" if remove => {
+                self.external_id = None;
+                Ok(())
+            }
+            "externalid" => {
+                self.external_id = Some(string_value(value)?);
+                Ok(())
+            }
+            "active" if remove => Err(ScimError::invalid_value("active no se puede eliminar")),
+            "active" => {
+                self.active = bool_value(value)?;
+                Ok(())
+            }
+            "password" if remove => Err(ScimError::invalid_value("password no se puede eliminar")),
+            "password" => {
+                self.password = Some(string_value(value)?);
+                Ok(())
+            }
+            // Atributos que no se almacenan (name, displayName, etc.) se ignoran.
+            _ => Ok(()),
+        }
+    }
+}
+
+impl GroupAttributes {
+    fn from_resource(group: &ScimGroup) -> Result<Self, ScimError> {
+        Ok(GroupAttributes {
+            display_name: group.display_name.clone(),
+            external_id: group.external_id.clone(),
+            members: member_ids(&group.members)?,
+        })
+    }
+
+    fn validate(&self) -> Result<(), ScimError> {
+        let display_name = self.display_name.trim();
+        if display_name.is_empty() || display_name.len() > 255 {
+            return Err(ScimError::invalid_value(
+                "displayName es obligatorio y debe tener como máximo 255 caracteres",
+            ));
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, path: &ScimPath, value: Option<&Value>, op: &str) -> Result<(), ScimError> {
+        match (path.attribute.as_str(), op) {
+            ("displayname", "remove") => {
+                Err(ScimError::invalid_value("displayName es obligatorio"))
+            }
+            ("displayname", _) => {
+                self.display_name = string_value(value)?;
+                Ok(())
+            }
+            ("externalid", "remove") => {
+                self.external_id = None;
+                Ok(())
+            }
+            ("externalid", _) => {
+                self.external_id = Some(string_value(value)?);
+                Ok(())
+            }
+            ("members", "remove") => {
+                let removed = match (&path.filter, value) {
+                    (Some(filter), _) => filter_member_ids(filter)?,
+                    (None, Some(value)) => member_ids(&members_value(value)?)?,
+                    (None, None) => self.members.clone(),
+                };
+                self.members.retain(|id| !removed.contains(id));
+                Ok(())
+            }
+            ("members", op) => {
+                let value = value.ok_or_else(|| ScimError::invalid_value("Falta el valor"))?;
+                let ids = member_ids(&members_value(value)?)?;
+                if op == "replace" {
+                    self.members.clear();
+                }
+                for id in ids {
+                    if !self.members.contains(&id) {
+                        self.members.push(id);
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Aplica una operación PATCH. Sin `path`, el valor es un objeto cuyas claves
+/// son las rutas a modificar.
+fn apply_operation<F>(operation: &ScimPatchOperation, mut apply: F) -> Result<(), ScimError>
+where
+    F: FnMut(&ScimPath, Option<&Value>, &str) -> Result<(), ScimError>,
+{
+    let op = operation.op.to_lowercase();
+    if !matches!(op.as_str(), "add" | "replace" | "remove") {
+        return Err(ScimError::invalid_syntax(&format!(
+            "Operación PATCH desconocida: {}",
+            operation.op
+        )));
+    }
+
+    match &operation.path {
+        Some(path) => apply(&ScimPath::parse(path)?, operation.value.as_ref(), &op),
+        None => match &operation.value {
+            Some(Value::Object(map)) if op != "remove" => {
+                for (key, value) in map {
+                    apply(&ScimPath::parse(key)?, Some(value), &op)?;
+                }
+                Ok(())
+            }
+            _ => Err(ScimError::invalid_path(
+                "Se requiere path o un objeto como valor",
+            )),
+        },
+    }
+}
+
+fn user_column(attribute: &str) -> Option<(&'static str, ColumnKind)> {
+    match attribute {
+        "id" => Some(("id", ColumnKind::Id)),
+        "username" => Some(("username", ColumnKind::Text)),
+        "emails" | "emails.value" => Some(("email", ColumnKind::Text)),
+        "emails.type" | "emails.primary" => Some(("email", ColumnKind::Ignored)),
+        "externalid" => Some(("external_id", ColumnKind::ExactText)),
+        "active" => Some(("status", ColumnKind::Active)),
+        "meta.created" => Some(("created_at", ColumnKind::Timestamp)),
+        "meta.lastmodified" => Some(("updated_at", ColumnKind::Timestamp)),
+        _ => None,
+    }
+}
+
+fn group_column(attribute: &str) -> Option<(&'static str, ColumnKind)> {
+    match attribute {
+        "id" => Some(("g.id", ColumnKind::Id)),
+        "displayname" => Some(("g.display_name", ColumnKind::Text)),
+        "externalid" => Some(("g.external_id", ColumnKind::ExactText)),
+        "members" | "members.value" => Some((
+            "SELECT 1 FROM group_members gm WHERE gm.group_id = g.id AND gm.user_id = $USER",
+            ColumnKind::Member,
+        )),
+        "meta.created" => Some(("g.created_at", ColumnKind::Timestamp)),
+        "meta.lastmodified" => Some(("g.updated_at", ColumnKind::Timestamp)),
+        _ => None,
+    }
+}
+
+fn where_clause<F>(filter: Option<&str>, column: &F) -> Result<(String, SqlParams), ScimError>
+where
+    F: Fn(&str) -> Option<(&'static str, ColumnKind)>,
+{
+    let mut params = SqlParams::new();
+    let condition = match filter.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(filter) => to_sql(&ScimFilter::parse(filter)?, column, &mut params)?,
+        None => "TRUE".to_string(),
+    };
+    Ok((condition, params))
+}
+
+fn param_refs(params: &SqlParams) -> Vec<&(dyn ToSql + Sync)> {
+    params
+        .iter()
+        .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+        .collect()
+}
+
+/// `startIndex` es 1-based; `count` se limita a `SCIM_MAX_RESULTS`.
+fn pagination(query: &ScimListQuery) -> (i64, i64) {
+    let start_index = query.start_index.unwrap_or(1).max(1);
+    let count = query
+        .count
+        .unwrap_or(SCIM_DEFAULT_COUNT)
+        .clamp(0, SCIM_MAX_RESULTS);
+    (start_index, count)
+}
+
+fn list_response<T>(total: i64, start_index: i64, resources: Vec<T>) -> ScimListResponse<T> {
+    ScimListResponse {
+        schemas: vec![SCHEMA_LIST_RESPONSE.to_string()],
+        total_results: total,
+        start_index,
+        items_per_page: resources.len() as i64,
+        resources,
+    }
+}
+
+fn parse_id(id: &str, not_found: &str) -> Result<i64, ScimError> {
+    id.parse().map_err(|_| ScimError::not_found(not_found))
+}
+
+fn user_resource(row: &Row, groups: Vec<ScimMemberRef>) -> ScimUser {
+    let id: i64 = row.get("id");
+    let status: UserStatus = row.get("status");
+    let attributes = row.get::<_, Json<Map<String, Value>>>("attributes").0;
+    let mut schemas = vec![SCHEMA_USER.to_string()];
+    if !attributes.is_empty() {
+        schemas.push(SCHEMA_USER_ATTRIBUTES.to_string());
+    }
+    ScimUser {
+        schemas,
+        id: Some(id.to_string()),
+        external_id: row.get("external_id"),
+        user_name: row.get("username"),
+        emails: vec![ScimEmail {
+            value: row.get("email"),
+            kind: Some("work".to_string()),
+            primary: Some(true),
+        }],
+        active: Some(status == UserStatus::Active),
+        groups,
+        password: None,
+        attributes: (!attributes.is_empty()).then_some(attributes),
+        meta: Some(ScimMeta {
+            resource_type: "User".to_string(),
+            created: row.get("created_at"),
+            last_modified: row.get("updated_at"),
+            location: format!("/scim/v2/Users/{}", id),
+        }),
+    }
+}
+
+fn group_resource(row: &Row, members: Vec<ScimMemberRef>) -> ScimGroup {
+    let id: i64 = row.get("id");
+    ScimGroup {
+        schemas: vec![SCHEMA_GROUP.to_string()],
+        id: Some(id.to_string()),
+        external_id: row.get("external_id"),
+        display_name: row.get("display_name"),
+        members,
+        meta: Some(ScimMeta {
+            resource_type: "Group".to_string(),
+            created: row.get("created_at"),
+            last_modified: row.get("updated_at"),
+            location: format!("/scim/v2/Groups/{}", id),
+        }),
+    }
+}
+
+async fn set_members(
+    tx: &deadpool_postgres::Transaction<'_>,
+    group_id: i64,
+    members: &[i64],
+) -> Result<(), ScimError> {
+    let existing: i64 = tx
+        .query_one(
+            "SELECT COUNT(*) FROM users WHERE id = ANY($1) AND status <> $2",
+            &[&members, &UserStatus::Deleted],
+        )
+        .await
+        .map_err(|e| map_db_error("Error verificando miembros del grupo", e))?
+        .get(0);
+    if existing != members.len() as i64 {
+        return Err(ScimError::invalid_value(
+            "Algún miembro del grupo no existe",
+        ));
+    }
+
+    tx.execute(
+        "DELETE FROM group_members WHERE group_id = $1",
+        &[&group_id],
+    )
+    .await
+    .map_err(|e| map_db_error("Error limpiando miembros del grupo", e))?;
+    tx.execute(
+        "INSERT INTO group_members (group_id, user_id) SELECT $1, unnest($2::bigint[])",
+        &[&group_id, &members],
+    )
+    .await
+    .map_err(|e| map_db_error("Error insertando miembros del grupo", e))?;
+    Ok(())
+}
+
+fn member_ids(members: &[ScimMemberRef]) -> Result<Vec<i64>, ScimError> {
+    let mut ids = Vec::with_capacity(members.len());
+    for member in members {
+        let id: i64 = member.value.parse().map_err(|_| {
+            ScimError::invalid_value(&format!("Id de miembro inválido: {}", member.value))
+        })?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Ids seleccionados por un filtro de ruta como `members[value eq "2"]`.
+fn filter_member_ids(filter: &ScimFilter) -> Result<Vec<i64>, ScimError> {
+    match filter {
+        ScimFilter::Or(a, b) => {
+            let mut ids = filter_member_ids(a)?;
+            ids.extend(filter_member_ids(b)?);
+            Ok(ids)
+        }
+        ScimFilter::Compare {
+            attribute,
+            op: CompareOp::Eq,
+            value: ScimValue::String(value),
+        } if attribute == "value" => Ok(vec![value.parse().map_err(|_| {
+            ScimError::invalid_value(&format!("Id de miembro inválido: {}", value))
+        })?]),
+        _ => Err(ScimError::invalid_filter(
+            "Solo se soporta members[value eq \"id\"]",
+        )),
+    }
+}
+
+fn members_value(value: &Value) -> Result<Vec<ScimMemberRef>, ScimError> {
+    let value = match value {
+        Value::Array(_) => value.clone(),
+        other => Value::Array(vec![other.clone()]),
+    };
+    serde_json::from_value(value).map_err(|_| ScimError::invalid_value("Miembros inválidos"))
+}
+
+fn string_value(value: Option<&Value>) -> Result<String, ScimError> {
+    match value {
+        Some(Value::String(s)) => Ok(s.clone()),
+        _ => Err(ScimError::invalid_value("Se esperaba un texto")),
+    }
+}
+
+/// Algunos clientes (ej. Entra ID) envían los booleanos como `"True"`/`"False"`.
+fn bool_value(value: Option<&Value>) -> Result<bool, ScimError> {
+    match value {
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(Value::String(s)) if s.eq_ignore_ascii_case("true") => Ok(true),
+        Some(Value::String(s)) if s.eq_ignore_ascii_case("false") => Ok(false),
+        _ => Err(ScimError::invalid_value("Se esperaba un booleano")),
+    }
+}
+
+/// Acepta un email suelto, un objeto `{value}` o una lista de ellos.
+fn email_value(value: Option<&Value>) -> Result<String, ScimError> {
+    let invalid = || ScimError::invalid_value("Email inválido");
+    match value {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(Value::Object(_)) => {
+            let email: ScimEmail = serde_json::from_value(value.cloned().unwrap_or_default())
+                .map_err(|_| invalid())?;
+            Ok(email.value)
+        }
+        Some(Value::Array(items)) => {
+            let emails: Vec<ScimEmail> =
+                serde_json::from_value(Value::Array(items.clone())).map_err(|_| invalid())?;
+            emails
+                .iter()
+                .find(|e| e.primary == Some(true))
+                .or_else(|| emails.first())
+                .map(|e| e.value.clone())
+                .ok_or_else(invalid)
+        }
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,38 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::{error, info};
+
+use super::{PrivacyService, UsersService};
+use crate::config::UserPurgeConfig;
+
+/// Lanza en segundo plano el mantenimiento de usuarios: anonimiza las bajas
+/// cuyo período de gracia terminó y borra definitivamente a los eliminados
+/// hace más de `after_days` días (0 deshabilita el borrado definitivo).
+pub fn spawn_user_purge(
+    users: Arc<UsersService>,
+    privacy: Arc<PrivacyService>,
+    config: &UserPurgeConfig,
+) {
+    let after_days = config.after_days;
+    let period = Duration::from_secs(u64::from(config.interval_minutes) * 60);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match privacy.erase_due().await {
+                Ok(0) => {}
+                Ok(erased) => info!(erased, "Bajas de usuarios ejecutadas"),
+                Err((status, _)) => error!(%status, "Error ejecutando bajas de usuarios"),
+            }
+            if after_days == 0 {
+                continue;
+            }
+            match users.purge_deleted(after_days).await {
+                Ok(0) => {}
+                Ok(purged) => info!(purged, "Usuarios eliminados purgados"),
+                Err((status, _)) => error!(%status, "Error purgando usuarios eliminados"),
+            }
+        }
+    });
+}
@@ -0,0 +1,579 @@
+use std::{collections::HashSet, io};
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures_util::{Stream, StreamExt, stream};
+use serde_json::{Map, Value};
+use tokio_postgres::{error::SqlState, types::ToSql};
+use tracing::{error, info};
+
+use crate::{
+    auth::{HASHING_POOL, hash_password_queued, is_supported_hash_with, validate_password_with},
+    config::{PasswordPolicyConfig, get_config},
+    database::{
+        connection::PgPool,
+        models::{
+            dto::{ImportReport, ImportRowError, TransferFormat, UserRecord},
+            entities::{
+                attribute_definition::AttributeDefinition, user::User, user_status::UserStatus,
+            },
+        },
+    },
+    services::{AttributeEditor, apply_attributes, check_required_attributes, load_definitions},
+    utils::{
+        ApiError, Permissions, USER_PERMISSIONS, commit_transaction, errors::HttpError,
+        get_pg_client, get_transaction, map_db_error, validate_dto,
+    },
+};
+
+/// Usuarios por transacción al importar. Un fallo inesperado solo deshace su
+/// lote; los errores de una fila no afectan a las demás.
+pub const IMPORT_BATCH_SIZE: usize = 500;
+
+/// Columnas del CSV exportado, en el orden de `UserRecord`.
+const EXPORT_COLUMNS: [&str; 10] = [
+    "username",
+    "email",
+    "permissions",
+    "status",
+    "displayName",
+    "locale",
+    "timezone",
+    "avatarUrl",
+    "phoneNumber",
+    "attributes",
+];
+
+/// Columnas aceptadas al importar un CSV.
+const IMPORT_COLUMNS: [&str; 12] = [
+    "username",
+    "email",
+    "password",
+    "passwordHash",
+    "permissions",
+    "status",
+    "displayName",
+    "locale",
+    "timezone",
+    "avatarUrl",
+    "phoneNumber",
+    "attributes",
+];
+
+/// Fila leída del archivo: el número de línea y el registro o su error.
+type ParsedRow = (u64, Result<UserRecord, ApiError>);
+
+/// Usuario validado, listo para insertar.
+struct PendingUser {
+    line: u64,
+    record: UserRecord,
+    permissions: i64,
+    status: UserStatus,
+    attributes: Value,
+}
+
+/// Importación y exportación masiva de usuarios en CSV y NDJSON.
+pub struct UserTransferService {
+    pool: PgPool,
+    password_policy: PasswordPolicyConfig,
+    batch_size: usize,
+}
+
+impl UserTransferService {
+    pub fn new(pool: &PgPool) -> Self {
+        UserTransferService {
+            pool: pool.clone(),
+            password_policy: get_config().password_policy.clone(),
+            batch_size: IMPORT_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Valida cada fila igual que el alta (más permisos, estado, perfil y
+    /// atributos) y crea los usuarios válidos. Las filas con errores se
+    /// reportan con su número de línea y no se crean. Un archivo ilegible
+    /// (encabezado inválido) se rechaza entero con 400.
+    pub async fn import(
+        &self,
+        body: &[u8],
+        format: TransferFormat,
+        dry_run: bool,
+    ) -> Result<ImportReport, ApiError> {
+        let rows = match format {
+            TransferFormat::Csv => parse_csv(body)?,
+            TransferFormat::Ndjson => parse_ndjson(body),
+        };
+        let total = rows.len();
+        let mut errors = Vec::new();
+        let mut pending = Vec::new();
+
+        let context = self.load_context(&rows).await?;
+        let mut seen_emails = HashSet::new();
+        let mut seen_usernames = HashSet::new();
+        for (line, parsed) in rows {
+            let username = parsed.as_ref().ok().map(|r| r.username.clone());
+            let prepared = match parsed {
+                Ok(record) => self.prepare(line, record, &context).await,
+                Err(e) => Err(e),
+            };
+            let result = prepared.and_then(|user| {
+                // La primera fila válida gana; las siguientes son duplicados.
+                let username = user.record.username.to_lowercase();
+                if seen_emails.contains(&user.record.email) || seen_usernames.contains(&username) {
+                    return Err(HttpError::conflict(
+                        "El username o el email se repite en el archivo",
+                    ));
+                }
+                seen_emails.insert(user.record.email.clone());
+                seen_usernames.insert(username);
+                Ok(user)
+            });
+            match result {
+                Ok(user) => pending.push(user),
+                Err(e) => errors.push(row_error(line, username, e)),
+            }
+        }
+
+        let mut imported = pending.len();
+        if !dry_run {
+            imported = 0;
+            for batch in pending.chunks(self.batch_size) {
+                imported += self.insert_batch(batch, &mut errors).await?;
+            }
+            info!(imported, failed = errors.len(), "Importación de usuarios");
+        }
+
+        errors.sort_by_key(|e| e.line);
+        let failed_lines: HashSet<u64> = errors.iter().map(|e| e.line).collect();
+        let without_password = pending
+            .iter()
+            .filter(|u| u.record.password.is_none() && u.record.password_hash.is_none())
+            .map(|u| u.line)
+            .filter(|line| !failed_lines.contains(line))
+            .collect();
+        Ok(ImportReport {
+            dry_run,
+            total,
+            imported,
+            failed: errors.len(),
+            without_password,
+            errors,
+        })
+    }
+
+    /// Exporta los usuarios ordenados por id, leyendo las filas a medida que
+    /// se envían. Sin `status` se omiten los eliminados.
+    pub async fn export(
+        &self,
+        format: TransferFormat,
+        status: Option<UserStatus>,
+    ) -> Result<impl Stream<Item = Result<Bytes, io::Error>> + Send + 'static, ApiError> {
+        let (condition, status) = match status {
+            Some(status) => ("status = $1", status),
+            None => ("status <> $1", UserStatus::Deleted),
+        };
+        let client = get_pg_client(&self.pool).await?;
+        let rows = client
+            .query_raw(
+                &format!(
+                    r#"
+                        SELECT
+                            id,
+                            username,
+                            email,
+                            permissions,
+                            status,
+                            display_name,
+                            locale,
+                            timezone,
+                            avatar_url,
+                            phone_number,
+                            attributes,
+                            created_at,
+                            updated_at
+                        FROM users WHERE {} ORDER BY id
+                    "#,
+                    condition
+                ),
+                [&status as &(dyn ToSql + Sync)],
+            )
+            .await
+            .map_err(|e| map_db_error("Error ejecutando la exportación", e))?;
+
+        let header = match format {
+            TransferFormat::Csv => Some(csv_line(&EXPORT_COLUMNS)),
+            TransferFormat::Ndjson => None,
+        };
+        // El cliente viaja con el stream para no devolver la conexión al pool
+        // mientras se leen las filas.
+        let rows = stream::unfold(
+            (client, Box::pin(rows)),
+            move |(client, mut rows)| async move {
+                let row = rows.next().await?;
+                let chunk = row.map_err(io::Error::other).and_then(|row| {
+                    let user = User::from_row(&row).map_err(|e| io::Error::other(e.to_string()))?;
+                    encode_record(format, UserRecord::from(user))
+                });
+                Some((chunk, (client, rows)))
+            },
+        );
+        Ok(stream::iter(header).chain(rows))
+    }
+
+    /// Definiciones de atributos, zonas horarias y usuarios existentes con
+    /// alguno de los emails o usernames del archivo.
+    async fn load_context(&self, rows: &[ParsedRow]) -> Result<ImportContext, ApiError> {
+        let records = rows.iter().filter_map(|(_, r)| r.as_ref().ok());
+        let (emails, usernames): (Vec<String>, Vec<String>) = records
+            .map(|r| {
+                let r = r.clone().normalized();
+                (r.email, r.username.to_lowercase())
+            })
+            .unzip();
+
+        let client = get_pg_client(&self.pool).await?;
+        let definitions = load_definitions(&client).await?;
+        let timezones = client
+            .query("SELECT name FROM pg_timezone_names", &[])
+            .await
+            .map_err(|e| map_db_error("Error consultando las zonas horarias", e))?
+            .iter()
+            .map(|row| row.get("name"))
+            .collect();
+
+        let mut existing_emails = HashSet::new();
+        let mut existing_usernames = HashSet::new();
+        let existing = client
+            .query(
+                r#"
+                    SELECT lower(email) AS email, lower(username) AS username FROM users
+                    WHERE lower(email) = ANY($1) OR lower(username) = ANY($2)
+                "#,
+                &[&emails, &usernames],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando usuarios existentes", e))?;
+        for row in existing {
+            existing_emails.insert(row.get::<_, String>("email"));
+            existing_usernames.insert(row.get::<_, String>("username"));
+        }
+
+        Ok(ImportContext {
+            definitions,
+            timezones,
+            existing_emails,
+            existing_usernames,
+        })
+    }
+
+    async fn prepare(
+        &self,
+        line: u64,
+        record: UserRecord,
+        context: &ImportContext,
+    ) -> Result<PendingUser, ApiError> {
+        let record = record.normalized();
+        validate_dto(&record)?;
+
+        match (&record.password, &record.password_hash) {
+            (Some(_), Some(_)) => {
+                return Err(HttpError::bad_request(
+                    "Indique password o passwordHash, no ambos",
+                ));
+            }
+            (Some(password), None) => {
+                validate_password_with(
+                    password,
+                    &[&record.username, &record.email],
+                    &self.password_policy,
+                )
+                .await?
+            }
+            (None, Some(hash)) if !is_supported_hash_with(hash, &get_config().password) => {
+                return Err(HttpError::bad_request(
+                    "passwordHash debe ser un hash Argon2 o bcrypt válido",
+                ));
+            }
+            _ => {}
+        }
+
+        let permissions = match record.permissions {
+            Some(bits) => Permissions::from_bits(bits)
+                .ok_or_else(|| HttpError::bad_request("Permisos inválidos"))?,
+            None => USER_PERMISSIONS,
+        };
+
+        if let Some(timezone) = &record.timezone
+            && !context.timezones.contains(timezone)
+        {
+            return Err(HttpError::bad_request("Zona horaria desconocida"));
+        }
+
+        let mut attributes = Map::new();
+        apply_attributes(
+            &context.definitions,
+            &mut attributes,
+            record.attributes.as_ref().unwrap_or(&Map::new()),
+            AttributeEditor::Admin,
+        )?;
+        check_required_attributes(&context.definitions, &attributes)?;
+
+        if context.existing_emails.contains(&record.email)
+            || context
+                .existing_usernames
+                .contains(&record.username.to_lowercase())
+        {
+            return Err(HttpError::conflict(
+                "Ya existe un usuario con ese nombre de usuario o email",
+            ));
+        }
+
+        Ok(PendingUser {
+            line,
+            permissions: permissions.bits(),
+            status: record.status.unwrap_or(UserStatus::Active),
+            attributes: Value::Object(attributes),
+            record,
+        })
+    }
+
+    /// Inserta un lote en una transacción, cada fila en su savepoint para
+    /// reportar un conflicto de unicidad sin perder el resto del lote.
+    async fn insert_batch(
+        &self,
+        batch: &[PendingUser],
+        errors: &mut Vec<ImportRowError>,
+    ) -> Result<usize, ApiError> {
+        // Se hashea antes de tomar la conexión, como en el alta. Se encolan a
+        // lo sumo tantos hashes como admite el pool, sin timeout de cola, para
+        // no competir con los logins; un fallo se reporta en su fila.
+        let jobs: Vec<_> = batch
+            .iter()
+            .map(|user| {
+                let password = user.record.password.clone();
+                let hash = user.record.password_hash.clone();
+                async move {
+                    match password {
+                        Some(password) => hash_password_queued(&password).await.map(Some),
+                        None => Ok(hash),
+                    }
+                }
+            })
+            .collect();
+        let hashes: Vec<Result<Option<String>, ApiError>> = stream::iter(jobs)
+            .buffered(HASHING_POOL.concurrency())
+            .collect()
+            .await;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let mut tx = get_transaction(&mut client).await?;
+        let mut inserted = 0;
+        for (user, hash) in batch.iter().zip(hashes) {
+            let record = &user.record;
+            let hash = match hash {
+                Ok(hash) => hash,
+                Err(e) => {
+                    errors.push(row_error(user.line, Some(record.username.clone()), e));
+                    continue;
+                }
+            };
+            // Los eliminados entran al ciclo de purgado como un borrado lógico.
+            let deleted_at = (user.status == UserStatus::Deleted).then(Utc::now);
+            let savepoint = tx
+                .savepoint("import_row")
+                .await
+                .map_err(|e| map_db_error("Error creando el savepoint de la importación", e))?;
+            let result = savepoint
+                .execute(
+                    r#"
+                        INSERT INTO users (
+                            username, email, password, permissions, status,
+                            display_name, locale, timezone, avatar_url, phone_number,
+                            attributes, deleted_at
+                        )
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    "#,
+                    &[
+                        &record.username,
+                        &record.email,
+                        &hash,
+                        &user.permissions,
+                        &user.status,
+                        &record.display_name,
+                        &record.locale,
+                        &record.timezone,
+                        &record.avatar_url,
+                        &record.phone_number,
+                        &user.attributes,
+                        &deleted_at,
+                    ],
+                )
+                .await;
+            match result {
+                Ok(_) => {
+                    savepoint.commit().await.map_err(|e| {
+                        map_db_error("Error liberando el savepoint de la importación", e)
+                    })?;
+                    inserted += 1;
+                }
+                Err(e) => {
+                    savepoint.rollback().await.map_err(|e| {
+                        map_db_error("Error deshaciendo el savepoint de la importación", e)
+                    })?;
+                    let error = if e.code() == Some(&SqlState::UNIQUE_VIOLATION) {
+                        HttpError::conflict(
+                            "Ya existe un usuario con ese nombre de usuario o email",
+                        )
+                    } else {
+                        error!(error = %e, line = user.line, "Error importando usuario");
+                        HttpError::internal_server_error()
+                    };
+                    errors.push(row_error(user.line, Some(record.username.clone()), error));
+                }
+            }
+        }
+        commit_transaction(tx, "Error haciendo commit del lote de importación").await?;
+        Ok(inserted)
+    }
+}
+
+struct ImportContext {
+    definitions: Vec<AttributeDefinition>,
+    timezones: HashSet<String>,
+    existing_emails: HashSet<String>,
+    existing_usernames: HashSet<String>,
+}
+
+fn row_error(line: u64, username: Option<String>, (_, error): ApiError) -> ImportRowError {
+    ImportRowError {
+        line,
+        username,
+        errors: error.0.errors,
+    }
+}
+
+/// Las celdas vacías se omiten; `permissions` y `attributes` se convierten a
+/// número y objeto para leer cada fila como un `UserRecord`.
+fn parse_csv(body: &[u8]) -> Result<Vec<ParsedRow>, ApiError> {
+    let body = body.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(body);
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body);
+    let headers = reader
+        .headers()
+        .map_err(|e| HttpError::bad_request(&format!("Encabezado CSV inválido: {}", e)))?
+        .clone();
+    if let Some(unknown) = headers.iter().find(|h| !IMPORT_COLUMNS.contains(h)) {
+        return Err(HttpError::bad_request(&format!(
+            "Columna desconocida: {}",
+            unknown
+        )));
+    }
+    for required in ["username", "email"] {
+        if !headers.iter().any(|h| h == required) {
+            return Err(HttpError::bad_request(&format!(
+                "Falta la columna {}",
+                required
+            )));
+        }
+    }
+
+    let mut rows = Vec::new();
+    for result in reader.records() {
+        let (line, record) = match result {
+            Ok(record) => (record.position().map_or(0, |p| p.line()), record),
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                rows.push((line, Err(HttpError::bad_request(&e.to_string()))));
+                continue;
+            }
+        };
+        let mut fields = Map::new();
+        let mut parsed = Ok(());
+        for (header, cell) in headers.iter().zip(record.iter()) {
+            if cell.is_empty() {
+                continue;
+            }
+            let value = match header {
+                "permissions" => cell
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .map_err(|_| HttpError::bad_request("permissions debe ser un número entero")),
+                "attributes" => serde_json::from_str::<Value>(cell)
+                    .ok()
+                    .filter(Value::is_object)
+                    .ok_or_else(|| HttpError::bad_request("attributes debe ser un objeto JSON")),
+                _ => Ok(Value::from(cell)),
+            };
+            match value {
+                Ok(value) => {
+                    fields.insert(header.to_string(), value);
+                }
+                Err(e) => {
+                    parsed = Err(e);
+                    break;
+                }
+            }
+        }
+        let record = parsed.and_then(|()| {
+            serde_json::from_value::<UserRecord>(Value::Object(fields))
+                .map_err(|e| HttpError::bad_request(&e.to_string()))
+        });
+        rows.push((line, record));
+    }
+    Ok(rows)
+}
+
+fn parse_ndjson(body: &[u8]) -> Vec<ParsedRow> {
+    body.split(|b| *b == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.trim_ascii().is_empty())
+        .map(|(i, line)| {
+            let record = serde_json::from_slice::<UserRecord>(line)
+                .map_err(|e| HttpError::bad_request(&e.to_string()));
+            (i as u64 + 1, record)
+        })
+        .collect()
+}
+
+fn encode_record(format: TransferFormat, record: UserRecord) -> Result<Bytes, io::Error> {
+    match format {
+        TransferFormat::Ndjson => {
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            Ok(Bytes::from(line))
+        }
+        TransferFormat::Csv => {
+            let optional = |value: Option<String>| value.unwrap_or_default();
+            let attributes = match record.attributes {
+                Some(attributes) => Value::Object(attributes).to_string(),
+                None => String::new(),
+            };
+            csv_line(&[
+                record.username,
+                record.email,
+                record.permissions.map_or(String::new(), |p| p.to_string()),
+                record.status.map_or(String::new(), |s| s.to_string()),
+                optional(record.display_name),
+                optional(record.locale),
+                optional(record.timezone),
+                optional(record.avatar_url),
+                optional(record.phone_number),
+                attributes,
+            ])
+        }
+    }
+}
+
+fn csv_line<T: AsRef<[u8]>>(fields: &[T]) -> Result<Bytes, io::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields)?;
+    writer
+        .into_inner()
+        .map(Bytes::from)
+        .map_err(|e| e.into_error())
+}
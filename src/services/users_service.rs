@@ -1,44 +1,106 @@
+use std::{collections::BTreeMap, sync::Arc};
+
 use axum::{Json, http::StatusCode};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Client;
+use serde_json::Value;
+use tokio_postgres::types::ToSql;
 use tracing::error;
 use validator::Validate;
 
 use crate::{
-    auth::{generate_jwt, hash_password, validate_password, verify_password},
+    auth::{
+        HashParams,
+        backends::{AuthBackend, PasswordBackend},
+        hash_password_pooled, issue_login_token, validate_password_with, verify_password_pooled,
+    },
+    config::{PasswordPolicyConfig, get_config},
     database::{
         connection::PgPool,
         models::{
             FindQuery, FindResult,
-            claims::Claims,
-            dto::{ChangePasswordDto, CreateUserDto, LoginRequest, UpdateUserDto},
-            entities::user::User,
+            dto::{
+                ChangePasswordDto, CollidingUser, CreateUserDto, IdentityCollision, LoginRequest,
+                PasswordHashGroup, PasswordHashReport, UpdateUserDto,
+            },
+            entities::{audit_event::AuditEventKind, user::User, user_status::UserStatus},
         },
     },
+    services::{AttributeEditor, merge_attributes, new_user_attributes, record_audit_event},
     utils::{
         ApiError, USER_PERMISSIONS, check_duplicate, commit_transaction, ensure_row_exists,
-        errors::HttpError, get_pg_client, get_transaction, map_db_error, validate_dto,
-        validate_query_key,
+        errors::HttpError, escape_like, get_pg_client, get_transaction, map_db_error,
+        normalize_email, normalize_username, validate_dto, validate_query_key,
     },
 };
 
+/// Columnas por las que se puede ordenar la búsqueda de usuarios.
+const SORT_COLUMNS: &[&str] = &[
+    "id",
+    "username",
+    "email",
+    "status",
+    "created_at",
+    "updated_at",
+];
+
 pub struct UsersService {
     pool: PgPool,
+    backends: Vec<Arc<dyn AuthBackend>>,
+    password_policy: PasswordPolicyConfig,
 }
 
 impl UsersService {
     pub fn new(pool: &PgPool) -> Self {
-        UsersService { pool: pool.clone() }
+        Self::with_backends(pool, vec![Arc::new(PasswordBackend::new(pool))])
+    }
+
+    /// Crea el servicio consultando los backends de autenticación en orden.
+    pub fn with_backends(pool: &PgPool, backends: Vec<Arc<dyn AuthBackend>>) -> Self {
+        UsersService {
+            pool: pool.clone(),
+            backends,
+            password_policy: get_config().password_policy.clone(),
+        }
+    }
+
+    /// Reemplaza la política de contraseñas tomada de la configuración.
+    pub fn with_password_policy(mut self, policy: PasswordPolicyConfig) -> Self {
+        self.password_policy = policy;
+        self
     }
 
     pub async fn create(&self, dto: CreateUserDto) -> Result<User, (StatusCode, Json<HttpError>)> {
+        self.create_as(dto, AttributeEditor::Admin).await
+    }
+
+    /// Alta con los atributos permitidos a `editor`; el registro público la
+    /// usa como `Owner`.
+    pub(crate) async fn create_as(
+        &self,
+        dto: CreateUserDto,
+        editor: AttributeEditor,
+    ) -> Result<User, ApiError> {
+        let dto = dto.normalized();
         validate_dto(&dto)?;
-        validate_password(&dto.password)?;
+        validate_password_with(
+            &dto.password,
+            &[&dto.username, &dto.email],
+            &self.password_policy,
+        )
+        .await?;
+
+        // Se hashea antes de tomar una conexión para no retenerla mientras
+        // el trabajo espera en la cola del pool de hashing.
+        let password_hash = hash_password_pooled(&dto.password).await?;
 
         let mut client = get_pg_client(&self.pool).await?;
         let tx = get_transaction(&mut client).await?;
 
         let exists = match tx
             .query_opt(
-                "SELECT 1 FROM users WHERE username = $1 OR email = $2 LIMIT 1",
+                "SELECT 1 FROM users WHERE lower(username) = lower($1) OR lower(email) = $2 LIMIT 1",
                 &[&dto.username, &dto.email],
             )
             .await
@@ -50,22 +112,19 @@ impl UsersService {
             }
         };
 
-        if let Some(_) = exists {
+        if exists.is_some() {
             return Err(HttpError::conflict(
                 "Ya existe un usuario con ese nombre de usuario o email",
             ));
         }
 
-        let password_hash = hash_password(dto.password.as_str()).map_err(|e| {
-            error!("Error hasheando password: {}", e);
-            HttpError::internal_server_error()
-        })?;
+        let attributes = new_user_attributes(&tx, dto.attributes.as_ref(), editor).await?;
 
         let row = tx
             .query_one(
                 r#"
-                    INSERT INTO users (username, email, password, permissions, status)
-                    VALUES ($1, $2, $3, $4, 1)
+                    INSERT INTO users (username, email, password, permissions, status, attributes)
+                    VALUES ($1, $2, $3, $4, $5, $6)
                     RETURNING id;
                 "#,
                 &[
@@ -73,6 +132,8 @@ impl UsersService {
                     &dto.email,
                     &password_hash,
                     &USER_PERMISSIONS.bits(),
+                    &UserStatus::Active,
+                    &attributes,
                 ],
             )
             .await
@@ -92,24 +153,157 @@ impl UsersService {
     ) -> Result<FindResult<User>, (StatusCode, Json<HttpError>)> {
         validate_dto(&dto)?;
 
-        let client = get_pg_client(&self.pool).await?;
-
         let key = dto.query_key.as_deref().unwrap_or("id");
-        let value = dto.query_value.as_deref().unwrap_or("");
         validate_query_key(&["id", "username", "email"], key)?;
+        let match_mode = dto.match_mode.as_deref().unwrap_or("contains");
+        validate_query_key(&["contains", "exact"], match_mode)?;
+        let exact = match_mode == "exact";
+        let order = dto.order.as_deref().unwrap_or("asc");
+        validate_query_key(&["asc", "desc"], order)?;
+        // `Some(None)` es la primera página en modo cursor.
+        let cursor = match dto.cursor.as_deref() {
+            None => None,
+            Some(_) if dto.page.is_some() => {
+                return Err(HttpError::bad_request(
+                    "La paginación por cursor no se combina con page",
+                ));
+            }
+            Some(_) if dto.sort.as_deref().is_some_and(|s| s != "created_at") => {
+                return Err(HttpError::bad_request(
+                    "La paginación por cursor solo ordena por created_at",
+                ));
+            }
+            Some("") => Some(None),
+            Some(token) => Some(Some(decode_cursor(token)?)),
+        };
+        let default_sort = if cursor.is_some() { "created_at" } else { "id" };
+        let sort = dto.sort.as_deref().unwrap_or(default_sort);
+        validate_query_key(SORT_COLUMNS, sort)?;
+        let include_total = dto.include_total.unwrap_or(cursor.is_none());
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut params: SqlParams = Vec::new();
+        let mut push = |condition: &str, param: Box<dyn ToSql + Sync + Send>| {
+            params.push(param);
+            conditions.push(condition.replace('$', &format!("${}", params.len())));
+        };
+
+        if let Some(value) = dto.query_value.as_deref() {
+            match (key, exact) {
+                ("id", true) => {
+                    let id: i64 = value
+                        .parse()
+                        .map_err(|_| HttpError::bad_request("Id de usuario inválido"))?;
+                    push("id = $", Box::new(id));
+                }
+                (_, true) => push(
+                    &format!("lower({}) = lower($)", key),
+                    Box::new(value.to_string()),
+                ),
+                (_, false) => push(
+                    &format!("{}::text ILIKE $", key),
+                    Box::new(format!("%{}%", escape_like(value))),
+                ),
+            }
+        }
+        // Los usuarios eliminados solo se ven filtrando por ese estado, para
+        // poder restaurarlos.
+        match dto.status {
+            Some(status) => push("status = $", Box::new(status)),
+            None => push("status <> $", Box::new(UserStatus::Deleted)),
+        }
+        if let Some(bits) = dto.permissions {
+            push("permissions & $ = $", Box::new(bits));
+        }
+        if let Some(raw) = dto.attributes.as_deref() {
+            let filter = serde_json::from_str::<Value>(raw)
+                .ok()
+                .filter(Value::is_object)
+                .ok_or_else(|| {
+                    HttpError::bad_request("El filtro de atributos debe ser un objeto JSON")
+                })?;
+            push("attributes @> $", Box::new(filter));
+        }
+        for (column, bound, value) in [
+            ("created_at", ">=", dto.created_from),
+            ("created_at", "<", dto.created_to),
+            ("updated_at", ">=", dto.updated_from),
+            ("updated_at", "<", dto.updated_to),
+        ] {
+            if let Some(value) = value {
+                push(&format!("{} {} $", column, bound), Box::new(value));
+            }
+        }
+
+        // `<%` (word_similarity de pg_trgm) tolera errores de tipeo; el
+        // ILIKE cubre fragmentos cortos. Ambos usan los índices de trigramas.
+        let mut rank = None;
+        if let Some(q) = dto.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            params.push(Box::new(format!("%{}%", escape_like(q))));
+            params.push(Box::new(q.to_string()));
+            let (pattern, text) = (params.len() - 1, params.len());
+            conditions.push(format!(
+                "(username ILIKE ${0} OR email ILIKE ${0} OR ${1} <% username OR ${1} <% email)",
+                pattern, text
+            ));
+            rank = Some(format!(
+                "GREATEST(word_similarity(${0}, username), word_similarity(${0}, email))",
+                text
+            ));
+        }
 
         let limit = dto.limit.unwrap_or(100);
-        let page = dto.page.unwrap_or(1);
-        let offset = limit * (page - 1);
-        let filter_value = format!("%{}%", value);
+        let client = get_pg_client(&self.pool).await?;
 
-        let count_query = format!("SELECT COUNT(*) FROM users WHERE {}::text ILIKE $1", key);
-        let count_row = client
-            .query_one(&count_query, &[&filter_value])
-            .await
-            .map_err(|e| map_db_error("Error ejecutando query de conteo", e))?;
-        let total_count: i64 = count_row.get(0);
+        // El conteo ignora el cursor: es el total de la búsqueda.
+        let total = if include_total {
+            let count_query = format!(
+                "SELECT COUNT(*) FROM users WHERE {}",
+                conditions.join(" AND ")
+            );
+            let refs = param_refs(&params);
+            let count_row = client
+                .query_one(&count_query, &refs)
+                .await
+                .map_err(|e| map_db_error("Error ejecutando query de conteo", e))?;
+            Some(count_row.get::<_, i64>(0) as u64)
+        } else {
+            None
+        };
+
+        let pagination = match cursor {
+            Some(after) => {
+                if let Some((created_at, id)) = after {
+                    let comparison = if order == "asc" { ">" } else { "<" };
+                    params.push(Box::new(created_at));
+                    params.push(Box::new(id));
+                    conditions.push(format!(
+                        "(created_at, id) {} (${}, ${})",
+                        comparison,
+                        params.len() - 1,
+                        params.len()
+                    ));
+                }
+                // Una fila de más indica si hay otra página.
+                format!("LIMIT {}", limit + 1)
+            }
+            None => {
+                let page = dto.page.unwrap_or(1);
+                format!("LIMIT {} OFFSET {}", limit, limit * (page - 1))
+            }
+        };
 
+        let tie_breaker = if sort == "id" {
+            String::new()
+        } else {
+            format!(", id {}", order)
+        };
+        let ordering = match rank {
+            Some(rank) if dto.sort.is_none() && cursor.is_none() => {
+                format!("{} DESC, id ASC", rank)
+            }
+            _ => format!("{} {}{}", sort, order, tie_breaker),
+        };
         let data_query = format!(
             r#"
                 SELECT 
@@ -117,21 +311,30 @@ impl UsersService {
                     username,
                     email,
                     status,
+                    display_name,
+                    locale,
+                    timezone,
+                    avatar_url,
+                    phone_number,
+                    attributes,
                     created_at,
                     updated_at
                 FROM users
-                WHERE {}::text ILIKE $1
-                LIMIT {} OFFSET {}
+                WHERE {}
+                ORDER BY {}
+                {}
             "#,
-            key, limit, offset
+            conditions.join(" AND "),
+            ordering,
+            pagination
         );
 
         let result = client
-            .query(&data_query, &[&filter_value])
+            .query(&data_query, &param_refs(&params))
             .await
             .map_err(|e| map_db_error("Error ejecutando query de búsqueda", e))?;
 
-        let users: Vec<User> = result
+        let mut users: Vec<User> = result
             .iter()
             .map(User::from_row_without_perms)
             .collect::<Result<Vec<_>, _>>()
@@ -140,9 +343,16 @@ impl UsersService {
                 HttpError::internal_server_error()
             })?;
 
+        let mut next_cursor = None;
+        if cursor.is_some() && users.len() > limit as usize {
+            users.truncate(limit as usize);
+            next_cursor = users.last().map(encode_cursor);
+        }
+
         Ok(FindResult {
             results: users,
-            total: total_count as u64,
+            total,
+            next_cursor,
         })
     }
 
@@ -158,6 +368,15 @@ impl UsersService {
                         email,
                         permissions,
                         status,
+                        must_change_password,
+                        display_name,
+                        locale,
+                        timezone,
+                        avatar_url,
+                        phone_number,
+                        attributes,
+                        deleted_at,
+                        erasure_scheduled_at,
                         created_at,
                         updated_at
                     FROM users WHERE id = $1 LIMIT 1
@@ -195,6 +414,15 @@ impl UsersService {
                         password,
                         permissions,
                         status,
+                        must_change_password,
+                        display_name,
+                        locale,
+                        timezone,
+                        avatar_url,
+                        phone_number,
+                        attributes,
+                        deleted_at,
+                        erasure_scheduled_at,
                         created_at,
                         updated_at
                     FROM users WHERE id = $1 LIMIT 1
@@ -214,6 +442,7 @@ impl UsersService {
     }
 
     pub async fn find_by_email(&self, email: &str) -> Result<User, (StatusCode, Json<HttpError>)> {
+        let email = normalize_email(email);
         let client = get_pg_client(&self.pool).await?;
 
         let row_opt = match client
@@ -228,9 +457,9 @@ impl UsersService {
                         status,
                         created_at,
                         updated_at
-                    FROM users WHERE email = $1
+                    FROM users WHERE lower(email) = $1 AND status <> $2
                 "#,
-                &[&email],
+                &[&email, &UserStatus::Deleted],
             )
             .await
         {
@@ -255,30 +484,40 @@ impl UsersService {
     pub async fn login(&self, dto: LoginRequest) -> Result<String, (StatusCode, Json<HttpError>)> {
         validate_dto(&dto)?;
 
-        let user = self.find_by_email(dto.email.as_str()).await?;
-
-        let hash = match user.password {
-            Some(pwd) => pwd,
-            None => return Err(HttpError::unauthorized("Credenciales inválidas")),
-        };
-
-        if !verify_password(&dto.password, &hash) {
-            error!(
-                "Fallo de verificación de password para el usuario: {}",
-                user.id
-            );
-            return Err(HttpError::unauthorized("Credenciales inválidas"));
+        for backend in &self.backends {
+            if let Some(user_id) = backend.authenticate(&dto.email, &dto.password).await? {
+                let client = get_pg_client(&self.pool).await?;
+                record_audit_event(
+                    &client,
+                    user_id,
+                    AuditEventKind::Login,
+                    Some(backend.name()),
+                )
+                .await;
+                return issue_login_token(&self.pool, user_id, self.password_policy.max_age_days)
+                    .await;
+            }
         }
 
-        let exp_minutes = 60;
-        let claims = Claims::new(user.id.to_string(), exp_minutes);
-        generate_jwt(claims).map_err(|e| {
-            error!("Error generando JWT: {}", e);
-            HttpError::internal_server_error()
-        })
+        Err(HttpError::unauthorized("Credenciales inválidas"))
     }
 
     pub async fn update(&self, dto: UpdateUserDto) -> Result<User, (StatusCode, Json<HttpError>)> {
+        self.update_as(dto, AttributeEditor::Admin).await
+    }
+
+    /// Actualización del propio usuario: solo admite los atributos marcados
+    /// como `user_editable`.
+    pub async fn update_myself(&self, dto: UpdateUserDto) -> Result<User, ApiError> {
+        self.update_as(dto, AttributeEditor::Owner).await
+    }
+
+    async fn update_as(
+        &self,
+        dto: UpdateUserDto,
+        editor: AttributeEditor,
+    ) -> Result<User, ApiError> {
+        let dto = dto.normalized();
         validate_dto(&dto)?;
         let mut client = get_pg_client(&self.pool).await?;
         let id = match &dto.id {
@@ -286,6 +525,9 @@ impl UsersService {
             None => return Err(HttpError::unauthorized("Id de usuario inválido")),
         };
         ensure_row_exists(&client, "users", "id", &id, "Usuario no encontrado").await?;
+        if dto.must_change_password == Some(true) {
+            ensure_local_password(&client, *id).await?;
+        }
         let tx = get_transaction(&mut client).await?;
 
         if let Some(ref username) = dto.username {
@@ -310,6 +552,26 @@ impl UsersService {
             .await?;
         }
 
+        if let Some(timezone) = dto.timezone.as_deref().filter(|tz| !tz.is_empty()) {
+            let known = tx
+                .query_opt(
+                    "SELECT 1 FROM pg_timezone_names WHERE name = $1",
+                    &[&timezone],
+                )
+                .await
+                .map_err(|e| map_db_error("Error verificando la zona horaria", e))?;
+            if known.is_none() {
+                return Err(HttpError::bad_request("Zona horaria desconocida"));
+            }
+        }
+
+        let attributes = match &dto.attributes {
+            Some(patch) => Some(Value::Object(
+                merge_attributes(&tx, *id, patch, editor).await?,
+            )),
+            None => None,
+        };
+
         let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::new();
         let mut set_clauses = Vec::new();
         let mut idx = 1;
@@ -332,6 +594,33 @@ impl UsersService {
             idx += 1;
         }
 
+        if let Some(must_change_password) = &dto.must_change_password {
+            set_clauses.push(format!("must_change_password = ${}", idx));
+            params.push(must_change_password);
+            idx += 1;
+        }
+
+        // Un texto vacío borra el campo de perfil.
+        for (column, value) in [
+            ("display_name", &dto.display_name),
+            ("locale", &dto.locale),
+            ("timezone", &dto.timezone),
+            ("avatar_url", &dto.avatar_url),
+            ("phone_number", &dto.phone_number),
+        ] {
+            if let Some(value) = value {
+                set_clauses.push(format!("{} = NULLIF(${}, '')", column, idx));
+                params.push(value);
+                idx += 1;
+            }
+        }
+
+        if let Some(attributes) = &attributes {
+            set_clauses.push(format!("attributes = ${}", idx));
+            params.push(attributes);
+            idx += 1;
+        }
+
         if set_clauses.is_empty() {
             return Err(HttpError::bad_request("No hay campos para actualizar"));
         }
@@ -347,6 +636,14 @@ impl UsersService {
                     email,
                     permissions,
                     status,
+                    must_change_password,
+                    display_name,
+                    locale,
+                    timezone,
+                    avatar_url,
+                    phone_number,
+                    attributes,
+                    deleted_at,
                     created_at,
                     updated_at
             "#,
@@ -378,28 +675,19 @@ impl UsersService {
         user_id: String,
         dto: ChangePasswordDto,
     ) -> Result<(), (StatusCode, Json<HttpError>)> {
-        dto.validate().map_err(|e| HttpError::errors(e))?;
+        dto.validate().map_err(HttpError::errors)?;
         let id: i64 = user_id.parse().map_err(|e| {
             error!(error = %e, "Error al parsear id del usuario");
             HttpError::bad_request("Id de usuario inválido")
         })?;
 
-        if dto.previous_password == dto.new_password {
-            return Err(HttpError::bad_request(
-                "La contraseña nueva no puede ser igual a la contraseña antigua",
-            ));
-        }
-
         let user = self.fetch(id).await?;
-        if user.password.is_none() {
-            return Err(HttpError::internal_server_error());
-        }
         let hash = match user.password {
             Some(pwd) => pwd,
-            None => return Err(HttpError::unauthorized("Credenciales inválidas")),
+            None => return Err(HttpError::bad_request(NO_LOCAL_PASSWORD)),
         };
 
-        if !verify_password(&dto.previous_password, &hash) {
+        if !verify_password_pooled(&dto.previous_password, &hash).await? {
             error!(
                 "Fallo de verificación de password para el usuario: {}",
                 user.id
@@ -407,56 +695,352 @@ impl UsersService {
             return Err(HttpError::unauthorized("Credenciales inválidas"));
         }
 
-        validate_password(&dto.new_password)?;
-
-        let statement = r#"
-            UPDATE users
-            SET password = $1
-            WHERE id = $2
-        "#;
-        let hash = hash_password(&dto.new_password).map_err(|e| {
-            map_db_error(
-                format!("Error al hashear la contraseña del usuario {}", &id).as_str(),
-                e,
-            )
-        })?;
+        validate_password_with(
+            &dto.new_password,
+            &[&user.username, &user.email],
+            &self.password_policy,
+        )
+        .await?;
 
-        let client = get_pg_client(&self.pool).await?;
-        match client.query_opt(statement, &[&hash, &id]).await {
-            Ok(r) => r,
-            Err(e) => {
-                error!(error = %e, "Error al actualizar el usuario");
-                return Err(HttpError::internal_server_error());
-            }
-        };
+        if verify_password_pooled(&dto.new_password, &hash).await? {
+            return Err(HttpError::bad_request(
+                "La contraseña nueva no puede ser igual a la contraseña antigua",
+            ));
+        }
+        self.check_password_history(id, &dto.new_password).await?;
+
+        let hash = hash_password_pooled(&dto.new_password).await?;
+
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+        replace_password(&tx, id, &hash, self.password_policy.history_size).await?;
+        commit_transaction(tx, "Error haciendo commit del cambio de contraseña").await?;
 
         Ok(())
     }
 
-    async fn set_user_status(&self, id: i64, status: i32) -> Result<(), ApiError> {
+    /// Rechaza la contraseña si coincide con alguna del historial.
+    async fn check_password_history(&self, id: i64, password: &str) -> Result<(), ApiError> {
+        let history_size = self.password_policy.history_size;
+        if history_size == 0 {
+            return Ok(());
+        }
+
         let client = get_pg_client(&self.pool).await?;
-        ensure_row_exists(&client, "users", "id", &id, "Usuario no encontrado").await?;
-        let statement = r#"
-            UPDATE users
-            SET status = $1
-            WHERE id = $2
-        "#;
-        match client.query_opt(statement, &[&status, &id]).await {
-            Ok(r) => r,
-            Err(e) => {
-                error!(error = %e, "Error al cambiar el status del usuario");
-                return Err(HttpError::internal_server_error());
+        let rows = client
+            .query(
+                "SELECT password FROM password_history WHERE user_id = $1 ORDER BY id DESC LIMIT $2",
+                &[&id, &(history_size as i64)],
+            )
+            .await
+            .map_err(|e| map_db_error("Error consultando el historial de contraseñas", e))?;
+        drop(client);
+
+        for row in rows {
+            let previous: String = row.get("password");
+            if verify_password_pooled(password, &previous).await? {
+                return Err(HttpError::bad_request(&format!(
+                    "La contraseña nueva no puede ser igual a ninguna de las últimas {} contraseñas",
+                    history_size
+                )));
             }
-        };
+        }
+        Ok(())
+    }
 
+    /// Marca o desmarca al usuario para que cambie la contraseña en el
+    /// próximo login.
+    pub async fn set_must_change_password(&self, id: i64, value: bool) -> Result<(), ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        ensure_row_exists(&client, "users", "id", &id, "Usuario no encontrado").await?;
+        if value {
+            ensure_local_password(&client, id).await?;
+        }
+        client
+            .execute(
+                "UPDATE users SET must_change_password = $1 WHERE id = $2",
+                &[&value, &id],
+            )
+            .await
+            .map_err(|e| map_db_error("Error actualizando must_change_password", e))?;
         Ok(())
     }
 
+    /// Cambia el estado respetando `UserStatus::can_transition_to`; con
+    /// `from` además se exige el estado de origen. El borrado lógico guarda
+    /// `deleted_at` para el purgado y salir de él lo limpia.
+    async fn set_user_status(
+        &self,
+        id: i64,
+        status: UserStatus,
+        from: Option<UserStatus>,
+    ) -> Result<(), ApiError> {
+        let mut client = get_pg_client(&self.pool).await?;
+        let tx = get_transaction(&mut client).await?;
+
+        let current: UserStatus = tx
+            .query_opt("SELECT status FROM users WHERE id = $1 FOR UPDATE", &[&id])
+            .await
+            .map_err(|e| map_db_error("Error consultando el status del usuario", e))?
+            .ok_or_else(|| HttpError::not_found("Usuario no encontrado"))?
+            .get("status");
+
+        if !current.can_transition_to(status) || from.is_some_and(|f| f != current) {
+            return Err(HttpError::conflict(&format!(
+                "No se puede pasar un usuario de '{}' a '{}'",
+                current, status
+            )));
+        }
+
+        tx.execute(
+            r#"
+                UPDATE users
+                SET status = $1,
+                    deleted_at = CASE WHEN $2 THEN now() END,
+                    updated_at = now()
+                WHERE id = $3
+            "#,
+            &[&status, &(status == UserStatus::Deleted), &id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error al cambiar el status del usuario", e))?;
+        let details = format!("{} -> {}", current, status);
+        record_audit_event(&tx, id, AuditEventKind::StatusChanged, Some(&details)).await;
+
+        commit_transaction(tx, "Error haciendo commit del cambio de status").await
+    }
+
     pub async fn inactive(&self, id: i64) -> Result<(), ApiError> {
-        self.set_user_status(id, 2).await
+        self.set_user_status(id, UserStatus::Inactive, None).await
     }
 
     pub async fn delete(&self, id: i64) -> Result<(), ApiError> {
-        self.set_user_status(id, 3).await
+        self.set_user_status(id, UserStatus::Deleted, None).await
+    }
+
+    /// Vuelve a activar a un usuario inactivo.
+    pub async fn reactivate(&self, id: i64) -> Result<(), ApiError> {
+        self.set_user_status(id, UserStatus::Active, Some(UserStatus::Inactive))
+            .await
+    }
+
+    /// Deshace el borrado lógico mientras el usuario no haya sido purgado.
+    pub async fn restore(&self, id: i64) -> Result<(), ApiError> {
+        self.set_user_status(id, UserStatus::Active, Some(UserStatus::Deleted))
+            .await
+    }
+
+    /// Borra definitivamente los usuarios eliminados hace más de
+    /// `older_than_days` días. Las tablas relacionadas caen en cascada.
+    pub async fn purge_deleted(&self, older_than_days: u32) -> Result<u64, ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        client
+            .execute(
+                r#"
+                    DELETE FROM users
+                    WHERE status = $1
+                      AND deleted_at < now() - make_interval(days => $2)
+                "#,
+                &[&UserStatus::Deleted, &(older_than_days as i32)],
+            )
+            .await
+            .map_err(|e| map_db_error("Error purgando usuarios eliminados", e))
     }
+
+    /// Cuenta cuántos usuarios tienen hashes generados con una variante o
+    /// parámetros distintos a los configurados. Se agrupa en SQL por la
+    /// cabecera del hash para no traer todos los hashes a memoria.
+    pub async fn password_hash_report(&self) -> Result<PasswordHashReport, ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        let rows = client
+            .query(
+                r#"
+                    SELECT min(password) AS sample, count(*) AS users
+                    FROM users
+                    WHERE password IS NOT NULL AND status <> $1
+                    GROUP BY regexp_replace(password, '\$[^$]*\$[^$]*$', ''), length(password)
+                "#,
+                &[&UserStatus::Deleted],
+            )
+            .await
+            .map_err(|e| map_db_error("Error generando el reporte de hashes", e))?;
+
+        let current = HashParams::current();
+        let mut groups: Vec<PasswordHashGroup> = vec![];
+        for row in rows {
+            let params = HashParams::parse(row.get("sample"));
+            let users: i64 = row.get("users");
+            let is_current = params.as_ref() == Some(&current);
+            let params = params.map(|p| p.to_string());
+
+            match groups.iter_mut().find(|g| g.params == params) {
+                Some(group) => group.users += users,
+                None => groups.push(PasswordHashGroup {
+                    params,
+                    users,
+                    current: is_current,
+                }),
+            }
+        }
+        groups.sort_by_key(|g| std::cmp::Reverse(g.users));
+
+        let total = groups.iter().map(|g| g.users).sum();
+        let up_to_date = groups.iter().filter(|g| g.current).map(|g| g.users).sum();
+        Ok(PasswordHashReport {
+            current_params: current.to_string(),
+            total,
+            current: up_to_date,
+            outdated: total - up_to_date,
+            groups,
+        })
+    }
+
+    /// Emails y usernames repetidos al normalizarlos. Se calcula en Rust para
+    /// detectar también las diferencias que solo resuelve NFKC.
+    pub async fn identity_collisions(&self) -> Result<Vec<IdentityCollision>, ApiError> {
+        let client = get_pg_client(&self.pool).await?;
+        let rows = client
+            .query(
+                "SELECT id, username, email, status, created_at FROM users ORDER BY created_at, id",
+                &[],
+            )
+            .await
+            .map_err(|e| map_db_error("Error listando usuarios para buscar colisiones", e))?;
+
+        let mut by_email: BTreeMap<String, Vec<CollidingUser>> = BTreeMap::new();
+        let mut by_username: BTreeMap<String, Vec<CollidingUser>> = BTreeMap::new();
+        for row in &rows {
+            let user = || CollidingUser {
+                id: row.get("id"),
+                username: row.get("username"),
+                email: row.get("email"),
+                status: row.get("status"),
+                created_at: row.get("created_at"),
+            };
+            let username: String = row.get("username");
+            let email: String = row.get("email");
+            by_email
+                .entry(normalize_email(&email))
+                .or_default()
+                .push(user());
+            by_username
+                .entry(normalize_username(&username).to_lowercase())
+                .or_default()
+                .push(user());
+        }
+
+        let collisions = [("email", by_email), ("username", by_username)]
+            .into_iter()
+            .flat_map(|(field, groups)| {
+                groups.into_iter().filter(|(_, users)| users.len() > 1).map(
+                    move |(value, users)| IdentityCollision {
+                        field,
+                        value,
+                        users,
+                    },
+                )
+            })
+            .collect();
+        Ok(collisions)
+    }
+}
+
+/// Guarda el hash nuevo, pasa el anterior al historial y recorta el historial
+/// a `history_size` entradas.
+pub(crate) async fn replace_password(
+    tx: &deadpool_postgres::Transaction<'_>,
+    user_id: i64,
+    hash: &str,
+    history_size: u32,
+) -> Result<(), ApiError> {
+    let history_size = history_size as i64;
+
+    if history_size > 0 {
+        tx.execute(
+            r#"
+            INSERT INTO password_history (user_id, password)
+            SELECT id, password FROM users WHERE id = $1 AND password IS NOT NULL
+            "#,
+            &[&user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error guardando el historial de contraseñas", e))?;
+    }
+
+    tx.execute(
+        r#"
+        UPDATE users
+        SET password = $1, password_changed_at = now(), must_change_password = false
+        WHERE id = $2
+        "#,
+        &[&hash, &user_id],
+    )
+    .await
+    .map_err(|e| map_db_error("Error al actualizar la contraseña", e))?;
+    record_audit_event(tx, user_id, AuditEventKind::PasswordChanged, None).await;
+
+    tx.execute(
+        r#"
+        DELETE FROM password_history
+        WHERE user_id = $1 AND id NOT IN (
+            SELECT id FROM password_history WHERE user_id = $1 ORDER BY id DESC LIMIT $2
+        )
+        "#,
+        &[&user_id, &history_size],
+    )
+    .await
+    .map_err(|e| map_db_error("Error recortando el historial de contraseñas", e))?;
+
+    Ok(())
+}
+
+const NO_LOCAL_PASSWORD: &str = "El usuario no tiene contraseña local";
+
+/// Pedir el cambio de contraseña a un usuario sin contraseña local (LDAP,
+/// OIDC, enlace mágico) lo dejaría sin poder entrar: no tiene cuál cambiar.
+async fn ensure_local_password(client: &Client, user_id: i64) -> Result<(), ApiError> {
+    let row = client
+        .query_opt(
+            "SELECT 1 FROM users WHERE id = $1 AND password IS NOT NULL",
+            &[&user_id],
+        )
+        .await
+        .map_err(|e| map_db_error("Error consultando la contraseña del usuario", e))?;
+    match row {
+        Some(_) => Ok(()),
+        None => Err(HttpError::bad_request(NO_LOCAL_PASSWORD)),
+    }
+}
+
+type SqlParams = Vec<Box<dyn ToSql + Sync + Send>>;
+
+fn param_refs(params: &SqlParams) -> Vec<&(dyn ToSql + Sync)> {
+    params
+        .iter()
+        .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+        .collect()
+}
+
+/// El cursor es la posición `(created_at, id)` del último usuario devuelto,
+/// en base64 para que el cliente lo trate como opaco.
+fn encode_cursor(user: &User) -> String {
+    URL_SAFE_NO_PAD.encode(format!(
+        "{}:{}",
+        user.created_at.timestamp_micros(),
+        user.id
+    ))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, i64), ApiError> {
+    let invalid = || HttpError::bad_request("Cursor inválido");
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let (micros, id) = decoded.split_once(':').ok_or_else(invalid)?;
+    let created_at = micros
+        .parse()
+        .ok()
+        .and_then(DateTime::from_timestamp_micros)
+        .ok_or_else(invalid)?;
+    let id = id.parse().map_err(|_| invalid())?;
+    Ok((created_at, id))
 }
@@ -4,8 +4,23 @@ use crate::{
     ApiInfo,
     database::models::{
         FindQuery, FindResult, OneResult,
-        dto::{ChangePasswordDto, CreateUserDto, LoginRequest, LoginResponse, UpdateUserDto},
-        entities::user::User,
+        dto::{
+            AcceptInvitationDto, AttributeDefinitionDto, ChangePasswordDto, CreateInvitationDto,
+            CreateUserDto, ErasureResponse, ImportReport, ImportRowError, LoginRequest,
+            LoginResponse, MagicLinkExport, MagicLinkRequest, OidcCallbackQuery,
+            OidcProvidersResponse, PasswordPolicyResponse, RedeemMagicLinkRequest, RegisterDto,
+            ScimEmail, ScimGroup, ScimListResponse, ScimMemberRef, ScimMeta, ScimPatchOperation,
+            ScimPatchRequest, ScimUser, TransferFormat, UpdateUserDto, UserDataExport, UserRecord,
+            VerifyQuery,
+        },
+        entities::{
+            attribute_definition::{AttributeDefinition, AttributeType},
+            audit_event::AuditEvent,
+            identity::UserIdentity,
+            invitation::{Invitation, InvitationStatus},
+            user::User,
+            user_status::UserStatus,
+        },
     },
     utils::{MessageResponse, errors::HttpError},
 };
@@ -14,6 +29,16 @@ use crate::{
 #[openapi(
     paths(
         crate::handlers::users_handler::login,
+        crate::handlers::users_handler::logout,
+        crate::handlers::password_policy_handler::password_policy,
+        crate::handlers::magic_link_handler::request_magic_link,
+        crate::handlers::magic_link_handler::redeem_magic_link,
+        crate::handlers::auth_handler::oidc_providers,
+        crate::handlers::auth_handler::oidc_authorize,
+        crate::handlers::auth_handler::oidc_callback,
+        crate::handlers::auth_handler::verify,
+        crate::handlers::auth_handler::jwks,
+        crate::handlers::metrics_handler::metrics,
         crate::handlers::users_handler::create_user,
         crate::handlers::users_handler::get_users,
         crate::handlers::users_handler::get_user,
@@ -23,25 +48,94 @@ use crate::{
         crate::handlers::users_handler::change_password,
         crate::handlers::users_handler::inactive_user,
         crate::handlers::users_handler::inactive_myself,
+        crate::handlers::users_handler::reactivate_user,
+        crate::handlers::invitation_handler::create_invitation,
+        crate::handlers::invitation_handler::list_invitations,
+        crate::handlers::invitation_handler::resend_invitation,
+        crate::handlers::invitation_handler::revoke_invitation,
+        crate::handlers::invitation_handler::accept_invitation,
+        crate::handlers::registration_handler::register,
+        crate::handlers::attribute_handler::list_attributes,
+        crate::handlers::attribute_handler::upsert_attribute,
+        crate::handlers::attribute_handler::delete_attribute,
+        crate::handlers::user_transfer_handler::import_users,
+        crate::handlers::user_transfer_handler::export_users,
+        crate::handlers::privacy_handler::export_myself,
+        crate::handlers::privacy_handler::request_erasure,
+        crate::handlers::privacy_handler::cancel_erasure,
+        crate::handlers::users_handler::restore_user,
         crate::handlers::users_handler::delete_user,
         crate::handlers::users_handler::delete_myself,
+        crate::handlers::scim_handler::service_provider_config,
+        crate::handlers::scim_handler::list_users,
+        crate::handlers::scim_handler::create_user,
+        crate::handlers::scim_handler::get_user,
+        crate::handlers::scim_handler::replace_user,
+        crate::handlers::scim_handler::patch_user,
+        crate::handlers::scim_handler::delete_user,
+        crate::handlers::scim_handler::list_groups,
+        crate::handlers::scim_handler::create_group,
+        crate::handlers::scim_handler::get_group,
+        crate::handlers::scim_handler::replace_group,
+        crate::handlers::scim_handler::patch_group,
+        crate::handlers::scim_handler::delete_group,
     ),
     components(schemas(
         LoginRequest,
         LoginResponse,
+        MagicLinkRequest,
+        RedeemMagicLinkRequest,
+        OidcCallbackQuery,
+        OidcProvidersResponse,
+        VerifyQuery,
         CreateUserDto,
         UpdateUserDto,
         ChangePasswordDto,
+        PasswordPolicyResponse,
+        UserDataExport,
+        CreateInvitationDto,
+        AcceptInvitationDto,
+        RegisterDto,
+        AttributeDefinitionDto,
+        AttributeDefinition,
+        AttributeType,
+        FindResult<AttributeDefinition>,
+        OneResult<AttributeDefinition>,
+        ImportReport,
+        ImportRowError,
+        TransferFormat,
+        UserRecord,
+        Invitation,
+        InvitationStatus,
+        FindResult<Invitation>,
+        OneResult<Invitation>,
+        MagicLinkExport,
+        ErasureResponse,
+        AuditEvent,
+        UserIdentity,
         FindQuery,
         FindResult<User>,
         OneResult<User>,
         User,
+        UserStatus,
+        ScimUser,
+        ScimGroup,
+        ScimEmail,
+        ScimMemberRef,
+        ScimMeta,
+        ScimPatchRequest,
+        ScimPatchOperation,
+        ScimListResponse<ScimUser>,
+        ScimListResponse<ScimGroup>,
         MessageResponse,
         HttpError,
         ApiInfo
     )),
     tags(
-        (name = "Users", description = "Operaciones relacionadas con usuarios")
+        (name = "Users", description = "Operaciones relacionadas con usuarios"),
+        (name = "Auth", description = "Autenticación con proveedores externos"),
+        (name = "SCIM", description = "Aprovisionamiento SCIM 2.0 de usuarios y grupos"),
+        (name = "Metrics", description = "Métricas operativas del servicio")
     ),
     modifiers(&SecurityAddon)
 )]
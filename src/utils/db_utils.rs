@@ -1,4 +1,5 @@
 use deadpool_postgres::{Client, Object, Transaction};
+use rand::{Rng, rng};
 use tokio_postgres::types::ToSql;
 use tracing::error;
 
@@ -26,9 +27,11 @@ pub async fn get_transaction(
 }
 
 pub fn validate_dto<T: Validate>(dto: &T) -> Result<(), (StatusCode, Json<HttpError>)> {
-    dto.validate().map_err(|e| HttpError::errors(e))
+    dto.validate().map_err(HttpError::errors)
 }
 
+/// Busca otro usuario con el mismo valor en `column`, sin distinguir
+/// mayúsculas (igual que los índices únicos).
 pub async fn check_duplicate(
     tx: &Transaction<'_>,
     column: &str,
@@ -36,7 +39,10 @@ pub async fn check_duplicate(
     exclude_id: i64,
     error_msg: &str,
 ) -> Result<(), (StatusCode, Json<HttpError>)> {
-    let query = format!("SELECT 1 FROM users WHERE {} = $1 AND id != $2", column);
+    let query = format!(
+        "SELECT 1 FROM users WHERE lower({}) = lower($1) AND id != $2",
+        column
+    );
     let exists = tx
         .query_opt(&query, &[value, &exclude_id])
         .await
@@ -87,7 +93,7 @@ pub async fn ensure_row_exists(
         .map_err(|e| map_db_error("Error al verificar la existencia del registro", e))?;
     match row {
         Some(_) => Ok(()),
-        None => return Err(HttpError::not_found(not_found_msg)),
+        None => Err(HttpError::not_found(not_found_msg)),
     }
 }
 
@@ -98,3 +104,44 @@ pub fn validate_query_key(allowed_keys: &[&str], key: &str) -> Result<(), ApiErr
 
     Ok(())
 }
+
+/// Escapa `\`, `%` y `_` para usar el valor literal dentro de un patrón
+/// `LIKE`/`ILIKE`.
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Deriva un username válido (`^[a-zA-Z0-9_]+$`, 3-100 caracteres) y libre a
+/// partir de `base` (parte local de un email, uid de un directorio, etc.).
+pub async fn available_username(tx: &Transaction<'_>, base: &str) -> Result<String, ApiError> {
+    let mut base: String = base
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(90)
+        .collect();
+    while base.len() < 3 {
+        base.push('_');
+    }
+
+    let mut candidate = base.clone();
+    for _ in 0..10 {
+        let taken = tx
+            .query_opt(
+                "SELECT 1 FROM users WHERE lower(username) = lower($1)",
+                &[&candidate],
+            )
+            .await
+            .map_err(|e| map_db_error("Error verificando el username", e))?;
+        if taken.is_none() {
+            return Ok(candidate);
+        }
+        candidate = format!("{}_{}", base, rng().random_range(1000..10000));
+    }
+
+    Err(HttpError::conflict(
+        "No se pudo generar un nombre de usuario disponible",
+    ))
+}
@@ -0,0 +1 @@
+pub use r_auth_types::{normalize_email, normalize_username};
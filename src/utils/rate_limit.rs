@@ -0,0 +1,41 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Limitador de ventana deslizante en memoria, indexado por una clave arbitraria
+/// (email, IP, etc.).
+pub struct RateLimiter {
+    max_hits: usize,
+    window: Duration,
+    hits: Mutex<HashMap<String, Vec<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_hits: u32, window: Duration) -> Self {
+        RateLimiter {
+            max_hits: max_hits as usize,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registra un intento para `key`. Devuelve `false` si se superó el límite.
+    pub fn check(&self, key: &str) -> bool {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+
+        hits.retain(|_, times| {
+            times.retain(|t| now.duration_since(*t) < self.window);
+            !times.is_empty()
+        });
+
+        let times = hits.entry(key.to_string()).or_default();
+        if times.len() >= self.max_hits {
+            return false;
+        }
+        times.push(now);
+        true
+    }
+}
@@ -0,0 +1,453 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    auth::{decode_jwt, generate_login_token},
+    database::models::{
+        FindQuery,
+        dto::{AttributeDefinitionDto, CreateUserDto, UpdateUserDto},
+        entities::attribute_definition::AttributeType,
+    },
+    services::{AttributeService, UsersService},
+};
+use serde_json::{Map, Value, json};
+
+use super::{create_user, define};
+use crate::common;
+
+fn attributes(value: Value) -> Option<Map<String, Value>> {
+    match value {
+        Value::Object(map) => Some(map),
+        _ => panic!("Se esperaba un objeto"),
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: Los campos de perfil se validan y un texto vacío los borra
+///
+#[tokio::test]
+async fn test_profile_fields_update_and_clear() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "profile_user").await;
+
+    let user = service
+        .update(UpdateUserDto {
+            id: Some(id),
+            display_name: Some(" Ana Pérez ".to_string()),
+            locale: Some("es-AR".to_string()),
+            timezone: Some("America/Argentina/Buenos_Aires".to_string()),
+            avatar_url: Some("https://cdn.example.com/ana.png".to_string()),
+            phone_number: Some("+5491122334455".to_string()),
+            ..Default::default()
+        })
+        .await
+        .expect("Error actualizando el perfil");
+    assert_eq!(user.display_name.as_deref(), Some("Ana Pérez"));
+    assert_eq!(user.locale.as_deref(), Some("es-AR"));
+    assert_eq!(user.phone_number.as_deref(), Some("+5491122334455"));
+
+    for dto in [
+        UpdateUserDto {
+            phone_number: Some("1122334455".to_string()),
+            ..Default::default()
+        },
+        UpdateUserDto {
+            avatar_url: Some("javascript:alert(1)".to_string()),
+            ..Default::default()
+        },
+        UpdateUserDto {
+            timezone: Some("Mars/Olympus_Mons".to_string()),
+            ..Default::default()
+        },
+    ] {
+        let Err((status, _)) = service
+            .update(UpdateUserDto {
+                id: Some(id),
+                ..dto
+            })
+            .await
+        else {
+            panic!("El perfil inválido debería rechazarse");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let user = service
+        .update(UpdateUserDto {
+            id: Some(id),
+            phone_number: Some(String::new()),
+            ..Default::default()
+        })
+        .await
+        .expect("Error borrando el teléfono");
+    assert_eq!(user.phone_number, None);
+    assert_eq!(user.locale.as_deref(), Some("es-AR"));
+}
+
+/// ---
+///
+/// ## Test Case 2: Los atributos se validan contra sus definiciones y se fusionan
+///
+#[tokio::test]
+async fn test_attributes_validated_and_merged() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let attributes_service = AttributeService::new(pool);
+    let id = create_user(&service, "attrs_user").await;
+
+    define(
+        &attributes_service,
+        "department",
+        AttributeType::String,
+        true,
+        false,
+    )
+    .await;
+    define(
+        &attributes_service,
+        "seniority",
+        AttributeType::Number,
+        false,
+        false,
+    )
+    .await;
+
+    for (patch, reason) in [
+        (json!({"department": "ventas", "unknown": 1}), "desconocido"),
+        (json!({"department": 3}), "de otro tipo"),
+        (json!({"department": null}), "obligatorio borrado"),
+    ] {
+        let Err((status, _)) = service
+            .update(UpdateUserDto {
+                id: Some(id),
+                attributes: attributes(patch),
+                ..Default::default()
+            })
+            .await
+        else {
+            panic!("Un atributo {} debería rechazarse", reason);
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    service
+        .update(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"department": "ventas", "seniority": 2})),
+            ..Default::default()
+        })
+        .await
+        .expect("Error guardando los atributos");
+    let user = service
+        .update(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"seniority": null})),
+            ..Default::default()
+        })
+        .await
+        .expect("Error borrando un atributo");
+    assert_eq!(
+        Value::Object(user.attributes),
+        json!({"department": "ventas"})
+    );
+
+    let Err((status, _)) = attributes_service
+        .upsert(
+            "department",
+            AttributeDefinitionDto {
+                kind: AttributeType::Boolean,
+                required: false,
+                include_in_token: false,
+                description: None,
+                user_editable: false,
+            },
+        )
+        .await
+    else {
+        panic!("No debería cambiar el tipo con valores existentes");
+    };
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    attributes_service
+        .delete("department")
+        .await
+        .expect("Error borrando la definición");
+    let user = service.find_by_id(id).await.unwrap();
+    assert!(user.attributes.is_empty());
+}
+
+/// ---
+///
+/// ## Test Case 3: La búsqueda filtra por atributos
+///
+#[tokio::test]
+async fn test_find_filters_by_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    define(
+        &AttributeService::new(pool),
+        "department",
+        AttributeType::String,
+        false,
+        false,
+    )
+    .await;
+
+    for (username, department) in [("sales_one", "ventas"), ("support_one", "soporte")] {
+        let id = create_user(&service, username).await;
+        service
+            .update(UpdateUserDto {
+                id: Some(id),
+                attributes: attributes(json!({ "department": department })),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    create_user(&service, "no_department").await;
+
+    let result = service
+        .find(FindQuery {
+            attributes: Some(r#"{"department":"ventas"}"#.to_string()),
+            ..Default::default()
+        })
+        .await
+        .expect("Error buscando por atributos");
+    let usernames: Vec<_> = result.results.iter().map(|u| u.username.as_str()).collect();
+    assert_eq!(usernames, ["sales_one"]);
+
+    let Err((status, _)) = service
+        .find(FindQuery {
+            attributes: Some("[1]".to_string()),
+            ..Default::default()
+        })
+        .await
+    else {
+        panic!("El filtro debe ser un objeto JSON");
+    };
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// ---
+///
+/// ## Test Case 4: El token de login incluye solo los atributos marcados
+///
+#[tokio::test]
+async fn test_login_token_includes_marked_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let attributes_service = AttributeService::new(pool);
+    define(
+        &attributes_service,
+        "tenant",
+        AttributeType::String,
+        false,
+        true,
+    )
+    .await;
+    define(
+        &attributes_service,
+        "internal_note",
+        AttributeType::String,
+        false,
+        false,
+    )
+    .await;
+
+    let id = create_user(&service, "token_attrs").await;
+    let claims = decode_jwt(&generate_login_token(pool, id).await.unwrap()).unwrap();
+    assert!(claims.attrs.is_none());
+
+    service
+        .update(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"tenant": "acme", "internal_note": "vip"})),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let claims = decode_jwt(&generate_login_token(pool, id).await.unwrap()).unwrap();
+    assert_eq!(
+        claims.attrs.map(Value::Object),
+        Some(json!({"tenant": "acme"}))
+    );
+}
+
+/// ---
+///
+/// ## Test Case 5: El propio usuario solo modifica los atributos marcados como editables
+///
+#[tokio::test]
+async fn test_update_myself_only_user_editable_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let attributes_service = AttributeService::new(pool);
+    define(
+        &attributes_service,
+        "tenant",
+        AttributeType::String,
+        false,
+        true,
+    )
+    .await;
+    attributes_service
+        .upsert(
+            "nickname",
+            AttributeDefinitionDto {
+                kind: AttributeType::String,
+                required: false,
+                include_in_token: false,
+                user_editable: true,
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    let id = create_user(&service, "self_attrs").await;
+    service
+        .update(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"tenant": "acme"})),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    for patch in [json!({"tenant": "other"}), json!({"tenant": null})] {
+        let Err((status, _)) = service
+            .update_myself(UpdateUserDto {
+                id: Some(id),
+                attributes: attributes(patch),
+                ..Default::default()
+            })
+            .await
+        else {
+            panic!("El usuario no debería modificar un atributo de administrador");
+        };
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    let user = service
+        .update_myself(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"nickname": "yo"})),
+            ..Default::default()
+        })
+        .await
+        .expect("Un atributo editable debería guardarse");
+    assert_eq!(
+        Value::Object(user.attributes),
+        json!({"tenant": "acme", "nickname": "yo"})
+    );
+}
+
+/// ---
+///
+/// ## Test Case 6: El alta exige los atributos obligatorios
+///
+#[tokio::test]
+async fn test_create_requires_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    define(
+        &AttributeService::new(pool),
+        "department",
+        AttributeType::String,
+        true,
+        false,
+    )
+    .await;
+    let dto = |values: Option<Map<String, Value>>| CreateUserDto {
+        username: "required_attrs".to_string(),
+        email: "required_attrs@example.com".to_string(),
+        password: "StrongPassword@123".to_string(),
+        attributes: values,
+    };
+
+    for values in [None, attributes(json!({"department": 1}))] {
+        let Err((status, _)) = service.create(dto(values)).await else {
+            panic!("Sin el atributo obligatorio válido no debería crearse el usuario");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let user = service
+        .create(dto(attributes(json!({"department": "ventas"}))))
+        .await
+        .expect("Error creando el usuario con sus atributos");
+    assert_eq!(
+        Value::Object(user.attributes),
+        json!({"department": "ventas"})
+    );
+}
+
+/// ---
+///
+/// ## Test Case 7: Un obligatorio definido después del alta no bloquea editar el resto
+///
+#[tokio::test]
+async fn test_new_required_attribute_does_not_block_edits() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let attributes_service = AttributeService::new(pool);
+    attributes_service
+        .upsert(
+            "nickname",
+            AttributeDefinitionDto {
+                kind: AttributeType::String,
+                required: false,
+                include_in_token: false,
+                user_editable: true,
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    let id = create_user(&service, "legacy_attrs").await;
+    define(
+        &attributes_service,
+        "department",
+        AttributeType::String,
+        true,
+        false,
+    )
+    .await;
+
+    let user = service
+        .update_myself(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"nickname": "yo"})),
+            ..Default::default()
+        })
+        .await
+        .expect("Le falta un obligatorio que no puede cargar, pero sí edita el resto");
+    assert_eq!(Value::Object(user.attributes), json!({"nickname": "yo"}));
+
+    service
+        .update(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"department": "ventas"})),
+            ..Default::default()
+        })
+        .await
+        .expect("El administrador completa el obligatorio");
+    let Err((status, _)) = service
+        .update(UpdateUserDto {
+            id: Some(id),
+            attributes: attributes(json!({"department": null})),
+            ..Default::default()
+        })
+        .await
+    else {
+        panic!("Un atributo obligatorio no debería poder borrarse");
+    };
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,44 @@
+pub mod attributes;
+
+use r_auth_api::{
+    database::models::{
+        dto::{AttributeDefinitionDto, CreateUserDto},
+        entities::attribute_definition::AttributeType,
+    },
+    services::{AttributeService, UsersService},
+};
+
+pub async fn define(
+    service: &AttributeService,
+    name: &str,
+    kind: AttributeType,
+    required: bool,
+    include_in_token: bool,
+) {
+    service
+        .upsert(
+            name,
+            AttributeDefinitionDto {
+                kind,
+                required,
+                include_in_token,
+                description: None,
+                user_editable: false,
+            },
+        )
+        .await
+        .expect("Error definiendo el atributo");
+}
+
+pub async fn create_user(service: &UsersService, username: &str) -> i64 {
+    service
+        .create(CreateUserDto {
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando el usuario")
+        .id
+}
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use r_auth_api::auth::{BloomFilter, BreachedPasswords, sha1_hex};
+
+const BREACHED: &[&str] = &["P@ssw0rd123!", "Summer2024!", "Qwerty#2020"];
+
+/// Directorio temporal con un volcado en el formato por prefijos de HIBP.
+fn prefix_dump(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("r_auth_breached_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for password in BREACHED {
+        let hash = sha1_hex(password);
+        let (prefix, suffix) = hash.split_at(5);
+        let content = format!("0000000000000000000000000000000000A:3\r\n{}:42\r\n", suffix);
+        std::fs::write(dir.join(format!("{}.txt", prefix)), content).unwrap();
+    }
+    dir
+}
+
+/// ---
+///
+/// ## Test Case 1: El SHA-1 coincide con el formato de HIBP
+///
+#[test]
+fn test_sha1_hex() {
+    assert_eq!(
+        sha1_hex("password"),
+        "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 2: El directorio por prefijos detecta las contraseñas filtradas
+///
+#[test]
+fn test_prefix_directory() {
+    let list = BreachedPasswords::open(prefix_dump("prefix")).unwrap();
+    for password in BREACHED {
+        assert!(
+            list.contains(password),
+            "{} debería estar filtrada",
+            password
+        );
+    }
+    assert!(!list.contains("Tq8#vLm2!pRz"));
+}
+
+/// ---
+///
+/// ## Test Case 3: El filtro armado desde el volcado no tiene falsos negativos
+///
+#[test]
+fn test_bloom_filter_from_dump() {
+    let dir = prefix_dump("filter");
+    let filter = BloomFilter::build_from_dump(&dir, 0.001, 1).unwrap();
+    let path = std::env::temp_dir().join("r_auth_breached_filter.bin");
+    filter.save(&path).unwrap();
+
+    let list = BreachedPasswords::open(&path).unwrap();
+    for password in BREACHED {
+        assert!(
+            list.contains(password),
+            "{} debería estar filtrada",
+            password
+        );
+    }
+    let false_positives = (0..1000)
+        .filter(|i| list.contains(&format!("no-filtrada-{}", i)))
+        .count();
+    assert!(false_positives < 20, "{} falsos positivos", false_positives);
+}
+
+/// ---
+///
+/// ## Test Case 4: Un volcado completo respeta la cantidad mínima de apariciones
+///
+#[test]
+fn test_full_dump_with_min_count() {
+    let path = std::env::temp_dir().join("r_auth_breached_full.txt");
+    let content = format!(
+        "{}:42\n{}:1\n",
+        sha1_hex(BREACHED[0]),
+        sha1_hex(BREACHED[1]).to_lowercase()
+    );
+    std::fs::write(&path, content).unwrap();
+
+    let filter = BloomFilter::build_from_dump(&path, 0.001, 2).unwrap();
+    assert!(filter.contains_hash(&sha1_hex(BREACHED[0])));
+    assert!(!filter.contains_hash(&sha1_hex(BREACHED[1])));
+
+    let all = BloomFilter::build_from_dump(&path, 0.001, 1).unwrap();
+    assert!(all.contains_hash(&sha1_hex(BREACHED[1])));
+}
+
+/// ---
+///
+/// ## Test Case 5: Archivos inválidos se rechazan
+///
+#[test]
+fn test_invalid_files() {
+    let path = std::env::temp_dir().join("r_auth_breached_invalid.bin");
+    std::fs::write(&path, b"no es un filtro").unwrap();
+    assert!(BreachedPasswords::open(&path).is_err());
+
+    let dump = std::env::temp_dir().join("r_auth_breached_invalid.txt");
+    std::fs::write(&dump, "no-es-un-hash:3\n").unwrap();
+    assert!(BloomFilter::build_from_dump(&dump, 0.001, 1).is_err());
+}
@@ -0,0 +1,283 @@
+use std::sync::{Arc, Mutex};
+
+use bytes::BytesMut;
+use lber::{
+    common::TagClass,
+    parse::parse_tag,
+    structure::{PL, StructureTag},
+    write::encode_into,
+};
+use r_auth_api::config::LdapConfig;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+pub const BASE_DN: &str = "dc=example,dc=org";
+pub const SERVICE_DN: &str = "cn=admin,dc=example,dc=org";
+pub const SERVICE_PASSWORD: &str = "admin-secret";
+pub const ADMINS_GROUP: &str = "cn=admins,ou=groups,dc=example,dc=org";
+
+#[derive(Clone)]
+pub struct MockEntry {
+    pub dn: String,
+    pub password: String,
+    pub attributes: Vec<(String, Vec<String>)>,
+}
+
+impl MockEntry {
+    pub fn person(uid: &str, email: &str, password: &str, groups: &[&str]) -> Self {
+        MockEntry {
+            dn: format!("uid={},ou=people,{}", uid, BASE_DN),
+            password: password.to_string(),
+            attributes: vec![
+                ("objectClass".to_string(), vec!["person".to_string()]),
+                ("uid".to_string(), vec![uid.to_string()]),
+                ("mail".to_string(), vec![email.to_string()]),
+                (
+                    "memberOf".to_string(),
+                    groups.iter().map(|g| g.to_string()).collect(),
+                ),
+            ],
+        }
+    }
+
+    fn values(&self, attribute: &str) -> Vec<String> {
+        self.attributes
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(attribute))
+            .flat_map(|(_, v)| v.clone())
+            .collect()
+    }
+}
+
+/// Servidor LDAP en memoria que entiende bind simple y búsquedas con filtros
+/// `&`, `|`, igualdad y presencia.
+pub struct MockLdapServer {
+    pub url: String,
+    pub entries: Arc<Mutex<Vec<MockEntry>>>,
+}
+
+impl MockLdapServer {
+    pub async fn start(entries: Vec<MockEntry>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("TEST ERROR: no se pudo iniciar el servidor LDAP simulado");
+        let url = format!("ldap://{}", listener.local_addr().unwrap());
+        let entries = Arc::new(Mutex::new(entries));
+
+        let shared = entries.clone();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = match listener.accept().await {
+                    Ok(s) => s,
+                    Err(_) => return,
+                };
+                let entries = shared.clone();
+                tokio::spawn(async move {
+                    let _ = serve(socket, entries).await;
+                });
+            }
+        });
+
+        MockLdapServer { url, entries }
+    }
+
+    pub fn config(&self) -> LdapConfig {
+        LdapConfig {
+            url: self.url.clone(),
+            bind_dn: SERVICE_DN.to_string(),
+            bind_password: SERVICE_PASSWORD.to_string(),
+            search_base: BASE_DN.to_string(),
+            user_filter: "(&(objectClass=person)(mail={email}))".to_string(),
+            username_attribute: "uid".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            group_search_base: None,
+            group_filter: "(member={dn})".to_string(),
+            group_permissions: vec![(
+                ADMINS_GROUP.to_string(),
+                r_auth_api::utils::Permissions::ADMIN,
+            )],
+            link_by_email: false,
+        }
+    }
+}
+
+async fn serve(
+    mut socket: TcpStream,
+    entries: Arc<Mutex<Vec<MockEntry>>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut buffer = Vec::new();
+    loop {
+        let message = match parse_tag(&buffer) {
+            Ok((rest, message)) => {
+                buffer = rest.to_vec();
+                message
+            }
+            Err(lber::Err::Incomplete(_)) => {
+                if socket.read_buf(&mut buffer).await? == 0 {
+                    return Ok(());
+                }
+                continue;
+            }
+            Err(e) => return Err(e.to_string().into()),
+        };
+        let mut parts = children(message).into_iter();
+        let id = integer(parts.next().unwrap());
+        let op = parts.next().unwrap();
+
+        match (op.class, op.id) {
+            (TagClass::Application, 0) => {
+                let fields = children(op);
+                let dn = text(&fields[1]);
+                let password = text(&fields[2]);
+                let ok = dn == SERVICE_DN && password == SERVICE_PASSWORD
+                    || entries
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .any(|e| e.dn == dn && e.password == password && !password.is_empty());
+                let code = if ok { 0 } else { 49 };
+                write(&mut socket, response(id, 1, code)).await?;
+            }
+            (TagClass::Application, 3) => {
+                let fields = children(op);
+                let base = text(&fields[0]).to_lowercase();
+                let filter = &fields[6];
+                let matches: Vec<MockEntry> = entries
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|e| e.dn.to_lowercase().ends_with(&base) && matches(e, filter))
+                    .cloned()
+                    .collect();
+                for entry in matches {
+                    let attributes = entry
+                        .attributes
+                        .iter()
+                        .map(|(name, values)| {
+                            universal(
+                                16,
+                                PL::C(vec![
+                                    octet_string(name),
+                                    universal(
+                                        17,
+                                        PL::C(values.iter().map(|v| octet_string(v)).collect()),
+                                    ),
+                                ]),
+                            )
+                        })
+                        .collect();
+                    let op = application(
+                        4,
+                        PL::C(vec![
+                            octet_string(&entry.dn),
+                            universal(16, PL::C(attributes)),
+                        ]),
+                    );
+                    write(&mut socket, envelope(id, op)).await?;
+                }
+                write(&mut socket, response(id, 5, 0)).await?;
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn matches(entry: &MockEntry, filter: &StructureTag) -> bool {
+    match (&filter.payload, filter.id) {
+        (PL::C(items), 0) => items.iter().all(|f| matches(entry, f)),
+        (PL::C(items), 1) => items.iter().any(|f| matches(entry, f)),
+        (PL::C(ava), 3) => {
+            let attribute = text(&ava[0]);
+            let value = text(&ava[1]);
+            if attribute.eq_ignore_ascii_case("member") {
+                return false;
+            }
+            entry
+                .values(&attribute)
+                .iter()
+                .any(|v| v.eq_ignore_ascii_case(&value))
+        }
+        (PL::P(attribute), 7) => !entry.values(&String::from_utf8_lossy(attribute)).is_empty(),
+        _ => false,
+    }
+}
+
+fn children(tag: StructureTag) -> Vec<StructureTag> {
+    tag.expect_constructed().unwrap()
+}
+
+fn text(tag: &StructureTag) -> String {
+    match &tag.payload {
+        PL::P(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        PL::C(_) => panic!("Se esperaba un valor primitivo"),
+    }
+}
+
+fn integer(tag: StructureTag) -> i64 {
+    let bytes = tag.expect_primitive().unwrap();
+    let sign = if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        -1
+    } else {
+        0
+    };
+    bytes.iter().fold(sign, |acc, b| (acc << 8) | *b as i64)
+}
+
+fn universal(id: u64, payload: PL) -> StructureTag {
+    StructureTag {
+        class: TagClass::Universal,
+        id,
+        payload,
+    }
+}
+
+fn application(id: u64, payload: PL) -> StructureTag {
+    StructureTag {
+        class: TagClass::Application,
+        id,
+        payload,
+    }
+}
+
+fn octet_string(value: &str) -> StructureTag {
+    universal(4, PL::P(value.as_bytes().to_vec()))
+}
+
+/// Entero BER en complemento a dos con la menor cantidad de bytes.
+fn encode_int(id: u64, value: i64) -> StructureTag {
+    let bytes = value.to_be_bytes();
+    let skip = (0..7)
+        .take_while(|&i| {
+            (bytes[i] == 0x00 && bytes[i + 1] & 0x80 == 0)
+                || (bytes[i] == 0xff && bytes[i + 1] & 0x80 != 0)
+        })
+        .count();
+    universal(id, PL::P(bytes[skip..].to_vec()))
+}
+
+fn envelope(id: i64, op: StructureTag) -> StructureTag {
+    universal(16, PL::C(vec![encode_int(2, id), op]))
+}
+
+fn response(id: i64, op: u64, code: i64) -> StructureTag {
+    envelope(
+        id,
+        application(
+            op,
+            PL::C(vec![
+                encode_int(10, code),
+                octet_string(""),
+                octet_string(""),
+            ]),
+        ),
+    )
+}
+
+async fn write(socket: &mut TcpStream, message: StructureTag) -> std::io::Result<()> {
+    let mut buffer = BytesMut::new();
+    encode_into(&mut buffer, message)?;
+    socket.write_all(&buffer).await
+}
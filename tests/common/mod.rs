@@ -1,8 +1,14 @@
+pub mod ldap_mock;
+pub mod oidc_mock;
+
+use async_trait::async_trait;
 use colored::Colorize;
 use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
 use dotenv::dotenv;
 use once_cell::sync::Lazy;
+use r_auth_api::mailer::{Email, Mailer, MailerError};
 use std::env;
+use std::sync::Mutex;
 use tokio_postgres::NoTls;
 use tokio_postgres::config::{Config, SslMode};
 
@@ -49,10 +55,42 @@ pub async fn setup_test_environment(pool: &PgPool) {
     client
         .query(
             r#"
-            TRUNCATE TABLE users RESTART IDENTITY CASCADE;
+            TRUNCATE TABLE users, groups, user_attribute_definitions RESTART IDENTITY CASCADE;
         "#,
             &[],
         )
         .await
         .expect("TEST ERROR: Error al limpiar la base de datos de pruebas");
 }
+
+/// Mailer de pruebas que guarda los correos en memoria.
+#[derive(Default)]
+pub struct MemoryMailer {
+    pub sent: Mutex<Vec<Email>>,
+}
+
+#[async_trait]
+impl Mailer for MemoryMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        self.sent.lock().unwrap().push(email);
+        Ok(())
+    }
+}
+
+impl MemoryMailer {
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+/// Extrae el token de un enlace `...?token=<token>` contenido en el cuerpo de un correo.
+pub fn extract_token(body: &str) -> String {
+    let start = body
+        .find("token=")
+        .expect("TEST ERROR: el correo no contiene token")
+        + 6;
+    body[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Form, Json, Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use r_auth_api::config::OidcProviderConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+pub const CLIENT_ID: &str = "r-auth-test-client";
+pub const CLIENT_SECRET: &str = "r-auth-test-secret";
+const PRIVATE_KEY: &[u8] = include_bytes!("../fixtures/oidc_private_key.pem");
+const JWKS: &str = include_str!("../fixtures/oidc_jwks.json");
+
+/// Identidad que el proveedor simulado devolverá al canjear un código.
+#[derive(Clone)]
+pub struct MockLogin {
+    pub subject: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub nonce: String,
+    pub code_challenge: String,
+}
+
+#[derive(Clone)]
+struct MockState {
+    issuer: String,
+    codes: Arc<Mutex<HashMap<String, MockLogin>>>,
+    algorithm: Arc<Mutex<Algorithm>>,
+}
+
+/// Proveedor OIDC en memoria que expone discovery, JWKS y token endpoint.
+pub struct MockOidcProvider {
+    pub issuer: String,
+    codes: Arc<Mutex<HashMap<String, MockLogin>>>,
+    algorithm: Arc<Mutex<Algorithm>>,
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    grant_type: String,
+    code: String,
+    client_id: String,
+    client_secret: String,
+    code_verifier: String,
+}
+
+#[derive(Serialize)]
+struct IdToken {
+    iss: String,
+    aud: String,
+    sub: String,
+    email: String,
+    email_verified: bool,
+    nonce: String,
+    iat: i64,
+    exp: i64,
+}
+
+impl MockOidcProvider {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("TEST ERROR: no se pudo iniciar el proveedor OIDC simulado");
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let codes = Arc::new(Mutex::new(HashMap::new()));
+        let algorithm = Arc::new(Mutex::new(Algorithm::RS256));
+
+        let state = MockState {
+            issuer: issuer.clone(),
+            codes: codes.clone(),
+            algorithm: algorithm.clone(),
+        };
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(state);
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        MockOidcProvider {
+            issuer,
+            codes,
+            algorithm,
+        }
+    }
+
+    pub fn provider_config(&self, name: &str) -> OidcProviderConfig {
+        OidcProviderConfig {
+            name: name.to_string(),
+            issuer: self.issuer.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: CLIENT_SECRET.to_string(),
+            redirect_uri: format!("http://localhost:3032/api/auth/oidc/{}/callback", name),
+            scopes: "openid email profile".to_string(),
+            algorithms: vec![],
+        }
+    }
+
+    /// Firma los próximos id_token con `algorithm`; HS* usa el client_secret.
+    pub fn sign_with(&self, algorithm: Algorithm) {
+        *self.algorithm.lock().unwrap() = algorithm;
+    }
+
+    /// Simula que el usuario se autenticó en el proveedor y devuelve el código.
+    pub fn issue_code(&self, login: MockLogin) -> String {
+        let code = format!("code-{}", login.subject);
+        self.codes.lock().unwrap().insert(code.clone(), login);
+        code
+    }
+}
+
+async fn discovery(State(state): State<MockState>) -> Json<Value> {
+    Json(json!({
+        "issuer": state.issuer,
+        "authorization_endpoint": format!("{}/authorize", state.issuer),
+        "token_endpoint": format!("{}/token", state.issuer),
+        "jwks_uri": format!("{}/jwks", state.issuer),
+        "id_token_signing_alg_values_supported": ["RS256"],
+    }))
+}
+
+async fn jwks() -> Json<Value> {
+    Json(serde_json::from_str(JWKS).unwrap())
+}
+
+async fn token(
+    State(state): State<MockState>,
+    Form(form): Form<TokenForm>,
+) -> (StatusCode, Json<Value>) {
+    let invalid = (
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": "invalid_grant" })),
+    );
+    if form.grant_type != "authorization_code"
+        || form.client_id != CLIENT_ID
+        || form.client_secret != CLIENT_SECRET
+    {
+        return invalid;
+    }
+    let login = match state.codes.lock().unwrap().remove(&form.code) {
+        Some(l) => l,
+        None => return invalid,
+    };
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(form.code_verifier.as_bytes()));
+    if challenge != login.code_challenge {
+        return invalid;
+    }
+
+    let now = Utc::now().timestamp();
+    let claims = IdToken {
+        iss: state.issuer.clone(),
+        aud: CLIENT_ID.to_string(),
+        sub: login.subject,
+        email: login.email,
+        email_verified: login.email_verified,
+        nonce: login.nonce,
+        iat: now,
+        exp: now + 300,
+    };
+    let algorithm = *state.algorithm.lock().unwrap();
+    let mut header = Header::new(algorithm);
+    let key = match algorithm {
+        Algorithm::HS256 => EncodingKey::from_secret(CLIENT_SECRET.as_bytes()),
+        _ => {
+            header.kid = Some("test-key".to_string());
+            EncodingKey::from_rsa_pem(PRIVATE_KEY).unwrap()
+        }
+    };
+    let id_token = encode(&header, &claims, &key).unwrap();
+
+    (
+        StatusCode::OK,
+        Json(json!({ "access_token": "mock", "token_type": "Bearer", "id_token": id_token })),
+    )
+}
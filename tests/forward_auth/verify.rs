@@ -0,0 +1,123 @@
+use axum::{
+    Json,
+    extract::Query,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+};
+use r_auth_api::{
+    auth::{AuthenticatedClaims, SESSION_COOKIE, request_token},
+    database::models::{claims::Claims, dto::VerifyQuery, entities::user::User},
+    handlers::auth_handler::verify,
+    utils::{Permissions, USER_PERMISSIONS, errors::HttpError},
+};
+
+fn claims_with(permissions: Permissions) -> AuthenticatedClaims {
+    let mut user = User::new(7, "proxy_user".to_string(), "proxy@example.com".to_string());
+    user.permissions = permissions.bits();
+    let mut claims = Claims::new("7".to_string(), 60);
+    claims.set_user(user);
+    AuthenticatedClaims(claims)
+}
+
+fn query(permissions: Option<&str>) -> Query<VerifyQuery> {
+    Query(VerifyQuery {
+        permissions: permissions.map(str::to_string),
+    })
+}
+
+/// ---
+///
+/// ## Test Case 1: Un token válido devuelve los headers X-Auth-*
+///
+#[tokio::test]
+async fn test_verify_success_headers() {
+    let (status, headers) = verify(Ok(claims_with(USER_PERMISSIONS)), query(None))
+        .await
+        .expect("La verificación debería ser exitosa");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["x-auth-user-id"], "7");
+    assert_eq!(headers["x-auth-username"], "proxy_user");
+    assert_eq!(
+        headers["x-auth-permissions"],
+        "READ_MYSELF,UPDATE_MYSELF,DELETE_MYSELF"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 2: Los permisos de la query se exigen; ADMIN los cubre todos
+///
+#[tokio::test]
+async fn test_verify_required_permissions() {
+    let err = verify(
+        Ok(claims_with(USER_PERMISSIONS)),
+        query(Some("READ_USERS,UPDATE_USERS")),
+    )
+    .await
+    .expect_err("Debería faltar el permiso");
+    assert_eq!(err.0, StatusCode::FORBIDDEN);
+
+    let ok = verify(
+        Ok(claims_with(
+            Permissions::READ_USERS | Permissions::UPDATE_USERS,
+        )),
+        query(Some("read_users|UPDATE_USERS")),
+    )
+    .await;
+    assert!(ok.is_ok());
+
+    let ok = verify(
+        Ok(claims_with(Permissions::ADMIN)),
+        query(Some("DELETE_USERS")),
+    )
+    .await;
+    assert!(ok.is_ok());
+
+    let err = verify(Ok(claims_with(Permissions::ADMIN)), query(Some("FLY")))
+        .await
+        .expect_err("Un permiso desconocido debería fallar");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+}
+
+/// ---
+///
+/// ## Test Case 3: Los fallos de autenticación se responden como 401, salvo usuario inactivo
+///
+#[tokio::test]
+async fn test_verify_rejections() {
+    for rejection in [
+        HttpError::not_found("Usuario no encontrado"),
+        HttpError::bad_request("Id de usuario inválido"),
+        HttpError::unauthorized("Token inválido"),
+    ] {
+        let err = verify(Err(rejection), query(None)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, Json(_)) = verify(Err(HttpError::forbbiden("Usuario inactivo")), query(None))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// ---
+///
+/// ## Test Case 4: El token se toma del header Bearer o de la cookie de sesión
+///
+#[test]
+fn test_request_token_sources() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(request_token(&headers), None);
+
+    headers.insert(
+        header::COOKIE,
+        HeaderValue::from_str(&format!("theme=dark; {}=cookie-jwt", SESSION_COOKIE)).unwrap(),
+    );
+    assert_eq!(request_token(&headers).as_deref(), Some("cookie-jwt"));
+
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_static("Bearer header-jwt"),
+    );
+    assert_eq!(request_token(&headers).as_deref(), Some("header-jwt"));
+}
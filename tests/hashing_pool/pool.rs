@@ -0,0 +1,135 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::http::StatusCode;
+use r_auth_api::{auth::HashingPool, handlers::metrics_handler::render_prometheus};
+use tokio::sync::Barrier;
+
+/// ---
+///
+/// ## Test Case 1: Los trabajos se ejecutan y se cuentan como completados
+///
+#[tokio::test]
+async fn test_run_completes_jobs() {
+    let pool = HashingPool::new(2, Duration::from_secs(5));
+
+    let result = pool.run(|| 21 * 2).await.unwrap();
+    assert_eq!(result, 42);
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.concurrency, 2);
+    assert_eq!(metrics.completed, 1);
+    assert_eq!(metrics.rejected, 0);
+    assert_eq!(metrics.queue_depth, 0);
+    assert_eq!(metrics.in_flight, 0);
+}
+
+/// ---
+///
+/// ## Test Case 2: Con el pool lleno, la espera más allá del timeout responde 503
+///
+#[tokio::test]
+async fn test_overload_returns_service_unavailable() {
+    let pool = Arc::new(HashingPool::new(1, Duration::from_millis(50)));
+    let started = Arc::new(Barrier::new(2));
+    let (release, released) = std::sync::mpsc::channel::<()>();
+
+    let busy = {
+        let pool = pool.clone();
+        let started = started.clone();
+        tokio::spawn(async move {
+            let handle = tokio::runtime::Handle::current();
+            pool.run(move || {
+                handle.block_on(started.wait());
+                released.recv().unwrap();
+            })
+            .await
+        })
+    };
+    started.wait().await;
+    assert_eq!(pool.metrics().in_flight, 1);
+
+    let (status, error) = pool.run(|| ()).await.unwrap_err();
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(error.0.errors.contains_key("server"));
+
+    release.send(()).unwrap();
+    busy.await.unwrap().unwrap();
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.rejected, 1);
+    assert_eq!(metrics.completed, 1);
+    assert_eq!(metrics.in_flight, 0);
+    assert_eq!(metrics.queue_depth, 0);
+}
+
+/// ---
+///
+/// ## Test Case 3: La cola refleja los trabajos esperando un lugar
+///
+#[tokio::test]
+async fn test_queue_depth_counts_waiting_jobs() {
+    let pool = Arc::new(HashingPool::new(1, Duration::from_secs(5)));
+    let (release, released) = std::sync::mpsc::channel::<()>();
+
+    let busy = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.run(move || released.recv().unwrap()).await })
+    };
+    let queued = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.run(|| ()).await })
+    };
+
+    let mut depth = 0;
+    for _ in 0..100 {
+        depth = pool.metrics().queue_depth;
+        if depth == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(depth, 1);
+
+    release.send(()).unwrap();
+    busy.await.unwrap().unwrap();
+    queued.await.unwrap().unwrap();
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.completed, 2);
+    assert_eq!(metrics.queue_depth, 0);
+
+    let text = render_prometheus(&metrics);
+    assert!(text.contains("r_auth_password_hash_completed_total 2\n"));
+    assert!(text.contains("r_auth_password_hash_queue_depth 0\n"));
+    assert!(text.contains("# TYPE r_auth_password_hash_rejected_total counter\n"));
+}
+
+/// ---
+///
+/// ## Test Case 4: Los trabajos encolados sin límite esperan más allá del timeout
+///
+#[tokio::test]
+async fn test_run_queued_waits_past_queue_timeout() {
+    let pool = Arc::new(HashingPool::new(1, Duration::from_millis(50)));
+    let (release, released) = std::sync::mpsc::channel::<()>();
+
+    let busy = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.run(move || released.recv().unwrap()).await })
+    };
+    let queued = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.run_queued(|| 7).await })
+    };
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!queued.is_finished());
+
+    release.send(()).unwrap();
+    busy.await.unwrap().unwrap();
+    assert_eq!(queued.await.unwrap().unwrap(), 7);
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.rejected, 0);
+    assert_eq!(metrics.completed, 2);
+}
@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use axum::{Json, http::StatusCode};
+use r_auth_api::{
+    database::models::{
+        dto::{AcceptInvitationDto, AttributeDefinitionDto, CreateInvitationDto},
+        entities::attribute_definition::AttributeType,
+    },
+    services::{AttributeService, InvitationService, UsersService},
+    utils::Permissions,
+};
+use serde_json::{Value, json};
+
+use crate::common::{self, MemoryMailer};
+
+fn accept_dto(token: &str, username: &str, password: &str) -> AcceptInvitationDto {
+    AcceptInvitationDto {
+        token: token.to_string(),
+        username: username.to_string(),
+        password: password.to_string(),
+        attributes: None,
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: Aceptar crea el usuario con los permisos y grupos de la invitación
+///
+#[tokio::test]
+async fn test_accept_invitation_creates_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = InvitationService::new(pool, mailer.clone());
+
+    let client = pool.get().await.unwrap();
+    let group_id: i64 = client
+        .query_one(
+            "INSERT INTO groups (display_name) VALUES ('Soporte') RETURNING id",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+
+    let permissions = Permissions::READ_MYSELF | Permissions::READ_USERS;
+    let invitation = service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            CreateInvitationDto {
+                email: "invited@example.com".to_string(),
+                permissions: Some(permissions.bits()),
+                groups: vec![group_id],
+            },
+        )
+        .await
+        .expect("Error creando la invitación");
+    let token = common::extract_token(&mailer.sent().last().unwrap().body);
+
+    let user = service
+        .accept(accept_dto(&token, "invited_user", "Welcome@Pass1"))
+        .await
+        .expect("Error aceptando la invitación");
+    assert_eq!(user.email, "invited@example.com");
+    assert_eq!(user.permissions, permissions.bits());
+
+    let member: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM group_members WHERE group_id = $1 AND user_id = $2",
+            &[&group_id, &user.id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(member, 1);
+
+    let linked: Option<i64> = client
+        .query_one(
+            "SELECT user_id FROM invitations WHERE id = $1",
+            &[&invitation.id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(linked, Some(user.id));
+}
+
+/// ---
+///
+/// ## Test Case 2: El enlace es de un solo uso
+///
+#[tokio::test]
+async fn test_accept_invitation_single_use() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = InvitationService::new(pool, mailer.clone());
+
+    service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            CreateInvitationDto {
+                email: "single_use@example.com".to_string(),
+                permissions: None,
+                groups: vec![],
+            },
+        )
+        .await
+        .unwrap();
+    let token = common::extract_token(&mailer.sent().last().unwrap().body);
+
+    service
+        .accept(accept_dto(&token, "single_use", "Single@Pass1"))
+        .await
+        .unwrap();
+    let (status, _) = service
+        .accept(accept_dto(&token, "single_use_2", "Single@Pass1"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+/// ---
+///
+/// ## Test Case 3: La contraseña pasa por la política y una invitación vencida se rechaza
+///
+#[tokio::test]
+async fn test_accept_invitation_validates_password_and_expiry() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = InvitationService::new(pool, mailer.clone());
+
+    let invitation = service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            CreateInvitationDto {
+                email: "weak_invite@example.com".to_string(),
+                permissions: None,
+                groups: vec![],
+            },
+        )
+        .await
+        .unwrap();
+    let token = common::extract_token(&mailer.sent().last().unwrap().body);
+
+    let (status, Json(error)) = service
+        .accept(accept_dto(&token, "weak_invite", "weak"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.errors.contains_key("password"));
+
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE invitations SET expires_at = now() - interval '1 minute' WHERE id = $1",
+            &[&invitation.id],
+        )
+        .await
+        .unwrap();
+    let (status, _) = service
+        .accept(accept_dto(&token, "weak_invite", "Strong@Pass1"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let users = UsersService::new(pool);
+    assert!(
+        users
+            .find_by_email("weak_invite@example.com")
+            .await
+            .is_err()
+    );
+}
+
+/// ---
+///
+/// ## Test Case 4: Aceptar exige los atributos obligatorios sin consumir la invitación
+///
+#[tokio::test]
+async fn test_accept_invitation_requires_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = InvitationService::new(pool, mailer.clone());
+    AttributeService::new(pool)
+        .upsert(
+            "nickname",
+            AttributeDefinitionDto {
+                kind: AttributeType::String,
+                required: true,
+                include_in_token: false,
+                user_editable: true,
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            CreateInvitationDto {
+                email: "invited_attrs@example.com".to_string(),
+                permissions: None,
+                groups: vec![],
+            },
+        )
+        .await
+        .unwrap();
+    let token = common::extract_token(&mailer.sent().last().unwrap().body);
+
+    let (status, _) = service
+        .accept(accept_dto(&token, "invited_attrs", "Welcome@Pass1"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let user = service
+        .accept(AcceptInvitationDto {
+            attributes: json!({"nickname": "yo"}).as_object().cloned(),
+            ..accept_dto(&token, "invited_attrs", "Welcome@Pass1")
+        })
+        .await
+        .expect("La invitación debería seguir vigente tras el rechazo");
+    assert_eq!(Value::Object(user.attributes), json!({"nickname": "yo"}));
+}
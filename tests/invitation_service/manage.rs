@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::{
+        dto::{AcceptInvitationDto, CreateInvitationDto, CreateUserDto, InvitationListQuery},
+        entities::invitation::InvitationStatus,
+    },
+    services::{InvitationService, UsersService},
+    utils::Permissions,
+};
+
+use crate::common::{self, MemoryMailer};
+
+fn invite(email: &str) -> CreateInvitationDto {
+    CreateInvitationDto {
+        email: email.to_string(),
+        permissions: None,
+        groups: vec![],
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: No se invita a emails con cuenta ni se duplican invitaciones pendientes
+///
+#[tokio::test]
+async fn test_create_invitation_conflicts() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let service = InvitationService::new(pool, Arc::new(MemoryMailer::default()));
+    UsersService::new(pool)
+        .create(CreateUserDto {
+            username: "already_here".to_string(),
+            email: "already_here@example.com".to_string(),
+            password: "Password@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .unwrap();
+
+    let (status, _) = service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            invite("already_here@example.com"),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    service
+        .create(inviter, Permissions::ADMIN, invite("twice@example.com"))
+        .await
+        .unwrap();
+    let (status, _) = service
+        .create(inviter, Permissions::ADMIN, invite("Twice@example.com"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+/// ---
+///
+/// ## Test Case 2: Solo un administrador otorga permisos que no tiene
+///
+#[tokio::test]
+async fn test_create_invitation_permission_escalation() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let service = InvitationService::new(pool, Arc::new(MemoryMailer::default()));
+
+    let granter = Permissions::CREATE_USERS | Permissions::READ_MYSELF;
+    let (status, _) = service
+        .create(
+            inviter,
+            granter,
+            CreateInvitationDto {
+                permissions: Some(Permissions::ADMIN.bits()),
+                ..invite("escalate@example.com")
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let invitation = service
+        .create(
+            inviter,
+            granter,
+            CreateInvitationDto {
+                permissions: Some(Permissions::READ_MYSELF.bits()),
+                ..invite("escalate@example.com")
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(invitation.status, InvitationStatus::Pending);
+}
+
+/// ---
+///
+/// ## Test Case 3: Reenviar invalida el enlace anterior y revocar impide aceptar
+///
+#[tokio::test]
+async fn test_resend_and_revoke_invitation() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = InvitationService::new(pool, mailer.clone());
+
+    let invitation = service
+        .create(inviter, Permissions::ADMIN, invite("resend@example.com"))
+        .await
+        .unwrap();
+    let first = common::extract_token(&mailer.sent().last().unwrap().body);
+
+    service.resend(invitation.id).await.unwrap();
+    let second = common::extract_token(&mailer.sent().last().unwrap().body);
+    assert_ne!(first, second);
+    assert_eq!(mailer.sent().len(), 2);
+
+    let accept = |token: &str| AcceptInvitationDto {
+        token: token.to_string(),
+        username: "resend_user".to_string(),
+        password: "Resend@Pass1".to_string(),
+        attributes: None,
+    };
+    let (status, _) = service.accept(accept(&first)).await.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    service.revoke(invitation.id).await.unwrap();
+    let (status, _) = service.accept(accept(&second)).await.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = service.revoke(invitation.id).await.unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = service.resend(i64::MAX).await.unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// ---
+///
+/// ## Test Case 4: El listado filtra por estado
+///
+#[tokio::test]
+async fn test_list_invitations_by_status() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = super::inviter().await;
+    let service = InvitationService::new(pool, Arc::new(MemoryMailer::default()));
+
+    let revoked = service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            invite("list_revoked@example.com"),
+        )
+        .await
+        .unwrap();
+    service.revoke(revoked.id).await.unwrap();
+    service
+        .create(
+            inviter,
+            Permissions::ADMIN,
+            invite("list_pending@example.com"),
+        )
+        .await
+        .unwrap();
+
+    let all = service.list(InvitationListQuery::default()).await.unwrap();
+    assert_eq!(all.total, Some(2));
+
+    let pending = service
+        .list(InvitationListQuery {
+            status: Some("pending".to_string()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(pending.total, Some(1));
+    assert_eq!(pending.results[0].email, "list_pending@example.com");
+
+    let (status, _) = service
+        .list(InvitationListQuery {
+            status: Some("unknown".to_string()),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,20 @@
+pub mod accept;
+pub mod manage;
+
+use r_auth_api::{database::models::dto::CreateUserDto, services::UsersService};
+
+use crate::common;
+
+/// Usuario que figura como autor de las invitaciones.
+pub async fn inviter() -> i64 {
+    UsersService::new(common::get_test_pool())
+        .create(CreateUserDto {
+            username: "inviter".to_string(),
+            email: "inviter@example.com".to_string(),
+            password: "Admin@Pass123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando el usuario que invita")
+        .id
+}
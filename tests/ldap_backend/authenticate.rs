@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use axum::{Json, http::StatusCode};
+use r_auth_api::{
+    auth::{
+        backends::{AuthBackend, LdapBackend, PasswordBackend},
+        decode_jwt,
+    },
+    config::LdapConfig,
+    database::models::dto::{CreateUserDto, LoginRequest},
+    services::UsersService,
+    utils::{Permissions, USER_PERMISSIONS},
+};
+
+use crate::common::{
+    self,
+    ldap_mock::{ADMINS_GROUP, MockEntry, MockLdapServer},
+};
+
+fn service_with_ldap(server: &MockLdapServer) -> UsersService {
+    service_with_config(server.config())
+}
+
+fn service_with_config(config: LdapConfig) -> UsersService {
+    let pool = common::get_test_pool();
+    let backends: Vec<Arc<dyn AuthBackend>> = vec![
+        Arc::new(PasswordBackend::new(pool)),
+        Arc::new(LdapBackend::new(pool, config)),
+    ];
+    UsersService::with_backends(pool, backends)
+}
+
+/// Cuenta local con el mismo email que una entrada administradora del directorio.
+async fn local_and_directory_user(username: &str) -> (UsersService, MockLdapServer, i64) {
+    let email = format!("{}@example.org", username);
+    let server = MockLdapServer::start(vec![MockEntry::person(
+        username,
+        &email,
+        "DirectoryPass1!",
+        &[ADMINS_GROUP],
+    )])
+    .await;
+    let users_service = service_with_ldap(&server);
+    let local = users_service
+        .create(CreateUserDto {
+            username: username.to_string(),
+            email,
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+    (users_service, server, local.id)
+}
+
+fn directory_login(username: &str) -> LoginRequest {
+    LoginRequest {
+        email: format!("{}@example.org", username),
+        password: "DirectoryPass1!".to_string(),
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: El primer login LDAP aprovisiona el usuario con permisos de sus grupos
+///
+#[tokio::test]
+async fn test_ldap_login_provisions_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let server = MockLdapServer::start(vec![MockEntry::person(
+        "jdoe",
+        "jdoe@example.org",
+        "DirectoryPass1!",
+        &[ADMINS_GROUP],
+    )])
+    .await;
+    let users_service = service_with_ldap(&server);
+
+    let result = users_service
+        .login(LoginRequest {
+            email: "jdoe@example.org".to_string(),
+            password: "DirectoryPass1!".to_string(),
+        })
+        .await;
+
+    assert!(
+        result.is_ok(),
+        "El login LDAP debería ser exitoso. Error: {:?}",
+        result.unwrap_err()
+    );
+    let claims = decode_jwt(&result.unwrap()).unwrap();
+    let user = users_service
+        .find_by_id(claims.user_id.parse().unwrap())
+        .await
+        .expect("El usuario debería haberse aprovisionado");
+    assert_eq!(user.username, "jdoe");
+    assert_eq!(user.email, "jdoe@example.org");
+    assert_eq!(
+        user.permissions,
+        (USER_PERMISSIONS | Permissions::ADMIN).bits()
+    );
+}
+
+/// ---
+///
+/// ## Test Case 2: Contraseña incorrecta en el directorio devuelve credenciales inválidas
+///
+#[tokio::test]
+async fn test_ldap_login_wrong_password() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let server = MockLdapServer::start(vec![MockEntry::person(
+        "wrongpwd",
+        "wrongpwd@example.org",
+        "DirectoryPass1!",
+        &[],
+    )])
+    .await;
+    let users_service = service_with_ldap(&server);
+
+    let result = users_service
+        .login(LoginRequest {
+            email: "wrongpwd@example.org".to_string(),
+            password: "NotThePassword1!".to_string(),
+        })
+        .await;
+
+    assert!(result.is_err(), "El login debería fallar");
+    let (status, Json(http_error)) = result.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        http_error.errors.get("client").unwrap().first().unwrap(),
+        "Credenciales inválidas"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: Los usuarios locales siguen autenticándose con su contraseña
+///
+#[tokio::test]
+async fn test_local_user_login_with_ldap_enabled() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let server = MockLdapServer::start(vec![]).await;
+    let users_service = service_with_ldap(&server);
+
+    users_service
+        .create(CreateUserDto {
+            username: "local_only".to_string(),
+            email: "local_only@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    let result = users_service
+        .login(LoginRequest {
+            email: "local_only@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+        })
+        .await;
+
+    assert!(result.is_ok(), "El login local debería ser exitoso");
+}
+
+/// ---
+///
+/// ## Test Case 4: Los permisos se sincronizan con los grupos en cada login
+///
+#[tokio::test]
+async fn test_ldap_login_syncs_permissions() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let server = MockLdapServer::start(vec![MockEntry::person(
+        "demoted",
+        "demoted@example.org",
+        "DirectoryPass1!",
+        &[ADMINS_GROUP],
+    )])
+    .await;
+    let users_service = service_with_ldap(&server);
+    let token = users_service
+        .login(LoginRequest {
+            email: "demoted@example.org".to_string(),
+            password: "DirectoryPass1!".to_string(),
+        })
+        .await
+        .expect("El primer login debería ser exitoso");
+    let user_id: i64 = decode_jwt(&token).unwrap().user_id.parse().unwrap();
+
+    *server.entries.lock().unwrap() = vec![MockEntry::person(
+        "demoted",
+        "demoted@example.org",
+        "DirectoryPass1!",
+        &[],
+    )];
+
+    users_service
+        .login(LoginRequest {
+            email: "demoted@example.org".to_string(),
+            password: "DirectoryPass1!".to_string(),
+        })
+        .await
+        .expect("El segundo login debería ser exitoso");
+
+    let user = users_service.find_by_id(user_id).await.unwrap();
+    assert_eq!(user.permissions, USER_PERMISSIONS.bits());
+}
+
+/// ---
+///
+/// ## Test Case 5: Sin vinculación explícita, una entrada LDAP no toma una cuenta local
+///
+#[tokio::test]
+async fn test_ldap_does_not_link_by_email_by_default() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let (users_service, _server, local_id) = local_and_directory_user("taken").await;
+
+    let result = users_service.login(directory_login("taken")).await;
+
+    let Err((status, _)) = result else {
+        panic!("La entrada LDAP no debería entrar a la cuenta local");
+    };
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let user = users_service.find_by_id(local_id).await.unwrap();
+    assert_eq!(user.permissions, USER_PERMISSIONS.bits());
+}
+
+/// ---
+///
+/// ## Test Case 6: Con vinculación por email se conservan los permisos locales y se rechazan cuentas inactivas
+///
+#[tokio::test]
+async fn test_ldap_link_by_email_keeps_local_permissions() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let (_, server, local_id) = local_and_directory_user("linked").await;
+    let users_service = service_with_config(LdapConfig {
+        link_by_email: true,
+        ..server.config()
+    });
+
+    let token = users_service
+        .login(directory_login("linked"))
+        .await
+        .expect("El login LDAP debería vincular la cuenta local");
+    assert_eq!(decode_jwt(&token).unwrap().user_id, local_id.to_string());
+    let user = users_service.find_by_id(local_id).await.unwrap();
+    assert_eq!(user.permissions, USER_PERMISSIONS.bits());
+
+    let (_, server, inactive_id) = local_and_directory_user("dormant").await;
+    users_service.inactive(inactive_id).await.unwrap();
+    let users_service = service_with_config(LdapConfig {
+        link_by_email: true,
+        ..server.config()
+    });
+    let Err((status, _)) = users_service.login(directory_login("dormant")).await else {
+        panic!("Una cuenta inactiva no debería vincularse");
+    };
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,90 @@
+//! Pruebas contra un OpenLDAP real. Solo se ejecutan si `LDAP_TEST_URL` está
+//! definida; el directorio debe cargarse con `tests/fixtures/ldap/seed.ldif`:
+//!
+//! ```sh
+//! docker run -d --name r-auth-ldap -p 1389:1389 \
+//!     -e LDAP_ROOT=dc=example,dc=org \
+//!     -e LDAP_ADMIN_USERNAME=admin -e LDAP_ADMIN_PASSWORD=admin-secret \
+//!     -e LDAP_CUSTOM_LDIF_DIR=/ldifs \
+//!     -v "$PWD/tests/fixtures/ldap:/ldifs" bitnami/openldap:2.6
+//! LDAP_TEST_URL=ldap://localhost:1389 cargo tests
+//! ```
+
+use std::sync::Arc;
+
+use r_auth_api::{
+    auth::{
+        backends::{AuthBackend, LdapBackend},
+        decode_jwt,
+    },
+    config::LdapConfig,
+    database::models::dto::LoginRequest,
+    services::UsersService,
+    utils::{Permissions, USER_PERMISSIONS},
+};
+
+use crate::common;
+
+fn openldap_config() -> Option<LdapConfig> {
+    let url = std::env::var("LDAP_TEST_URL").ok()?;
+    Some(LdapConfig {
+        url,
+        bind_dn: "cn=admin,dc=example,dc=org".to_string(),
+        bind_password: "admin-secret".to_string(),
+        search_base: "ou=people,dc=example,dc=org".to_string(),
+        user_filter: "(&(objectClass=inetOrgPerson)(mail={email}))".to_string(),
+        username_attribute: "uid".to_string(),
+        email_attribute: "mail".to_string(),
+        group_attribute: "memberOf".to_string(),
+        group_search_base: Some("ou=groups,dc=example,dc=org".to_string()),
+        group_filter: "(&(objectClass=groupOfNames)(member={dn}))".to_string(),
+        group_permissions: vec![(
+            "cn=admins,ou=groups,dc=example,dc=org".to_string(),
+            Permissions::ADMIN,
+        )],
+        link_by_email: false,
+    })
+}
+
+/// ---
+///
+/// ## Test Case 1: Login contra OpenLDAP con grupos resueltos por búsqueda
+///
+#[tokio::test]
+async fn test_openldap_login() {
+    let config = match openldap_config() {
+        Some(c) => c,
+        None => {
+            println!("LDAP_TEST_URL no definida, se omite la prueba contra OpenLDAP");
+            return;
+        }
+    };
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let backends: Vec<Arc<dyn AuthBackend>> = vec![Arc::new(LdapBackend::new(pool, config))];
+    let users_service = UsersService::with_backends(pool, backends);
+
+    let token = users_service
+        .login(LoginRequest {
+            email: "alice@example.org".to_string(),
+            password: "AlicePass1!".to_string(),
+        })
+        .await
+        .expect("El login contra OpenLDAP debería ser exitoso");
+
+    let user_id: i64 = decode_jwt(&token).unwrap().user_id.parse().unwrap();
+    let user = users_service.find_by_id(user_id).await.unwrap();
+    assert_eq!(user.username, "alice");
+    assert_eq!(
+        user.permissions,
+        (USER_PERMISSIONS | Permissions::ADMIN).bits()
+    );
+
+    let result = users_service
+        .login(LoginRequest {
+            email: "alice@example.org".to_string(),
+            password: "WrongPass1!".to_string(),
+        })
+        .await;
+    assert!(result.is_err(), "Una contraseña incorrecta debería fallar");
+}
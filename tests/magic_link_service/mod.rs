@@ -0,0 +1,2 @@
+pub mod redeem;
+pub mod request;
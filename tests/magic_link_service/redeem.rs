@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use axum::{Json, http::StatusCode};
+use r_auth_api::{
+    auth::decode_jwt,
+    database::models::dto::{CreateUserDto, MagicLinkRequest, RedeemMagicLinkRequest},
+    services::{MagicLinkService, UsersService},
+};
+
+use crate::common::{self, MemoryMailer};
+
+async fn request_token(
+    users_service: &UsersService,
+    service: &MagicLinkService,
+    mailer: &MemoryMailer,
+    username: &str,
+) -> (i64, String) {
+    let email = format!("{}@example.com", username);
+    let user = users_service
+        .create(CreateUserDto {
+            username: username.to_string(),
+            email: email.clone(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    service
+        .request(MagicLinkRequest { email })
+        .await
+        .expect("Fallo al solicitar el enlace");
+
+    let sent = mailer.sent();
+    (user.id, common::extract_token(&sent.last().unwrap().body))
+}
+
+/// ---
+///
+/// ## Test Case 1: Canjear un enlace válido devuelve un JWT del usuario
+///
+#[tokio::test]
+async fn test_redeem_magic_link_success() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let (user_id, token) = request_token(&users_service, &service, &mailer, "redeem_ok").await;
+
+    let result = service.redeem(RedeemMagicLinkRequest { token }).await;
+
+    assert!(
+        result.is_ok(),
+        "El canje debería ser exitoso. Error: {:?}",
+        result.unwrap_err()
+    );
+    let claims = decode_jwt(&result.unwrap()).expect("El JWT debería ser válido");
+    assert_eq!(claims.user_id, user_id.to_string());
+}
+
+/// ---
+///
+/// ## Test Case 2: Un enlace solo puede usarse una vez
+///
+#[tokio::test]
+async fn test_redeem_magic_link_single_use() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let (_, token) = request_token(&users_service, &service, &mailer, "redeem_twice").await;
+
+    service
+        .redeem(RedeemMagicLinkRequest {
+            token: token.clone(),
+        })
+        .await
+        .expect("El primer canje debería ser exitoso");
+
+    let result = service.redeem(RedeemMagicLinkRequest { token }).await;
+
+    assert!(result.is_err(), "El segundo canje debería fallar");
+    let (status, Json(http_error)) = result.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        http_error.errors.get("client").unwrap().first().unwrap(),
+        "Enlace inválido o expirado"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: Un enlace expirado es rechazado
+///
+#[tokio::test]
+async fn test_redeem_magic_link_expired() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let (user_id, token) = request_token(&users_service, &service, &mailer, "redeem_exp").await;
+
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE magic_links SET expires_at = now() - interval '1 minute' WHERE user_id = $1",
+            &[&user_id],
+        )
+        .await
+        .unwrap();
+
+    let result = service.redeem(RedeemMagicLinkRequest { token }).await;
+
+    assert!(
+        result.is_err(),
+        "El canje de un enlace expirado debería fallar"
+    );
+    let (status, _) = result.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+/// ---
+///
+/// ## Test Case 4: Un usuario inactivo no puede canjear su enlace
+///
+#[tokio::test]
+async fn test_redeem_magic_link_inactive_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let (user_id, token) =
+        request_token(&users_service, &service, &mailer, "redeem_inactive").await;
+    users_service.inactive(user_id).await.unwrap();
+
+    let result = service.redeem(RedeemMagicLinkRequest { token }).await;
+
+    assert!(
+        result.is_err(),
+        "Un usuario inactivo no debería poder acceder"
+    );
+    let (status, _) = result.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+/// ---
+///
+/// ## Test Case 5: Con el cambio de contraseña pendiente el enlace da un token limitado
+///
+#[tokio::test]
+async fn test_redeem_magic_link_must_change_password() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let (user_id, token) =
+        request_token(&users_service, &service, &mailer, "redeem_must_change").await;
+    users_service
+        .set_must_change_password(user_id, true)
+        .await
+        .unwrap();
+
+    let jwt = service
+        .redeem(RedeemMagicLinkRequest { token })
+        .await
+        .expect("El canje debería ser exitoso");
+    let claims = decode_jwt(&jwt).expect("El JWT debería ser válido");
+    assert_eq!(claims.user_id, user_id.to_string());
+    assert!(claims.is_password_change_only());
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use axum::{Json, http::StatusCode};
+use r_auth_api::{
+    database::models::dto::{CreateUserDto, MagicLinkRequest},
+    mailer::FileMailer,
+    services::{MagicLinkService, UsersService},
+};
+
+use crate::common::{self, MemoryMailer};
+
+/// ---
+///
+/// ## Test Case 1: Solicitar enlace para un usuario existente envía un correo
+///
+#[tokio::test]
+async fn test_request_magic_link_sends_email() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    users_service
+        .create(CreateUserDto {
+            username: "magic_user".to_string(),
+            email: "magic_user@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    let result = service
+        .request(MagicLinkRequest {
+            email: "magic_user@example.com".to_string(),
+        })
+        .await;
+
+    assert!(
+        result.is_ok(),
+        "La solicitud debería ser exitosa. Error: {:?}",
+        result.unwrap_err()
+    );
+
+    let sent = mailer.sent();
+    assert_eq!(sent.len(), 1, "Debería haberse enviado un único correo");
+    assert_eq!(sent[0].to, "magic_user@example.com");
+    assert!(!common::extract_token(&sent[0].body).is_empty());
+}
+
+/// ---
+///
+/// ## Test Case 2: Un email inexistente responde igual pero no envía correo
+///
+#[tokio::test]
+async fn test_request_magic_link_unknown_email() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let result = service
+        .request(MagicLinkRequest {
+            email: "nobody@example.com".to_string(),
+        })
+        .await;
+
+    assert!(result.is_ok(), "No se debería revelar si el email existe");
+    assert!(
+        mailer.sent().is_empty(),
+        "No debería enviarse ningún correo"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: Superar el límite de solicitudes por email devuelve 429
+///
+#[tokio::test]
+async fn test_request_magic_link_rate_limited() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    let mut last = Ok(());
+    for _ in 0..10 {
+        last = service
+            .request(MagicLinkRequest {
+                email: "Spam@Example.com".to_string(),
+            })
+            .await;
+        if last.is_err() {
+            break;
+        }
+    }
+
+    assert!(last.is_err(), "Debería rechazar solicitudes excesivas");
+    let (status, Json(http_error)) = last.unwrap_err();
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        http_error.errors.get("client").unwrap().first().unwrap(),
+        "Demasiadas solicitudes para este email, intenta más tarde"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 4: El FileMailer escribe el correo en el archivo configurado
+///
+#[tokio::test]
+async fn test_request_magic_link_file_mailer() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+
+    let path = std::env::temp_dir().join("r_auth_magic_link_test.jsonl");
+    let _ = std::fs::remove_file(&path);
+    let service = MagicLinkService::new(pool, Arc::new(FileMailer::new(&path)));
+
+    users_service
+        .create(CreateUserDto {
+            username: "magic_file".to_string(),
+            email: "magic_file@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    service
+        .request(MagicLinkRequest {
+            email: "magic_file@example.com".to_string(),
+        })
+        .await
+        .expect("La solicitud debería ser exitosa");
+
+    let content = std::fs::read_to_string(&path).expect("El archivo de correos debería existir");
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.contains("magic_file@example.com"));
+    assert!(content.contains("token="));
+}
+
+/// ---
+///
+/// ## Test Case 5: El email se normaliza antes de buscar al usuario
+///
+#[tokio::test]
+async fn test_request_magic_link_normalizes_email() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let service = MagicLinkService::new(pool, mailer.clone());
+
+    UsersService::new(pool)
+        .create(CreateUserDto {
+            username: "magic_case".to_string(),
+            email: "magic_case@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    service
+        .request(MagicLinkRequest {
+            email: "Magic_Case@Example.COM".to_string(),
+        })
+        .await
+        .expect("La solicitud debería ser exitosa");
+
+    let sent = mailer.sent();
+    assert_eq!(sent.len(), 1, "Debería encontrarse al usuario");
+    assert_eq!(sent[0].to, "magic_case@example.com");
+}
@@ -1,2 +1,16 @@
+pub mod attribute_service;
+pub mod breached_passwords;
 pub mod common;
+pub mod forward_auth;
+pub mod hashing_pool;
+pub mod invitation_service;
+pub mod ldap_backend;
+pub mod magic_link_service;
+pub mod oidc_service;
+pub mod password_policy;
+pub mod privacy_service;
+pub mod registration_service;
+pub mod scim_service;
+pub mod sessions;
+pub mod user_transfer_service;
 pub mod users_service;
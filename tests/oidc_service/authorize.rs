@@ -0,0 +1,57 @@
+use axum::{Json, http::StatusCode};
+use r_auth_api::services::OidcService;
+
+use crate::common::{self, oidc_mock::MockOidcProvider};
+
+/// ---
+///
+/// ## Test Case 1: La URL de autorización incluye state, nonce y PKCE
+///
+#[tokio::test]
+async fn test_authorize_url_contains_oidc_params() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+
+    let url = service
+        .authorize_url("mock")
+        .await
+        .expect("Debería generarse la URL de autorización");
+
+    assert!(url.starts_with(&format!("{}/authorize?", mock.issuer)));
+    let params = super::start_login(&service, "mock").await;
+    assert_eq!(params.get("response_type").unwrap(), "code");
+    assert_eq!(
+        params.get("client_id").unwrap(),
+        common::oidc_mock::CLIENT_ID
+    );
+    assert_eq!(params.get("code_challenge_method").unwrap(), "S256");
+    assert!(params.contains_key("state"));
+    assert!(params.contains_key("nonce"));
+    assert!(params.contains_key("code_challenge"));
+}
+
+/// ---
+///
+/// ## Test Case 2: Un proveedor no configurado devuelve 404
+///
+#[tokio::test]
+async fn test_authorize_url_unknown_provider() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = OidcService::new(pool, vec![]);
+
+    let result = service.authorize_url("unknown").await;
+
+    assert!(
+        result.is_err(),
+        "Debería fallar con un proveedor desconocido"
+    );
+    let (status, Json(http_error)) = result.unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        http_error.errors.get("client").unwrap().first().unwrap(),
+        "Proveedor de identidad no configurado"
+    );
+}
@@ -0,0 +1,241 @@
+use axum::{Json, http::StatusCode};
+use jsonwebtoken::Algorithm;
+use r_auth_api::{
+    auth::decode_jwt,
+    database::models::dto::{CreateUserDto, OidcCallbackQuery},
+    services::{OidcService, UsersService},
+    utils::USER_PERMISSIONS,
+};
+
+use crate::common::{
+    self,
+    oidc_mock::{MockLogin, MockOidcProvider},
+};
+
+async fn login(
+    service: &OidcService,
+    mock: &MockOidcProvider,
+    subject: &str,
+    email: &str,
+    email_verified: bool,
+) -> Result<String, (StatusCode, Json<r_auth_api::utils::errors::HttpError>)> {
+    let params = super::start_login(service, "mock").await;
+    let code = mock.issue_code(MockLogin {
+        subject: subject.to_string(),
+        email: email.to_string(),
+        email_verified,
+        nonce: params.get("nonce").unwrap().clone(),
+        code_challenge: params.get("code_challenge").unwrap().clone(),
+    });
+
+    service
+        .callback(
+            "mock",
+            OidcCallbackQuery {
+                code: Some(code),
+                state: params.get("state").cloned(),
+                error: None,
+            },
+        )
+        .await
+}
+
+/// ---
+///
+/// ## Test Case 1: Primer login aprovisiona un usuario con permisos por defecto
+///
+#[tokio::test]
+async fn test_callback_provisions_new_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+    let users_service = UsersService::new(pool);
+
+    let result = login(&service, &mock, "sub-new", "new.person@example.com", true).await;
+
+    assert!(
+        result.is_ok(),
+        "El login federado debería ser exitoso. Error: {:?}",
+        result.unwrap_err()
+    );
+    let claims = decode_jwt(&result.unwrap()).unwrap();
+    let user = users_service
+        .find_by_id(claims.user_id.parse().unwrap())
+        .await
+        .expect("El usuario debería haberse creado");
+    assert_eq!(user.email, "new.person@example.com");
+    assert_eq!(user.username, "new_person");
+    assert_eq!(user.permissions, USER_PERMISSIONS.bits());
+}
+
+/// ---
+///
+/// ## Test Case 2: Un usuario existente se vincula por email verificado
+///
+#[tokio::test]
+async fn test_callback_links_existing_user_by_email() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+    let users_service = UsersService::new(pool);
+
+    let existing = users_service
+        .create(CreateUserDto {
+            username: "oidc_existing".to_string(),
+            email: "oidc_existing@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    let token = login(
+        &service,
+        &mock,
+        "sub-existing",
+        "oidc_existing@example.com",
+        true,
+    )
+    .await
+    .expect("El login federado debería ser exitoso");
+    assert_eq!(decode_jwt(&token).unwrap().user_id, existing.id.to_string());
+
+    // Un segundo login se resuelve por la identidad aunque el email cambie.
+    let token = login(
+        &service,
+        &mock,
+        "sub-existing",
+        "changed@example.com",
+        false,
+    )
+    .await
+    .expect("El segundo login debería resolverse por la identidad");
+    assert_eq!(decode_jwt(&token).unwrap().user_id, existing.id.to_string());
+}
+
+/// ---
+///
+/// ## Test Case 3: Un email no verificado no se vincula ni aprovisiona
+///
+#[tokio::test]
+async fn test_callback_unverified_email() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+
+    let result = login(
+        &service,
+        &mock,
+        "sub-unverified",
+        "unverified@example.com",
+        false,
+    )
+    .await;
+
+    assert!(result.is_err(), "Debería rechazar emails no verificados");
+    let (status, Json(http_error)) = result.unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(
+        http_error.errors.get("client").unwrap().first().unwrap(),
+        "El proveedor de identidad no verificó el email"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 4: Un state desconocido es rechazado
+///
+#[tokio::test]
+async fn test_callback_invalid_state() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+
+    let result = service
+        .callback(
+            "mock",
+            OidcCallbackQuery {
+                code: Some("whatever".to_string()),
+                state: Some("forged-state".to_string()),
+                error: None,
+            },
+        )
+        .await;
+
+    assert!(result.is_err(), "Debería rechazar un state desconocido");
+    let (status, Json(http_error)) = result.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        http_error.errors.get("client").unwrap().first().unwrap(),
+        "Estado OIDC inválido o expirado"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 5: Un id_token con otro nonce es rechazado
+///
+#[tokio::test]
+async fn test_callback_nonce_mismatch() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+
+    let params = super::start_login(&service, "mock").await;
+    let code = mock.issue_code(MockLogin {
+        subject: "sub-nonce".to_string(),
+        email: "nonce@example.com".to_string(),
+        email_verified: true,
+        nonce: "another-nonce".to_string(),
+        code_challenge: params.get("code_challenge").unwrap().clone(),
+    });
+
+    let result = service
+        .callback(
+            "mock",
+            OidcCallbackQuery {
+                code: Some(code),
+                state: params.get("state").cloned(),
+                error: None,
+            },
+        )
+        .await;
+
+    assert!(result.is_err(), "Debería rechazar un nonce distinto");
+    let (status, _) = result.unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+/// ---
+///
+/// ## Test Case 6: Un id_token HS256 firmado con el client_secret solo se acepta si está configurado
+///
+#[tokio::test]
+async fn test_callback_rejects_unconfigured_algorithm() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let mock = MockOidcProvider::start().await;
+    mock.sign_with(Algorithm::HS256);
+
+    let service = OidcService::new(pool, vec![mock.provider_config("mock")]);
+    let result = login(&service, &mock, "sub-hs256", "hs256@example.com", true).await;
+    let Err((status, _)) = result else {
+        panic!("Debería rechazar un algoritmo que el discovery no anuncia");
+    };
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let mut config = mock.provider_config("mock");
+    config.algorithms = vec![Algorithm::HS256];
+    let service = OidcService::new(pool, vec![config]);
+    let result = login(&service, &mock, "sub-hs256", "hs256@example.com", true).await;
+    assert!(
+        result.is_ok(),
+        "HS256 configurado explícitamente debería aceptarse. Error: {:?}",
+        result.unwrap_err()
+    );
+}
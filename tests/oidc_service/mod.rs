@@ -0,0 +1,20 @@
+pub mod authorize;
+pub mod callback;
+
+use std::collections::HashMap;
+
+use r_auth_api::services::OidcService;
+use url::Url;
+
+/// Inicia el login y devuelve los parámetros de la URL de autorización.
+pub async fn start_login(service: &OidcService, provider: &str) -> HashMap<String, String> {
+    let url = service
+        .authorize_url(provider)
+        .await
+        .expect("TEST ERROR: no se pudo generar la URL de autorización");
+    Url::parse(&url)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect()
+}
@@ -0,0 +1,157 @@
+use axum::Json;
+use r_auth_api::{
+    auth::{password_strength, policy_violations},
+    config::{CharacterClass, PasswordPolicyConfig},
+    database::models::dto::PasswordPolicyResponse,
+    handlers::password_policy_handler::password_policy,
+};
+
+fn strict_policy() -> PasswordPolicyConfig {
+    PasswordPolicyConfig {
+        min_length: 10,
+        max_length: 20,
+        required_classes: vec![
+            CharacterClass::Lowercase,
+            CharacterClass::Uppercase,
+            CharacterClass::Digit,
+            CharacterClass::Symbol,
+        ],
+        forbid_user_info: true,
+        max_repeated_chars: 2,
+        min_strength: 3,
+        breached_passwords_path: None,
+        history_size: 5,
+        max_age_days: 90,
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: Una contraseña fuerte no incumple ninguna regla
+///
+#[test]
+fn test_strong_password_passes() {
+    let violations = policy_violations(
+        "Tq8#vLm2!pRz",
+        &["maria", "maria@example.com"],
+        &strict_policy(),
+    );
+    assert!(violations.is_empty(), "{:?}", violations);
+}
+
+/// ---
+///
+/// ## Test Case 2: Se listan todas las reglas incumplidas
+///
+#[test]
+fn test_lists_every_violation() {
+    let violations = policy_violations("aaamaria", &["maria"], &strict_policy());
+    assert_eq!(
+        violations,
+        vec![
+            "La contraseña debe tener al menos 10 caracteres",
+            "La contraseña debe tener al menos una mayúscula",
+            "La contraseña debe tener al menos un número",
+            "La contraseña debe tener al menos un caracter especial",
+            "La contraseña no puede contener el nombre de usuario ni el email",
+            "La contraseña no puede repetir el mismo caracter más de 2 veces seguidas",
+            "La contraseña es demasiado fácil de adivinar",
+        ]
+    );
+
+    let too_long = policy_violations("Tq8#vLm2!pRzTq8#vLm2!x", &[], &strict_policy());
+    assert_eq!(
+        too_long,
+        vec!["La contraseña debe tener como máximo 20 caracteres"]
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: La parte local del email también está prohibida
+///
+#[test]
+fn test_email_local_part_is_forbidden() {
+    let violations = policy_violations(
+        "Xjuan.perez9!",
+        &["jp", "juan.perez@example.com"],
+        &strict_policy(),
+    );
+    assert!(
+        violations.contains(
+            &"La contraseña no puede contener el nombre de usuario ni el email".to_string()
+        )
+    );
+}
+
+/// ---
+///
+/// ## Test Case 4: La fortaleza penaliza palabras comunes, l33t y secuencias
+///
+#[test]
+fn test_strength_score() {
+    assert_eq!(password_strength("password", &[]), 0);
+    assert_eq!(password_strength("P@ssw0rd", &[]), 0);
+    assert_eq!(password_strength("abcdefgh", &[]), 0);
+    assert!(password_strength("P@ssw0rd123!", &[]) < 3);
+    assert!(password_strength("Tq8#vLm2!pRz", &[]) >= 3);
+    assert!(password_strength("maria2024", &["maria"]) < password_strength("kzqwv2024", &[]));
+}
+
+/// ---
+///
+/// ## Test Case 5: La política se expone con los valores configurados
+///
+#[test]
+fn test_policy_response() {
+    let response = PasswordPolicyResponse::from(&strict_policy());
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["minLength"], 10);
+    assert_eq!(json["maxLength"], 20);
+    assert_eq!(
+        json["requiredClasses"],
+        serde_json::json!(["lower", "upper", "digit", "symbol"])
+    );
+    assert_eq!(json["maxRepeatedChars"], 2);
+    assert_eq!(json["minStrength"], 3);
+    assert_eq!(json["historySize"], 5);
+    assert_eq!(json["maxAgeDays"], 90);
+
+    let unlimited = PasswordPolicyResponse::from(&PasswordPolicyConfig {
+        max_repeated_chars: 0,
+        ..strict_policy()
+    });
+    assert_eq!(unlimited.max_repeated_chars, None);
+}
+
+/// ---
+///
+/// ## Test Case 6: El endpoint devuelve la política por defecto
+///
+#[tokio::test]
+async fn test_password_policy_endpoint() {
+    let Json(policy) = password_policy().await;
+    assert_eq!(policy.min_length, 8);
+    assert_eq!(policy.max_length, 64);
+    assert_eq!(policy.required_classes.len(), 4);
+}
+
+/// ---
+///
+/// ## Test Case 7: Las contraseñas enormes se rechazan sin demorar la validación
+///
+#[test]
+fn test_long_password_is_checked_quickly() {
+    let huge = "Tq8#vLm2!pRz".repeat(1000);
+    let started = std::time::Instant::now();
+    let violations = policy_violations(&huge, &["maria"], &strict_policy());
+    assert_eq!(
+        violations,
+        vec!["La contraseña debe tener como máximo 20 caracteres"]
+    );
+
+    // Aun sin límite de largo, el puntaje no recorre fragmentos más largos
+    // que la palabra más larga del diccionario.
+    assert!(password_strength(&huge, &["maria"]) >= 3);
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+}
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::{
+        dto::{AcceptInvitationDto, ChangePasswordDto, CreateInvitationDto, CreateUserDto},
+        entities::user_status::UserStatus,
+    },
+    services::{InvitationService, PrivacyService, UsersService},
+    utils::Permissions,
+};
+
+use crate::common::{self, MemoryMailer};
+
+async fn create_user(service: &UsersService, name: &str) -> i64 {
+    service
+        .create(CreateUserDto {
+            username: name.to_string(),
+            email: format!("{}@example.com", name),
+            password: "First@Pass1".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando usuario")
+        .id
+}
+
+async fn audit_events(id: i64) -> Vec<String> {
+    let client = common::get_test_pool().get().await.unwrap();
+    client
+        .query(
+            "SELECT event FROM audit_events WHERE user_id = $1 ORDER BY id",
+            &[&id],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get("event"))
+        .collect()
+}
+
+/// ---
+///
+/// ## Test Case 1: La baja queda pendiente durante el período de gracia y se puede cancelar
+///
+#[tokio::test]
+async fn test_erasure_can_be_cancelled() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let service = PrivacyService::with_grace_days(pool, 14);
+    let id = create_user(&users_service, "erasure_cancel").await;
+
+    let response = service.request_erasure(id).await.unwrap();
+    assert!(response.scheduled_at > chrono::Utc::now() + chrono::Duration::days(13));
+    let user = users_service.find_by_id(id).await.unwrap();
+    assert_eq!(user.erasure_scheduled_at, Some(response.scheduled_at));
+
+    let (status, _) = service.request_erasure(id).await.unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    assert_eq!(service.erase_due().await.unwrap(), 0);
+
+    service.cancel_erasure(id).await.unwrap();
+    let user = users_service.find_by_id(id).await.unwrap();
+    assert!(user.erasure_scheduled_at.is_none());
+    assert_eq!(user.username, "erasure_cancel");
+
+    let (status, _) = service.cancel_erasure(id).await.unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// ---
+///
+/// ## Test Case 2: Al vencer el plazo se anonimiza y solo queda la auditoría de la baja
+///
+#[tokio::test]
+async fn test_due_erasure_anonymizes_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let service = PrivacyService::with_grace_days(pool, 14);
+    let id = create_user(&users_service, "erasure_due").await;
+    users_service
+        .change_password(
+            id.to_string(),
+            ChangePasswordDto {
+                previous_password: "First@Pass1".to_string(),
+                new_password: "Second@Pass2".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+    service.request_erasure(id).await.unwrap();
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE users SET erasure_scheduled_at = now() - interval '1 minute' WHERE id = $1",
+            &[&id],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(service.erase_due().await.unwrap(), 1);
+
+    let row = client
+        .query_one(
+            "SELECT username, email, password, status FROM users WHERE id = $1",
+            &[&id],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>("username"), format!("erased_{}", id));
+    assert!(!row.get::<_, String>("email").contains("erasure_due"));
+    assert!(row.get::<_, Option<String>>("password").is_none());
+    assert_eq!(row.get::<_, UserStatus>("status"), UserStatus::Deleted);
+
+    let history: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM password_history WHERE user_id = $1",
+            &[&id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(history, 0);
+    assert_eq!(audit_events(id).await, vec!["erasure_requested", "erased"]);
+}
+
+/// ---
+///
+/// ## Test Case 3: Sin período de gracia la baja es inmediata y la auditoría sobrevive al purgado
+///
+#[tokio::test]
+async fn test_immediate_erasure_and_purge() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let service = PrivacyService::with_grace_days(pool, 0);
+    let id = create_user(&users_service, "erasure_now").await;
+
+    service.request_erasure(id).await.unwrap();
+    let user = users_service.find_by_id(id).await.unwrap();
+    assert_eq!(user.status, UserStatus::Deleted);
+    assert!(user.erasure_scheduled_at.is_none());
+
+    assert_eq!(users_service.purge_deleted(0).await.unwrap(), 1);
+    let client = pool.get().await.unwrap();
+    let orphaned: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM audit_events WHERE user_id IS NULL AND event = 'erased'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(orphaned, 1);
+}
+
+/// ---
+///
+/// ## Test Case 4: La anonimización no deja datos personales en las tablas relacionadas
+///
+#[tokio::test]
+async fn test_erasure_clears_related_tables() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let inviter = crate::invitation_service::inviter().await;
+    let mailer = Arc::new(MemoryMailer::default());
+    let invitations = InvitationService::new(pool, mailer.clone());
+    let email = "erasure_related@example.com";
+    let invite = || CreateInvitationDto {
+        email: email.to_string(),
+        permissions: None,
+        groups: vec![],
+    };
+
+    // Una invitación revocada y la aceptada quedan con el mismo email.
+    let revoked = invitations
+        .create(inviter, Permissions::ADMIN, invite())
+        .await
+        .unwrap();
+    invitations.revoke(revoked.id).await.unwrap();
+    invitations
+        .create(inviter, Permissions::ADMIN, invite())
+        .await
+        .unwrap();
+    let token = common::extract_token(&mailer.sent().last().unwrap().body);
+    let id = invitations
+        .accept(AcceptInvitationDto {
+            token,
+            username: "erasure_related".to_string(),
+            password: "Welcome@Pass1".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error aceptando la invitación")
+        .id;
+
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            r#"
+                INSERT INTO user_identities (user_id, provider, subject, email)
+                VALUES ($1, 'google', 'erasure-related-sub', $2)
+            "#,
+            &[&id, &email],
+        )
+        .await
+        .unwrap();
+    client
+        .execute(
+            r#"
+                INSERT INTO magic_links (user_id, token_hash, expires_at)
+                VALUES ($1, 'erasure-related-hash', now() + interval '1 hour')
+            "#,
+            &[&id],
+        )
+        .await
+        .unwrap();
+
+    PrivacyService::with_grace_days(pool, 0)
+        .request_erasure(id)
+        .await
+        .unwrap();
+
+    for table in [
+        "invitations",
+        "user_identities",
+        "magic_links",
+        "password_history",
+        "group_members",
+    ] {
+        let remaining: i64 = client
+            .query_one(
+                &format!("SELECT COUNT(*) FROM {} WHERE user_id = $1", table),
+                &[&id],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        assert_eq!(remaining, 0, "Quedaron filas del usuario en {}", table);
+    }
+    for table in ["users", "invitations", "user_identities"] {
+        let remaining: i64 = client
+            .query_one(
+                &format!("SELECT COUNT(*) FROM {} WHERE lower(email) = $1", table),
+                &[&email],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        assert_eq!(remaining, 0, "Quedó el email del usuario en {}", table);
+    }
+    let details: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM audit_events WHERE user_id = $1 AND details LIKE '%erasure_related%'",
+            &[&id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(details, 0);
+}
@@ -0,0 +1,66 @@
+use r_auth_api::{
+    database::models::dto::{CreateUserDto, LoginRequest},
+    services::{PrivacyService, UsersService},
+};
+
+use crate::common;
+
+/// ---
+///
+/// ## Test Case 1: La exportación incluye el usuario, sus grupos y su auditoría
+///
+#[tokio::test]
+async fn test_export_contains_user_data() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    let user = users_service
+        .create(CreateUserDto {
+            username: "export_user".to_string(),
+            email: "export_user@example.com".to_string(),
+            password: "Password@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando usuario");
+    users_service
+        .login(LoginRequest {
+            email: "export_user@example.com".to_string(),
+            password: "Password@123".to_string(),
+        })
+        .await
+        .expect("Error en el login");
+
+    let client = pool.get().await.unwrap();
+    let group_id: i64 = client
+        .query_one(
+            "INSERT INTO groups (display_name) VALUES ('Exportadores') RETURNING id",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    client
+        .execute(
+            "INSERT INTO group_members (group_id, user_id) VALUES ($1, $2)",
+            &[&group_id, &user.id],
+        )
+        .await
+        .unwrap();
+
+    let export = PrivacyService::new(pool).export(user.id).await.unwrap();
+
+    assert_eq!(export.user.email, "export_user@example.com");
+    assert_eq!(export.groups, vec!["Exportadores".to_string()]);
+    assert!(export.identities.is_empty());
+    assert!(
+        export
+            .audit_events
+            .iter()
+            .any(|e| e.event == "login" && e.details.as_deref() == Some("password"))
+    );
+
+    let json = serde_json::to_value(&export).unwrap();
+    assert!(json["user"].get("password").is_none());
+    assert!(json.get("auditEvents").is_some());
+}
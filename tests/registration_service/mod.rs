@@ -0,0 +1,51 @@
+pub mod register;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use r_auth_api::{
+    captcha::{CaptchaError, CaptchaVerifier},
+    config::{SignupConfig, SignupMode},
+    services::RegistrationService,
+};
+
+use crate::common;
+
+/// Acepta solo el token configurado y guarda los tokens recibidos.
+pub struct StubCaptcha {
+    valid_token: &'static str,
+    received: Mutex<Vec<String>>,
+}
+
+impl StubCaptcha {
+    pub fn new(valid_token: &'static str) -> Arc<Self> {
+        Arc::new(StubCaptcha {
+            valid_token,
+            received: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn received(&self) -> Vec<String> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl CaptchaVerifier for StubCaptcha {
+    async fn verify(&self, token: &str) -> Result<bool, CaptchaError> {
+        self.received.lock().unwrap().push(token.to_string());
+        Ok(token == self.valid_token)
+    }
+}
+
+pub fn service(mode: SignupMode, captcha: Arc<StubCaptcha>) -> RegistrationService {
+    RegistrationService::new(
+        common::get_test_pool(),
+        SignupConfig {
+            mode,
+            username_blocklist: vec!["admin".to_string(), "root".to_string()],
+            captcha: None,
+        },
+        captcha,
+    )
+}
@@ -0,0 +1,226 @@
+use axum::{Json, http::StatusCode};
+use r_auth_api::{
+    config::SignupMode,
+    database::models::{
+        dto::{AttributeDefinitionDto, RegisterDto},
+        entities::attribute_definition::AttributeType,
+    },
+    services::AttributeService,
+    utils::USER_PERMISSIONS,
+};
+use serde_json::{Value, json};
+
+use super::{StubCaptcha, service};
+use crate::common;
+
+fn register_dto(username: &str, email: &str, captcha_token: Option<&str>) -> RegisterDto {
+    RegisterDto {
+        username: username.to_string(),
+        email: email.to_string(),
+        password: "Signup@Pass123".to_string(),
+        captcha_token: captcha_token.map(str::to_string),
+        attributes: None,
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: Con registro abierto se crea el usuario con los permisos por defecto
+///
+#[tokio::test]
+async fn test_register_open_creates_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let captcha = StubCaptcha::new("ok");
+    let service = service(SignupMode::Open, captcha.clone());
+
+    let user = service
+        .register(register_dto("new_user", "new@example.com", Some("ok")))
+        .await
+        .expect("Error registrando el usuario");
+
+    assert_eq!(user.username, "new_user");
+    assert_eq!(user.permissions, USER_PERMISSIONS.bits());
+    assert_eq!(captcha.received(), vec!["ok".to_string()]);
+}
+
+/// ---
+///
+/// ## Test Case 2: Con el registro deshabilitado se responde 403 sin verificar el CAPTCHA
+///
+#[tokio::test]
+async fn test_register_disabled() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let captcha = StubCaptcha::new("ok");
+    let service = service(SignupMode::Disabled, captcha.clone());
+
+    let Err((status, _)) = service
+        .register(register_dto("new_user", "new@example.com", Some("ok")))
+        .await
+    else {
+        panic!("El registro debería estar deshabilitado");
+    };
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(captcha.received().is_empty());
+}
+
+/// ---
+///
+/// ## Test Case 3: La lista de dominios admite subdominios y rechaza el resto
+///
+#[tokio::test]
+async fn test_register_domain_allowlist() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = service(
+        SignupMode::Domains(vec!["example.com".to_string()]),
+        StubCaptcha::new("ok"),
+    );
+
+    service
+        .register(register_dto("sub_user", "sub@mail.example.com", Some("ok")))
+        .await
+        .expect("El subdominio debería estar permitido");
+
+    for email in ["other@example.org", "other@notexample.com"] {
+        let Err((status, _)) = service
+            .register(register_dto("other_user", email, Some("ok")))
+            .await
+        else {
+            panic!("El dominio de {} no debería estar permitido", email);
+        };
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
+
+/// ---
+///
+/// ## Test Case 4: Usernames reservados y CAPTCHA inválido se rechazan con 400
+///
+#[tokio::test]
+async fn test_register_blocklist_and_captcha() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = service(SignupMode::Open, StubCaptcha::new("ok"));
+
+    let Err((status, Json(error))) = service
+        .register(register_dto("Admin", "admin@example.com", Some("ok")))
+        .await
+    else {
+        panic!("El username reservado no debería registrarse");
+    };
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.errors.contains_key("client"));
+
+    for token in [None, Some("wrong")] {
+        let Err((status, _)) = service
+            .register(register_dto("new_user", "new@example.com", token))
+            .await
+        else {
+            panic!("El CAPTCHA inválido no debería aceptarse");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let client = pool.get().await.unwrap();
+    let count: i64 = client
+        .query_one("SELECT COUNT(*) FROM users", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+}
+
+/// ---
+///
+/// ## Test Case 5: Username y email se normalizan antes de validar y de aplicar las listas
+///
+#[tokio::test]
+async fn test_register_normalizes_input() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = service(
+        SignupMode::Domains(vec!["example.com".to_string()]),
+        StubCaptcha::new("ok"),
+    );
+
+    let user = service
+        .register(register_dto(
+            " spaced_user ",
+            " Spaced@Mail.EXAMPLE.com ",
+            Some("ok"),
+        ))
+        .await
+        .expect("Los espacios y las mayúsculas no deberían impedir el registro");
+    assert_eq!(user.username, "spaced_user");
+    assert_eq!(user.email, "spaced@mail.example.com");
+
+    // NFKC convierte los caracteres de ancho completo en "Admin".
+    let Err((status, Json(error))) = service
+        .register(register_dto(
+            "Ａｄｍｉｎ",
+            "fullwidth@example.com",
+            Some("ok"),
+        ))
+        .await
+    else {
+        panic!("El username reservado no debería registrarse");
+    };
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        error.errors.get("client").unwrap().first().unwrap(),
+        "Ese nombre de usuario no está disponible"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 6: El registro exige los obligatorios y solo acepta atributos editables
+///
+#[tokio::test]
+async fn test_register_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = service(SignupMode::Open, StubCaptcha::new("ok"));
+    let attributes_service = AttributeService::new(pool);
+    for (name, user_editable) in [("nickname", true), ("tenant", false)] {
+        attributes_service
+            .upsert(
+                name,
+                AttributeDefinitionDto {
+                    kind: AttributeType::String,
+                    required: user_editable,
+                    include_in_token: false,
+                    user_editable,
+                    description: None,
+                },
+            )
+            .await
+            .unwrap();
+    }
+    let dto = |attributes: Value| RegisterDto {
+        attributes: attributes.as_object().cloned(),
+        ..register_dto("attrs_user", "attrs@example.com", Some("ok"))
+    };
+
+    for (attributes, expected) in [
+        (Value::Null, StatusCode::BAD_REQUEST),
+        (
+            json!({"nickname": "yo", "tenant": "acme"}),
+            StatusCode::FORBIDDEN,
+        ),
+    ] {
+        let Err((status, _)) = service.register(dto(attributes)).await else {
+            panic!("El registro debería rechazarse");
+        };
+        assert_eq!(status, expected);
+    }
+
+    let user = service
+        .register(dto(json!({"nickname": "yo"})))
+        .await
+        .expect("Error registrando con atributos");
+    assert_eq!(Value::Object(user.attributes), json!({"nickname": "yo"}));
+}
@@ -0,0 +1,115 @@
+use r_auth_api::scim::filter::{CompareOp, ScimFilter, ScimPath, ScimValue};
+
+/// ---
+///
+/// ## Test Case 1: Operadores lógicos respetan la precedencia and > or
+///
+#[test]
+fn test_parse_filter_precedence() {
+    let filter =
+        ScimFilter::parse(r#"userName eq "a" or userName sw "b" and active eq true"#).unwrap();
+
+    match filter {
+        ScimFilter::Or(left, right) => {
+            assert!(matches!(
+                *left,
+                ScimFilter::Compare {
+                    op: CompareOp::Eq,
+                    ..
+                }
+            ));
+            assert!(matches!(*right, ScimFilter::And(_, _)));
+        }
+        other => panic!("Se esperaba un Or, se obtuvo {:?}", other),
+    }
+}
+
+/// ---
+///
+/// ## Test Case 2: Atributos con prefijo de esquema, not(), pr y filtros de valor
+///
+#[test]
+fn test_parse_filter_attribute_forms() {
+    let filter = ScimFilter::parse(
+        r#"not (urn:ietf:params:scim:schemas:core:2.0:User:userName pr) and emails[type eq "work" and value co "@example.com"]"#,
+    )
+    .unwrap();
+
+    let ScimFilter::And(left, right) = filter else {
+        panic!("Se esperaba un And");
+    };
+    assert_eq!(
+        *left,
+        ScimFilter::Not(Box::new(ScimFilter::Present("username".to_string())))
+    );
+    match *right {
+        ScimFilter::ValuePath { attribute, filter } => {
+            assert_eq!(attribute, "emails");
+            assert!(matches!(*filter, ScimFilter::And(_, _)));
+        }
+        other => panic!("Se esperaba un filtro de valor, se obtuvo {:?}", other),
+    }
+}
+
+/// ---
+///
+/// ## Test Case 3: Cadenas con comillas escapadas y valores literales
+///
+#[test]
+fn test_parse_filter_values() {
+    let filter = ScimFilter::parse(r#"displayName eq "say \"hi\"""#).unwrap();
+    assert_eq!(
+        filter,
+        ScimFilter::Compare {
+            attribute: "displayname".to_string(),
+            op: CompareOp::Eq,
+            value: ScimValue::String("say \"hi\"".to_string()),
+        }
+    );
+
+    let filter = ScimFilter::parse("active EQ False").unwrap();
+    assert!(matches!(
+        filter,
+        ScimFilter::Compare {
+            value: ScimValue::Bool(false),
+            ..
+        }
+    ));
+}
+
+/// ---
+///
+/// ## Test Case 4: Filtros mal formados se rechazan con invalidFilter
+///
+#[test]
+fn test_parse_filter_invalid() {
+    for input in [
+        r#"userName eq"#,
+        r#"userName foo "a""#,
+        r#"(userName eq "a""#,
+        r#"userName eq "a"#,
+        r#"userName eq "a" extra"#,
+    ] {
+        let err = ScimFilter::parse(input).expect_err(input);
+        assert_eq!(err.scim_type, Some("invalidFilter"), "{}", input);
+    }
+}
+
+/// ---
+///
+/// ## Test Case 5: Rutas de PATCH con subatributo y filtro de valor
+///
+#[test]
+fn test_parse_patch_paths() {
+    let path = ScimPath::parse("name.givenName").unwrap();
+    assert_eq!(path.attribute, "name");
+    assert_eq!(path.sub_attribute.as_deref(), Some("givenname"));
+
+    let path = ScimPath::parse(r#"members[value eq "2"]"#).unwrap();
+    assert_eq!(path.attribute, "members");
+    assert!(path.filter.is_some());
+
+    let path = ScimPath::parse(r#"emails[type eq "work"].value"#).unwrap();
+    assert_eq!(path.attribute, "emails");
+    assert_eq!(path.sub_attribute.as_deref(), Some("value"));
+}
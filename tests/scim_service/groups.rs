@@ -0,0 +1,182 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::dto::{ScimGroup, ScimListQuery, ScimMemberRef, ScimPatchRequest},
+    services::ScimService,
+};
+use serde_json::json;
+
+use super::provision_user;
+use crate::common;
+
+fn member(id: &str) -> ScimMemberRef {
+    ScimMemberRef {
+        value: id.to_string(),
+        ..Default::default()
+    }
+}
+
+fn patch(operations: serde_json::Value) -> ScimPatchRequest {
+    serde_json::from_value(json!({ "Operations": operations })).unwrap()
+}
+
+/// ---
+///
+/// ## Test Case 1: Crear un grupo con miembros y verlo desde el usuario
+///
+#[tokio::test]
+async fn test_scim_create_group_with_members() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    let ana = provision_user(&service, "ana").await;
+
+    let group = service
+        .create_group(ScimGroup {
+            display_name: "Engineering".to_string(),
+            members: vec![member(&ana)],
+            ..Default::default()
+        })
+        .await
+        .expect("La creación debería ser exitosa");
+
+    assert_eq!(group.members.len(), 1);
+    assert_eq!(group.members[0].display.as_deref(), Some("ana"));
+
+    let user = service.get_user(&ana).await.unwrap();
+    assert_eq!(user.groups[0].value, group.id.clone().unwrap());
+    assert_eq!(user.groups[0].display.as_deref(), Some("Engineering"));
+
+    let err = service
+        .create_group(ScimGroup {
+            display_name: "Engineering".to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("El nombre repetido debería fallar");
+    assert_eq!(err.status, StatusCode::CONFLICT);
+}
+
+/// ---
+///
+/// ## Test Case 2: PATCH agrega y quita miembros, incluso con filtro de valor
+///
+#[tokio::test]
+async fn test_scim_patch_group_members() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    let a = provision_user(&service, "member_a").await;
+    let b = provision_user(&service, "member_b").await;
+    let c = provision_user(&service, "member_c").await;
+    let group = service
+        .create_group(ScimGroup {
+            display_name: "Ops".to_string(),
+            members: vec![member(&a)],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let id = group.id.unwrap();
+
+    let group = service
+        .patch_group(
+            &id,
+            patch(json!([
+                { "op": "add", "path": "members", "value": [{ "value": b }, { "value": c }] },
+                { "op": "remove", "path": format!("members[value eq \"{}\"]", a) },
+                { "op": "replace", "value": { "displayName": "Operations" } }
+            ])),
+        )
+        .await
+        .expect("El PATCH debería ser exitoso");
+
+    assert_eq!(group.display_name, "Operations");
+    let members: Vec<_> = group.members.iter().map(|m| m.value.clone()).collect();
+    assert_eq!(members, vec![b.clone(), c.clone()]);
+
+    let err = service
+        .patch_group(
+            &id,
+            patch(json!([{ "op": "add", "path": "members", "value": [{ "value": "9999" }] }])),
+        )
+        .await
+        .expect_err("Un miembro inexistente debería fallar");
+    assert_eq!(err.scim_type, Some("invalidValue"));
+}
+
+/// ---
+///
+/// ## Test Case 3: Filtrar grupos por miembro y por nombre
+///
+#[tokio::test]
+async fn test_scim_list_groups_filter() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    let u = provision_user(&service, "grouped").await;
+    for (name, members) in [
+        ("Admins", vec![member(&u)]),
+        ("Sales", vec![]),
+        ("Audit", vec![member(&u)]),
+    ] {
+        service
+            .create_group(ScimGroup {
+                display_name: name.to_string(),
+                members,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    let result = service
+        .list_groups(ScimListQuery {
+            filter: Some(format!(
+                r#"members.value eq "{}" and not (displayName eq "audit")"#,
+                u
+            )),
+            ..Default::default()
+        })
+        .await
+        .expect("El listado debería ser exitoso");
+
+    assert_eq!(result.total_results, 1);
+    assert_eq!(result.resources[0].display_name, "Admins");
+}
+
+/// ---
+///
+/// ## Test Case 4: Eliminar un usuario lo quita de sus grupos
+///
+#[tokio::test]
+async fn test_scim_delete_user_removes_membership() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    let u = provision_user(&service, "leaving").await;
+    let group = service
+        .create_group(ScimGroup {
+            display_name: "Team".to_string(),
+            members: vec![member(&u)],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    service.delete_user(&u).await.unwrap();
+    let group = service
+        .get_group(group.id.as_deref().unwrap())
+        .await
+        .unwrap();
+    assert!(group.members.is_empty());
+
+    service
+        .delete_group(group.id.as_deref().unwrap())
+        .await
+        .unwrap();
+    let err = service
+        .get_group(group.id.as_deref().unwrap())
+        .await
+        .expect_err("El grupo no debería existir");
+    assert_eq!(err.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,25 @@
+pub mod filter;
+pub mod groups;
+pub mod users;
+
+use r_auth_api::{
+    database::models::dto::{ScimEmail, ScimUser},
+    services::ScimService,
+};
+
+/// Aprovisiona un usuario activo `<user_name>@example.com` y devuelve su id.
+pub async fn provision_user(service: &ScimService, user_name: &str) -> String {
+    service
+        .create_user(ScimUser {
+            user_name: user_name.to_string(),
+            emails: vec![ScimEmail {
+                value: format!("{}@example.com", user_name),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+        .await
+        .expect("TEST ERROR: no se pudo aprovisionar el usuario")
+        .id
+        .unwrap()
+}
@@ -0,0 +1,323 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::{
+        dto::{
+            AttributeDefinitionDto, ScimEmail, ScimListQuery, ScimPatchOperation, ScimPatchRequest,
+            ScimUser,
+        },
+        entities::attribute_definition::AttributeType,
+    },
+    services::{AttributeService, ScimService},
+};
+use serde_json::{Value, json};
+
+use super::provision_user;
+use crate::common;
+
+fn patch(operations: serde_json::Value) -> ScimPatchRequest {
+    serde_json::from_value(json!({
+        "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+        "Operations": operations,
+    }))
+    .unwrap()
+}
+
+/// ---
+///
+/// ## Test Case 1: Aprovisionar un usuario y recuperarlo como recurso SCIM
+///
+#[tokio::test]
+async fn test_scim_create_and_get_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+
+    let created = service
+        .create_user(ScimUser {
+            user_name: "jdoe@example.com".to_string(),
+            external_id: Some("ext-1".to_string()),
+            emails: vec![
+                ScimEmail {
+                    value: "other@example.com".to_string(),
+                    ..Default::default()
+                },
+                ScimEmail {
+                    value: "jdoe@example.com".to_string(),
+                    primary: Some(true),
+                    ..Default::default()
+                },
+            ],
+            active: Some(false),
+            ..Default::default()
+        })
+        .await
+        .expect("El aprovisionamiento debería ser exitoso");
+
+    let fetched = service
+        .get_user(created.id.as_deref().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(fetched.user_name, "jdoe@example.com");
+    assert_eq!(fetched.emails[0].value, "jdoe@example.com");
+    assert_eq!(fetched.external_id.as_deref(), Some("ext-1"));
+    assert_eq!(fetched.active, Some(false));
+    assert_eq!(fetched.meta.unwrap().resource_type, "User");
+
+    let client = pool.get().await.unwrap();
+    let status: i32 = client
+        .query_one(
+            "SELECT status FROM users WHERE username = $1",
+            &[&"jdoe@example.com"],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(status, 2, "active=false debería guardarse como status 2");
+}
+
+/// ---
+///
+/// ## Test Case 2: userName o email repetidos devuelven 409 uniqueness
+///
+#[tokio::test]
+async fn test_scim_create_user_conflict() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    provision_user(&service, "dup_user").await;
+
+    let err = service
+        .create_user(ScimUser {
+            user_name: "another".to_string(),
+            emails: vec![ScimEmail {
+                value: "dup_user@example.com".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+        .await
+        .expect_err("Debería fallar por email duplicado");
+
+    assert_eq!(err.status, StatusCode::CONFLICT);
+    assert_eq!(err.scim_type, Some("uniqueness"));
+}
+
+/// ---
+///
+/// ## Test Case 3: Listar usuarios con filtro y paginación
+///
+#[tokio::test]
+async fn test_scim_list_users_filter_and_pagination() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    for name in ["alice", "alfred", "bob", "albert"] {
+        provision_user(&service, name).await;
+    }
+
+    let page = service
+        .list_users(ScimListQuery {
+            filter: Some(r#"userName sw "AL" and emails.value co "example""#.to_string()),
+            start_index: Some(2),
+            count: Some(2),
+        })
+        .await
+        .expect("El listado debería ser exitoso");
+
+    assert_eq!(page.total_results, 3);
+    assert_eq!(page.start_index, 2);
+    assert_eq!(page.items_per_page, 2);
+    let names: Vec<_> = page
+        .resources
+        .iter()
+        .map(|u| u.user_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["alfred", "albert"]);
+
+    let err = service
+        .list_users(ScimListQuery {
+            filter: Some(r#"nickName eq "x""#.to_string()),
+            ..Default::default()
+        })
+        .await
+        .expect_err("Un atributo no soportado debería fallar");
+    assert_eq!(err.scim_type, Some("invalidFilter"));
+}
+
+/// ---
+///
+/// ## Test Case 4: PATCH de active acepta booleanos como texto y mapea a status
+///
+#[tokio::test]
+async fn test_scim_patch_user_active() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    let id = provision_user(&service, "patch_me").await;
+
+    let user = service
+        .patch_user(
+            &id,
+            patch(json!([
+                { "op": "Replace", "path": "active", "value": "False" },
+                { "op": "replace", "value": { "externalId": "ext-9", "name.givenName": "Pat" } },
+                { "op": "replace", "path": "emails[type eq \"work\"].value", "value": "new@example.com" }
+            ])),
+        )
+        .await
+        .expect("El PATCH debería ser exitoso");
+
+    assert_eq!(user.active, Some(false));
+    assert_eq!(user.external_id.as_deref(), Some("ext-9"));
+    assert_eq!(user.emails[0].value, "new@example.com");
+
+    let inactive = service
+        .list_users(ScimListQuery {
+            filter: Some("active eq false".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(inactive.total_results, 1);
+
+    let err = service
+        .patch_user(
+            &id,
+            ScimPatchRequest {
+                schemas: vec![],
+                operations: vec![ScimPatchOperation {
+                    op: "move".to_string(),
+                    path: Some("active".to_string()),
+                    value: None,
+                }],
+            },
+        )
+        .await
+        .expect_err("Una operación desconocida debería fallar");
+    assert_eq!(err.status, StatusCode::BAD_REQUEST);
+}
+
+/// ---
+///
+/// ## Test Case 5: DELETE marca status 3 y el usuario deja de ser visible
+///
+#[tokio::test]
+async fn test_scim_delete_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    let id = provision_user(&service, "delete_me").await;
+
+    service
+        .delete_user(&id)
+        .await
+        .expect("El borrado debería ser exitoso");
+
+    let err = service
+        .get_user(&id)
+        .await
+        .expect_err("No debería encontrarse");
+    assert_eq!(err.status, StatusCode::NOT_FOUND);
+    let err = service
+        .delete_user(&id)
+        .await
+        .expect_err("Ya estaba eliminado");
+    assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+    let client = pool.get().await.unwrap();
+    let status: i32 = client
+        .query_one(
+            "SELECT status FROM users WHERE id = $1",
+            &[&id.parse::<i64>().unwrap()],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(status, 3);
+}
+
+/// ---
+///
+/// ## Test Case 6: Solo el token de aprovisionamiento autoriza las peticiones
+///
+#[tokio::test]
+async fn test_scim_authorize() {
+    let pool = common::get_test_pool();
+    let service = ScimService::new(pool, Some("provisioning-secret".to_string()));
+
+    assert!(
+        service
+            .authorize(Some("Bearer provisioning-secret"))
+            .is_ok()
+    );
+    for header in [None, Some("Bearer wrong"), Some("provisioning-secret")] {
+        let err = service.authorize(header).expect_err("Debería rechazarse");
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+    }
+
+    let disabled = ScimService::new(pool, None);
+    assert!(disabled.authorize(Some("Bearer anything")).is_err());
+}
+
+/// ---
+///
+/// ## Test Case 7: Los atributos obligatorios llegan en la extensión y PATCH los conserva
+///
+#[tokio::test]
+async fn test_scim_user_attributes_extension() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = ScimService::new(pool, None);
+    AttributeService::new(pool)
+        .upsert(
+            "department",
+            AttributeDefinitionDto {
+                kind: AttributeType::String,
+                required: true,
+                include_in_token: false,
+                user_editable: false,
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    let user: ScimUser = serde_json::from_value(json!({
+        "schemas": [
+            "urn:ietf:params:scim:schemas:core:2.0:User",
+            "urn:r-auth:params:scim:schemas:extension:2.0:User"
+        ],
+        "userName": "scim_attrs",
+        "emails": [{"value": "scim_attrs@example.com"}],
+        "urn:r-auth:params:scim:schemas:extension:2.0:User": {"department": "ventas"}
+    }))
+    .unwrap();
+
+    let err = service
+        .create_user(ScimUser {
+            attributes: None,
+            ..user.clone()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.status, StatusCode::BAD_REQUEST);
+
+    let created = service.create_user(user).await.unwrap();
+    let id = created.id.clone().unwrap();
+    let resource = serde_json::to_value(&created).unwrap();
+    assert_eq!(
+        resource["urn:r-auth:params:scim:schemas:extension:2.0:User"],
+        json!({"department": "ventas"})
+    );
+
+    let patched = service
+        .patch_user(
+            &id,
+            patch(json!([{"op": "replace", "path": "active", "value": false}])),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        patched.attributes.map(Value::Object),
+        Some(json!({"department": "ventas"}))
+    );
+}
@@ -0,0 +1,137 @@
+use axum::{
+    body::to_bytes,
+    extract::FromRequestParts,
+    http::{Method, Request, StatusCode, header},
+    response::Response,
+};
+use r_auth_api::{
+    auth::{
+        AuthenticatedClaims, CSRF_COOKIE, CSRF_HEADER, SESSION_COOKIE, login_response,
+        logout_response,
+    },
+    config::{SessionConfig, SessionMode},
+};
+use serde_json::Value;
+
+fn session_config(mode: SessionMode) -> SessionConfig {
+    SessionConfig {
+        mode,
+        cookie_secure: true,
+        cookie_same_site: "Strict".to_string(),
+        cookie_domain: None,
+    }
+}
+
+fn set_cookies(response: &Response) -> Vec<String> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|h| h.to_str().unwrap().to_string())
+        .collect()
+}
+
+async fn body_json(response: Response) -> Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn extract(method: Method, cookie: &str, csrf: Option<&str>) -> StatusCode {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri("/")
+        .header(header::COOKIE, cookie);
+    if let Some(csrf) = csrf {
+        builder = builder.header(CSRF_HEADER, csrf);
+    }
+    let (mut parts, _) = builder.body(()).unwrap().into_parts();
+    match AuthenticatedClaims::from_request_parts(&mut parts, &()).await {
+        Ok(_) => StatusCode::OK,
+        Err((status, _)) => status,
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: En modo token el JWT va en el cuerpo y no hay cookies
+///
+#[tokio::test]
+async fn test_token_mode_returns_token() {
+    let response = login_response("jwt".to_string(), &session_config(SessionMode::Token));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(set_cookies(&response).is_empty());
+    let body = body_json(response).await;
+    assert_eq!(body["token"], "jwt");
+    assert!(body.get("csrfToken").is_none());
+}
+
+/// ---
+///
+/// ## Test Case 2: En modo cookie el JWT solo viaja en una cookie HttpOnly
+///
+#[tokio::test]
+async fn test_cookie_mode_sets_cookies() {
+    let response = login_response("jwt".to_string(), &session_config(SessionMode::Cookie));
+    let cookies = set_cookies(&response);
+
+    let session = cookies
+        .iter()
+        .find(|c| c.starts_with(&format!("{}=jwt;", SESSION_COOKIE)))
+        .expect("Falta la cookie de sesión");
+    assert!(session.contains("HttpOnly"));
+    assert!(session.contains("Secure"));
+    assert!(session.contains("SameSite=Strict"));
+
+    let csrf = cookies
+        .iter()
+        .find(|c| c.starts_with(&format!("{}=", CSRF_COOKIE)))
+        .expect("Falta la cookie CSRF");
+    assert!(!csrf.contains("HttpOnly"));
+
+    let body = body_json(response).await;
+    assert!(body.get("token").is_none());
+    let csrf_token = body["csrfToken"].as_str().unwrap();
+    assert!(csrf.starts_with(&format!("{}={};", CSRF_COOKIE, csrf_token)));
+}
+
+/// ---
+///
+/// ## Test Case 3: Logout vence ambas cookies
+///
+#[tokio::test]
+async fn test_logout_clears_cookies() {
+    let response = logout_response(&session_config(SessionMode::Cookie));
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 2);
+    assert!(cookies.iter().all(|c| c.contains("Max-Age=0")));
+}
+
+/// ---
+///
+/// ## Test Case 4: Las rutas que modifican estado exigen el token CSRF con la cookie
+///
+#[tokio::test]
+async fn test_cookie_auth_requires_csrf() {
+    // El JWT es inválido: pasar la verificación CSRF se ve como un 401.
+    let cookie = format!("{}=invalid; {}=abc123", SESSION_COOKIE, CSRF_COOKIE);
+
+    assert_eq!(
+        extract(Method::POST, &cookie, None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        extract(Method::PATCH, &cookie, Some("otro")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        extract(Method::DELETE, &cookie, Some("abc123")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        extract(Method::GET, &cookie, None).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
@@ -0,0 +1,93 @@
+use r_auth_api::{
+    database::models::{
+        FindQuery,
+        dto::{CreateUserDto, TransferFormat, UpdateUserDto},
+    },
+    services::{UserTransferService, UsersService},
+};
+
+use super::export_text;
+use crate::common;
+
+async fn create_users(service: &UsersService) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for username in ["export_one", "export_two", "export_gone"] {
+        let user = service
+            .create(CreateUserDto {
+                username: username.to_string(),
+                email: format!("{}@example.com", username),
+                password: "StrongPassword@123".to_string(),
+                attributes: None,
+            })
+            .await
+            .unwrap();
+        ids.push(user.id);
+    }
+    service
+        .update(UpdateUserDto {
+            id: Some(ids[0]),
+            display_name: Some("Uno, con coma".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    service.delete(ids[2]).await.unwrap();
+    ids
+}
+
+/// ---
+///
+/// ## Test Case 1: El CSV exportado tiene encabezado, omite eliminados y escapa comas
+///
+#[tokio::test]
+async fn test_export_csv() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    create_users(&UsersService::new(pool)).await;
+
+    let csv = export_text(&UserTransferService::new(pool), TransferFormat::Csv, None).await;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "username,email,permissions,status,displayName,locale,timezone,avatarUrl,phoneNumber,attributes"
+    );
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("export_one,export_one@example.com,"));
+    assert!(lines[1].contains(",active,\"Uno, con coma\","));
+    assert!(!csv.contains("export_gone"));
+    assert!(!csv.contains("argon2"));
+}
+
+/// ---
+///
+/// ## Test Case 2: El NDJSON exportado se puede volver a importar
+///
+#[tokio::test]
+async fn test_export_ndjson_round_trip() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UserTransferService::new(pool);
+    create_users(&UsersService::new(pool)).await;
+
+    let ndjson = export_text(&service, TransferFormat::Ndjson, None).await;
+    assert_eq!(ndjson.lines().count(), 2);
+
+    common::setup_test_environment(pool).await;
+    let report = service
+        .import(ndjson.as_bytes(), TransferFormat::Ndjson, false)
+        .await
+        .unwrap();
+    assert_eq!(report.imported, 2);
+    assert!(report.errors.is_empty());
+
+    let found = UsersService::new(pool)
+        .find(FindQuery::default())
+        .await
+        .unwrap();
+    let usernames: Vec<_> = found.results.iter().map(|u| u.username.as_str()).collect();
+    assert_eq!(usernames, ["export_one", "export_two"]);
+    assert_eq!(
+        ndjson,
+        export_text(&service, TransferFormat::Ndjson, None).await
+    );
+}
@@ -0,0 +1,195 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    auth::hash_password,
+    database::models::{
+        FindQuery,
+        dto::{AttributeDefinitionDto, CreateUserDto, LoginRequest, TransferFormat},
+        entities::{attribute_definition::AttributeType, user_status::UserStatus},
+    },
+    services::{AttributeService, UserTransferService, UsersService},
+};
+
+use crate::common;
+
+const PASSWORD: &str = "StrongPassword@123";
+
+async fn login(service: &UsersService, email: &str) -> bool {
+    service
+        .login(LoginRequest {
+            email: email.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .is_ok()
+}
+
+/// ---
+///
+/// ## Test Case 1: Importa un CSV con hashes previos y reporta los errores por línea
+///
+#[tokio::test]
+async fn test_import_csv_reports_row_errors() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+    users_service
+        .create(CreateUserDto {
+            username: "existing".to_string(),
+            email: "existing@example.com".to_string(),
+            password: PASSWORD.to_string(),
+            attributes: None,
+        })
+        .await
+        .unwrap();
+
+    let bcrypt_hash = bcrypt::hash(PASSWORD, 4).unwrap();
+    let argon2_hash = hash_password(PASSWORD).unwrap();
+    let csv = format!(
+        "\u{feff}username,email,password,passwordHash,status,phoneNumber\n\
+         plain_user,Plain@Example.com,{password},,,+5491122334455\n\
+         bcrypt_user,bcrypt@example.com,,{bcrypt_hash},,\n\
+         argon_user,argon@example.com,,\"{argon2_hash}\",inactive,\n\
+         bad_email,not-an-email,{password},,,\n\
+         plain_again,plain@example.com,{password},,,\n\
+         existing,other@example.com,{password},,,\n\
+         bad_hash,bad_hash@example.com,,$1$abc,,\n\
+         bad_status,bad_status@example.com,{password},,archived,\n",
+        password = PASSWORD,
+    );
+
+    let report = UserTransferService::new(pool)
+        .with_batch_size(2)
+        .import(csv.as_bytes(), TransferFormat::Csv, false)
+        .await
+        .expect("Error importando el CSV");
+
+    assert_eq!(report.total, 8);
+    assert_eq!(report.imported, 3);
+    assert_eq!(report.failed, 5);
+    let lines: Vec<u64> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, [5, 6, 7, 8, 9]);
+
+    let plain = users_service
+        .find_by_email("plain@example.com")
+        .await
+        .unwrap();
+    assert_eq!(plain.username, "plain_user");
+    assert!(login(&users_service, "plain@example.com").await);
+    assert!(login(&users_service, "bcrypt@example.com").await);
+
+    // El hash bcrypt se reemplaza por Argon2 en el primer login.
+    let client = pool.get().await.unwrap();
+    let hash: String = client
+        .query_one(
+            "SELECT password FROM users WHERE email = 'bcrypt@example.com'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(hash.starts_with("$argon2"));
+
+    let inactive = users_service
+        .find(FindQuery {
+            status: Some(UserStatus::Inactive),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let usernames: Vec<_> = inactive
+        .results
+        .iter()
+        .map(|u| u.username.as_str())
+        .collect();
+    assert_eq!(usernames, ["argon_user"]);
+}
+
+/// ---
+///
+/// ## Test Case 2: En dry-run se valida todo sin crear usuarios
+///
+#[tokio::test]
+async fn test_import_dry_run_creates_nothing() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+
+    let ndjson = format!(
+        "{{\"username\":\"dry_one\",\"email\":\"dry_one@example.com\",\"password\":\"{0}\"}}\n\
+         \n\
+         {{\"username\":\"dry_two\",\"email\":\"dry_two@example.com\",\"unknown\":1}}\n\
+         not json\n",
+        PASSWORD
+    );
+    let report = UserTransferService::new(pool)
+        .import(ndjson.as_bytes(), TransferFormat::Ndjson, true)
+        .await
+        .expect("Error validando el NDJSON");
+
+    assert!(report.dry_run);
+    assert_eq!(report.total, 3);
+    assert_eq!(report.imported, 1);
+    let lines: Vec<u64> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, [3, 4]);
+    assert!(report.without_password.is_empty());
+
+    let found = UsersService::new(pool)
+        .find(FindQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(found.total, Some(0));
+}
+
+/// ---
+///
+/// ## Test Case 3: Perfil y atributos se validan contra sus definiciones
+///
+#[tokio::test]
+async fn test_import_ndjson_with_attributes() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    AttributeService::new(pool)
+        .upsert(
+            "department",
+            AttributeDefinitionDto {
+                kind: AttributeType::String,
+                required: true,
+                include_in_token: false,
+                description: None,
+                user_editable: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let ndjson = r#"{"username":"attr_ok","email":"attr_ok@example.com","displayName":"Ana","timezone":"UTC","attributes":{"department":"ventas"}}
+{"username":"attr_type","email":"attr_type@example.com","attributes":{"department":7}}
+{"username":"attr_missing","email":"attr_missing@example.com"}
+{"username":"bad_zone","email":"bad_zone@example.com","timezone":"Mars/Base","attributes":{"department":"x"}}
+"#;
+    let report = UserTransferService::new(pool)
+        .import(ndjson.as_bytes(), TransferFormat::Ndjson, false)
+        .await
+        .expect("Error importando el NDJSON");
+    assert_eq!(report.imported, 1);
+    let lines: Vec<u64> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, [2, 3, 4]);
+    assert_eq!(report.without_password, [1]);
+
+    let user = UsersService::new(pool)
+        .find_by_email("attr_ok@example.com")
+        .await
+        .unwrap();
+    let user = UsersService::new(pool).find_by_id(user.id).await.unwrap();
+    assert_eq!(user.display_name.as_deref(), Some("Ana"));
+    assert_eq!(user.attributes["department"], "ventas");
+    // Sin contraseña solo puede entrar con otro método (OIDC, enlace mágico).
+    assert!(user.password.is_none());
+
+    let Err((status, _)) = UserTransferService::new(pool)
+        .import(b"username,mail\nx,y\n", TransferFormat::Csv, false)
+        .await
+    else {
+        panic!("Una columna desconocida debería rechazar el archivo");
+    };
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,28 @@
+pub mod export;
+pub mod import;
+
+use futures_util::StreamExt;
+use r_auth_api::{
+    database::models::{dto::TransferFormat, entities::user_status::UserStatus},
+    services::UserTransferService,
+};
+
+/// Junta el stream de la exportación en un texto.
+pub async fn export_text(
+    service: &UserTransferService,
+    format: TransferFormat,
+    status: Option<UserStatus>,
+) -> String {
+    let chunks: Vec<_> = service
+        .export(format, status)
+        .await
+        .expect("Error iniciando la exportación")
+        .collect()
+        .await;
+    let bytes: Vec<u8> = chunks
+        .into_iter()
+        .map(|chunk| chunk.expect("Error leyendo la exportación"))
+        .flat_map(|chunk| chunk.to_vec())
+        .collect();
+    String::from_utf8(bytes).expect("La exportación no es UTF-8")
+}
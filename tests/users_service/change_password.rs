@@ -20,6 +20,7 @@ async fn test_change_password_success() {
         username: "change_pwd".to_string(),
         email: "change_pwd@example.com".to_string(),
         password: "OldPassword@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -84,6 +85,7 @@ async fn test_change_password_new_equals_old() {
         username: "same_pwd".to_string(),
         email: "same_pwd@example.com".to_string(),
         password: "Password@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -126,6 +128,7 @@ async fn test_change_password_wrong_previous_password() {
         username: "wrong_prev".to_string(),
         email: "wrong_prev@example.com".to_string(),
         password: "CorrectPassword@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -168,6 +171,7 @@ async fn test_change_password_weak_new_password() {
         username: "weak_new_pwd".to_string(),
         email: "weak_new_pwd@example.com".to_string(),
         password: "OldStrongPwd@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -187,8 +191,7 @@ async fn test_change_password_weak_new_password() {
     assert!(result.is_err(), "Debería fallar por nueva contraseña débil");
     let (status, Json(http_error)) = result.unwrap_err();
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert_eq!(
-        http_error.errors.get("client").unwrap().first().unwrap(),
-        "La contraseña debe tener al menos: 1 minúscula, 1 mayúscula, 1 número y 1 caracter especial"
-    );
+    let violations = http_error.errors.get("password").unwrap();
+    assert!(violations.contains(&"La contraseña debe tener al menos una mayúscula".to_string()));
+    assert!(violations.contains(&"La contraseña debe tener al menos un número".to_string()));
 }
@@ -23,6 +23,7 @@ async fn test_create_user_success() {
         username: username.clone(),
         email: email.clone(),
         password: password.clone(),
+        attributes: None,
     };
 
     let result = users_service.create(new_user_dto.clone()).await;
@@ -90,6 +91,7 @@ async fn test_create_user_duplicate_username() {
         username: username.clone(),
         email: email.clone(),
         password: password.clone(),
+        attributes: None,
     };
 
     users_service
@@ -101,6 +103,7 @@ async fn test_create_user_duplicate_username() {
         username: username.clone(),
         email: "anotheremail@example.com".to_string(),
         password: password.clone(),
+        attributes: None,
     };
 
     let result = users_service.create(duplicate_user_dto).await;
@@ -136,6 +139,7 @@ async fn test_create_user_duplicate_email() {
         username: username.clone(),
         email: email.clone(),
         password: password.clone(),
+        attributes: None,
     };
 
     users_service
@@ -147,6 +151,7 @@ async fn test_create_user_duplicate_email() {
         username: "anotheruser2".to_string(),
         email: email.clone(),
         password: password.clone(),
+        attributes: None,
     };
 
     let result = users_service.create(duplicate_user_dto).await;
@@ -181,6 +186,7 @@ async fn test_create_user_weak_password() {
         username: username.clone(),
         email: email.clone(),
         password: password.clone(),
+        attributes: None,
     };
 
     let result = users_service.create(user_dto).await;
@@ -191,10 +197,13 @@ async fn test_create_user_weak_password() {
     );
     let (status, Json(http_error)) = result.unwrap_err();
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    println!("{:?}", http_error);
     assert_eq!(
-        http_error.errors.get("client").unwrap().first().unwrap(),
-        "La contraseña debe tener al menos: 1 minúscula, 1 mayúscula, 1 número y 1 caracter especial"
+        http_error.errors.get("password").unwrap(),
+        &vec![
+            "La contraseña debe tener al menos una mayúscula".to_string(),
+            "La contraseña debe tener al menos un número".to_string(),
+            "La contraseña debe tener al menos un caracter especial".to_string(),
+        ]
     );
 }
 
@@ -216,6 +225,7 @@ async fn test_create_user_invalid_dto() {
         username: username.clone(),
         email: email.clone(),
         password: password.clone(),
+        attributes: None,
     };
 
     let result = users_service.create(user_dto).await;
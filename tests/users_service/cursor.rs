@@ -0,0 +1,154 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::{FindQuery, dto::CreateUserDto},
+    services::UsersService,
+};
+
+use crate::common;
+
+async fn create_users(service: &UsersService, count: usize) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for i in 1..=count {
+        let user = service
+            .create(CreateUserDto {
+                username: format!("cursor_user_{}", i),
+                email: format!("cursor_user_{}@example.com", i),
+                password: "StrongPassword@123".to_string(),
+                attributes: None,
+            })
+            .await
+            .expect("Fallo al crear usuario de prueba");
+        ids.push(user.id);
+    }
+    ids
+}
+
+/// Recorre todas las páginas siguiendo `nextCursor`.
+async fn walk(service: &UsersService, order: &str) -> Vec<i64> {
+    let mut ids = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let page = service
+            .find(FindQuery {
+                cursor: Some(cursor),
+                order: Some(order.to_string()),
+                limit: Some(2),
+                ..Default::default()
+            })
+            .await
+            .expect("Error leyendo la página");
+        assert!(page.total.is_none());
+        assert!(page.results.len() <= 2);
+        ids.extend(page.results.iter().map(|u| u.id));
+        match page.next_cursor {
+            Some(next) => cursor = next,
+            None => return ids,
+        }
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: El cursor recorre todos los usuarios sin repetir, aun con created_at iguales
+///
+#[tokio::test]
+async fn test_cursor_walks_all_users() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let ids = create_users(&service, 5).await;
+
+    // Tres usuarios con el mismo created_at: el desempate es el id.
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE users SET created_at = '2024-01-01T00:00:00Z' WHERE id = ANY($1)",
+            &[&ids[1..4].to_vec()],
+        )
+        .await
+        .unwrap();
+    let mut expected = vec![ids[1], ids[2], ids[3], ids[0], ids[4]];
+
+    assert_eq!(walk(&service, "asc").await, expected);
+    expected.reverse();
+    assert_eq!(walk(&service, "desc").await, expected);
+
+    let with_total = service
+        .find(FindQuery {
+            cursor: Some(String::new()),
+            include_total: Some(true),
+            limit: Some(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(with_total.total, Some(5));
+    assert!(with_total.next_cursor.is_some());
+}
+
+/// ---
+///
+/// ## Test Case 2: La paginación por páginas conserva el total y no devuelve cursor
+///
+#[tokio::test]
+async fn test_page_mode_is_unchanged() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    create_users(&service, 3).await;
+
+    let page = service
+        .find(FindQuery {
+            limit: Some(2),
+            page: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.total, Some(3));
+    assert!(page.next_cursor.is_none());
+
+    let without_total = service
+        .find(FindQuery {
+            include_total: Some(false),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(without_total.total.is_none());
+    assert_eq!(without_total.results.len(), 3);
+}
+
+/// ---
+///
+/// ## Test Case 3: Cursores inválidos o combinaciones no soportadas responden 400
+///
+#[tokio::test]
+async fn test_cursor_invalid_requests() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+
+    let invalid = [
+        FindQuery {
+            cursor: Some("no-es-un-cursor".to_string()),
+            ..Default::default()
+        },
+        FindQuery {
+            cursor: Some(String::new()),
+            page: Some(2),
+            ..Default::default()
+        },
+        FindQuery {
+            cursor: Some(String::new()),
+            sort: Some("username".to_string()),
+            ..Default::default()
+        },
+    ];
+    for query in invalid {
+        let Err((status, _)) = service.find(query).await else {
+            panic!("La consulta debería rechazarse");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
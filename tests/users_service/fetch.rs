@@ -21,6 +21,7 @@ async fn test_fetch_user_success() {
         username: "fetch_success".to_string(),
         email: "fetch_success@example.com".to_string(),
         password: password.clone(),
+        attributes: None,
     };
 
     let created_user = users_service
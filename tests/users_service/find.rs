@@ -21,6 +21,7 @@ async fn test_find_user_by_id_success() {
         username: "findbyiduser".to_string(),
         email: "findbyid@example.com".to_string(),
         password: "StrongPassword@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -35,6 +36,7 @@ async fn test_find_user_by_id_success() {
             query_value: Some(created_user.id.to_string()),
             limit: Some(10),
             page: Some(1),
+            ..Default::default()
         })
         .await;
 
@@ -45,7 +47,7 @@ async fn test_find_user_by_id_success() {
     );
 
     let result_data = find_result.unwrap();
-    assert_eq!(result_data.total, 1);
+    assert_eq!(result_data.total, Some(1));
     let found_user = &result_data.results[0];
     assert_eq!(found_user.id, created_user.id);
     assert_eq!(found_user.username, "findbyiduser");
@@ -66,6 +68,7 @@ async fn test_find_user_by_username_success() {
         username: "findbyusername".to_string(),
         email: "findbyusername@example.com".to_string(),
         password: "StrongPassword@123".to_string(),
+        attributes: None,
     };
 
     users_service
@@ -79,6 +82,7 @@ async fn test_find_user_by_username_success() {
             query_value: Some("findbyusername".to_string()),
             limit: Some(10),
             page: Some(1),
+            ..Default::default()
         })
         .await;
 
@@ -89,7 +93,7 @@ async fn test_find_user_by_username_success() {
     );
 
     let result_data = find_result.unwrap();
-    assert_eq!(result_data.total, 1);
+    assert_eq!(result_data.total, Some(1));
     assert_eq!(result_data.results[0].username, "findbyusername");
 }
 
@@ -107,6 +111,7 @@ async fn test_find_user_by_email_success() {
         username: "findbyemail".to_string(),
         email: "findbyemail@example.com".to_string(),
         password: "StrongPassword@123".to_string(),
+        attributes: None,
     };
 
     users_service
@@ -120,6 +125,7 @@ async fn test_find_user_by_email_success() {
             query_value: Some("findbyemail@example.com".to_string()),
             limit: Some(10),
             page: Some(1),
+            ..Default::default()
         })
         .await;
 
@@ -130,7 +136,7 @@ async fn test_find_user_by_email_success() {
     );
 
     let result_data = find_result.unwrap();
-    assert_eq!(result_data.total, 1);
+    assert_eq!(result_data.total, Some(1));
     assert_eq!(result_data.results[0].email, "findbyemail@example.com");
 }
 
@@ -150,6 +156,7 @@ async fn test_find_user_invalid_key() {
             query_value: Some("value".to_string()),
             limit: Some(10),
             page: Some(1),
+            ..Default::default()
         })
         .await;
 
@@ -189,6 +196,7 @@ async fn test_find_user_pagination() {
             username: format!("user_pagination_{}", i),
             email: format!("user_pagination_{}@example.com", i),
             password: "StrongPassword@123".to_string(),
+            attributes: None,
         };
 
         users_service
@@ -203,6 +211,7 @@ async fn test_find_user_pagination() {
             query_value: Some("user_pagination".to_string()),
             limit: Some(10),
             page: Some(2),
+            ..Default::default()
         })
         .await;
 
@@ -212,7 +221,7 @@ async fn test_find_user_pagination() {
     );
 
     let result_data = find_result.unwrap();
-    assert_eq!(result_data.total, 15);
+    assert_eq!(result_data.total, Some(15));
     assert_eq!(
         result_data.results.len(),
         5,
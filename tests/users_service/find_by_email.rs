@@ -21,6 +21,7 @@ async fn test_find_by_email_success() {
         username: "findbyemail_success".to_string(),
         email: "findbyemail_success@example.com".to_string(),
         password: password.clone(),
+        attributes: None,
     };
 
     let created_user = users_service
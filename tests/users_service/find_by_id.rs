@@ -17,6 +17,7 @@ async fn test_find_by_id_success() {
         username: "findbyid_success".to_string(),
         email: "findbyid_success@example.com".to_string(),
         password: "StrongPassword@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -16,6 +16,7 @@ async fn test_inactive_user_success() {
         username: "inactive_user".to_string(),
         email: "inactive_user@example.com".to_string(),
         password: "Password@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -42,6 +43,7 @@ async fn test_delete_user_success() {
         username: "delete_user".to_string(),
         email: "delete_user@example.com".to_string(),
         password: "Password@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -2,7 +2,9 @@ use axum::{Json, http::StatusCode};
 use r_auth_api::{
     database::models::dto::{CreateUserDto, LoginRequest},
     services::UsersService,
+    utils::USER_PERMISSIONS,
 };
+use r_auth_middleware::TokenVerifier;
 
 use crate::common;
 
@@ -22,6 +24,7 @@ async fn test_login_success() {
         username: "login_success".to_string(),
         email: "login_success@example.com".to_string(),
         password: password.clone(),
+        attributes: None,
     };
 
     users_service
@@ -93,6 +96,7 @@ async fn test_login_wrong_password() {
         username: "login_wrong_pwd".to_string(),
         email: "login_wrong_pwd@example.com".to_string(),
         password: "CorrectPassword@123".to_string(),
+        attributes: None,
     };
 
     users_service
@@ -119,3 +123,87 @@ async fn test_login_wrong_password() {
         "Credenciales inválidas"
     );
 }
+
+/// ---
+///
+/// ## Test Case 4: El token de login lleva username y permisos verificables sin base de datos
+///
+#[tokio::test]
+async fn test_login_token_verifiable_by_middleware() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+
+    users_service
+        .create(CreateUserDto {
+            username: "login_claims".to_string(),
+            email: "login_claims@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    let token = users_service
+        .login(LoginRequest {
+            email: "login_claims@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+        })
+        .await
+        .expect("El login debería ser exitoso");
+
+    let secret = std::env::var("JWT_SECRET").unwrap();
+    let claims = TokenVerifier::from_secret(secret)
+        .verify(&token)
+        .await
+        .expect("El middleware debería aceptar el token");
+
+    assert_eq!(claims.username.as_deref(), Some("login_claims"));
+    assert_eq!(claims.permissions(), USER_PERMISSIONS);
+}
+
+/// ---
+///
+/// ## Test Case 5: La longitud de la contraseña no se valida en el login, solo las credenciales
+///
+#[tokio::test]
+async fn test_login_does_not_apply_password_policy_length() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let users_service = UsersService::new(pool);
+
+    users_service
+        .create(CreateUserDto {
+            username: "login_length".to_string(),
+            email: "login_length@example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    for password in ["short", &"x".repeat(100)] {
+        let (status, _) = users_service
+            .login(LoginRequest {
+                email: "login_length@example.com".to_string(),
+                password: password.to_string(),
+            })
+            .await
+            .expect_err("Una contraseña incorrecta no debería iniciar sesión");
+        assert_eq!(
+            status,
+            StatusCode::UNAUTHORIZED,
+            "Se esperaba 401 y no un error de validación para {} caracteres",
+            password.len()
+        );
+    }
+
+    let (status, _) = users_service
+        .login(LoginRequest {
+            email: "login_length@example.com".to_string(),
+            password: "x".repeat(1025),
+        })
+        .await
+        .expect_err("Una contraseña desmesurada debería rechazarse");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
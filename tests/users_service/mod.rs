@@ -1,9 +1,17 @@
 pub mod change_password;
 pub mod create;
+pub mod cursor;
 pub mod fetch;
 pub mod find;
 pub mod find_by_email;
 pub mod find_by_id;
 pub mod inactive_and_delete;
 pub mod login;
+pub mod must_change_password;
+pub mod normalization;
+pub mod password_history;
+pub mod pepper;
+pub mod rehash;
+pub mod search;
+pub mod status_lifecycle;
 pub mod update;
@@ -0,0 +1,163 @@
+use r_auth_api::{
+    auth::decode_jwt,
+    database::models::dto::{ChangePasswordDto, CreateUserDto, LoginRequest, UpdateUserDto},
+    services::UsersService,
+};
+
+use crate::common;
+
+async fn create_user(service: &UsersService, name: &str) -> i64 {
+    service
+        .create(CreateUserDto {
+            username: name.to_string(),
+            email: format!("{}@example.com", name),
+            password: "First@Pass1".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando usuario")
+        .id
+}
+
+/// ---
+///
+/// ## Test Case 1: Un administrador activa la marca desde la actualización
+///
+#[tokio::test]
+async fn test_update_sets_must_change_password() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "must_change_update").await;
+
+    let user = service
+        .update(UpdateUserDto {
+            id: Some(id),
+            username: None,
+            email: None,
+            permissions: None,
+            must_change_password: Some(true),
+            ..Default::default()
+        })
+        .await
+        .expect("Error actualizando usuario");
+    assert!(user.must_change_password);
+
+    let user = service.find_by_id(id).await.unwrap();
+    assert!(user.must_change_password);
+}
+
+/// ---
+///
+/// ## Test Case 2: Cambiar la contraseña limpia la marca
+///
+#[tokio::test]
+async fn test_change_password_clears_flag() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "must_change_clear").await;
+    service.set_must_change_password(id, true).await.unwrap();
+
+    service
+        .change_password(
+            id.to_string(),
+            ChangePasswordDto {
+                previous_password: "First@Pass1".to_string(),
+                new_password: "Second@Pass2".to_string(),
+            },
+        )
+        .await
+        .expect("Error cambiando la contraseña");
+
+    let user = service.find_by_id(id).await.unwrap();
+    assert!(!user.must_change_password);
+}
+
+/// ---
+///
+/// ## Test Case 3: Marcar a un usuario inexistente devuelve 404
+///
+#[tokio::test]
+async fn test_set_flag_unknown_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+
+    let (status, _) = service
+        .set_must_change_password(i64::MAX, true)
+        .await
+        .unwrap_err();
+    assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+}
+
+/// ---
+///
+/// ## Test Case 4: Con la marca activa el login da un token limitado aunque la contraseña no haya vencido
+///
+#[tokio::test]
+async fn test_flag_limits_login_token() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "must_change_login").await;
+    let login = |password: &str| LoginRequest {
+        email: "must_change_login@example.com".to_string(),
+        password: password.to_string(),
+    };
+
+    service.set_must_change_password(id, true).await.unwrap();
+    let token = service.login(login("First@Pass1")).await.unwrap();
+    assert!(decode_jwt(&token).unwrap().is_password_change_only());
+
+    service
+        .change_password(
+            id.to_string(),
+            ChangePasswordDto {
+                previous_password: "First@Pass1".to_string(),
+                new_password: "Second@Pass2".to_string(),
+            },
+        )
+        .await
+        .expect("Error cambiando la contraseña");
+    let token = service.login(login("Second@Pass2")).await.unwrap();
+    assert!(!decode_jwt(&token).unwrap().is_password_change_only());
+}
+
+/// ---
+///
+/// ## Test Case 5: No se puede marcar a un usuario sin contraseña local
+///
+#[tokio::test]
+async fn test_flag_requires_local_password() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "must_change_federated").await;
+    let client = pool.get().await.unwrap();
+    client
+        .execute("UPDATE users SET password = NULL WHERE id = $1", &[&id])
+        .await
+        .unwrap();
+
+    let (status, _) = service
+        .set_must_change_password(id, true)
+        .await
+        .unwrap_err();
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+
+    let (status, _) = service
+        .update(UpdateUserDto {
+            id: Some(id),
+            must_change_password: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+
+    // Desmarcar sigue permitido.
+    service.set_must_change_password(id, false).await.unwrap();
+    let user = service.find_by_id(id).await.unwrap();
+    assert!(!user.must_change_password);
+}
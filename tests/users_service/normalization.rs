@@ -0,0 +1,116 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::dto::{CreateUserDto, LoginRequest},
+    services::UsersService,
+};
+
+use crate::common;
+
+fn create_dto(username: &str, email: &str) -> CreateUserDto {
+    CreateUserDto {
+        username: username.to_string(),
+        email: email.to_string(),
+        password: "StrongPassword@123".to_string(),
+        attributes: None,
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: Email y username se normalizan al crear y la unicidad ignora mayúsculas
+///
+#[tokio::test]
+async fn test_create_normalizes_and_rejects_case_duplicates() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+
+    // NFKC convierte los caracteres de ancho completo a ASCII.
+    let user = service
+        .create(create_dto(" Ａlice_N ", " Alice@Example.COM "))
+        .await
+        .expect("Error creando el usuario");
+    assert_eq!(user.username, "Alice_N");
+    assert_eq!(user.email, "alice@example.com");
+
+    for (username, email) in [
+        ("other_user", "ALICE@example.com"),
+        ("alice_n", "other@example.com"),
+    ] {
+        let Err((status, _)) = service.create(create_dto(username, email)).await else {
+            panic!("{} / {} debería ser un duplicado", username, email);
+        };
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    let found = service
+        .find_by_email("ALICE@EXAMPLE.COM")
+        .await
+        .expect("El email debería encontrarse sin distinguir mayúsculas");
+    assert_eq!(found.id, user.id);
+
+    service
+        .login(LoginRequest {
+            email: "Alice@Example.com".to_string(),
+            password: "StrongPassword@123".to_string(),
+        })
+        .await
+        .expect("El login debería ignorar las mayúsculas del email");
+}
+
+/// ---
+///
+/// ## Test Case 2: El reporte de colisiones agrupa cuentas que solo difieren al normalizar
+///
+#[tokio::test]
+async fn test_identity_collisions_report() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let bob = service
+        .create(create_dto("bob", "bob@example.com"))
+        .await
+        .unwrap();
+    service
+        .create(create_dto("carol", "carol@example.com"))
+        .await
+        .unwrap();
+
+    // Filas anteriores a la normalización: los índices por lower() no las
+    // detectan porque difieren en NFKC o en espacios.
+    let client = pool.get().await.unwrap();
+    let legacy: i64 = client
+        .query_one(
+            r#"
+                INSERT INTO users (username, email, status, permissions)
+                VALUES ('bob_legacy', 'ｂｏｂ@example.com', 1, 0)
+                RETURNING id
+            "#,
+            &[],
+        )
+        .await
+        .unwrap()
+        .get("id");
+    client
+        .execute(
+            r#"
+                INSERT INTO users (username, email, status, permissions)
+                VALUES (' Carol', 'carol2@example.com', 1, 0)
+            "#,
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let collisions = service.identity_collisions().await.unwrap();
+    assert_eq!(collisions.len(), 2);
+
+    let email = collisions.iter().find(|c| c.field == "email").unwrap();
+    assert_eq!(email.value, "bob@example.com");
+    let ids: Vec<i64> = email.users.iter().map(|u| u.id).collect();
+    assert_eq!(ids, vec![bob.id, legacy]);
+
+    let username = collisions.iter().find(|c| c.field == "username").unwrap();
+    assert_eq!(username.value, "carol");
+    assert_eq!(username.users.len(), 2);
+}
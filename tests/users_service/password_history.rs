@@ -0,0 +1,176 @@
+use axum::{Json, http::StatusCode};
+use r_auth_api::{
+    auth::decode_jwt,
+    config::{PasswordPolicyConfig, get_config},
+    database::models::dto::{ChangePasswordDto, CreateUserDto, LoginRequest},
+    services::UsersService,
+};
+
+use crate::common;
+
+fn policy(history_size: u32, max_age_days: u32) -> PasswordPolicyConfig {
+    PasswordPolicyConfig {
+        history_size,
+        max_age_days,
+        ..get_config().password_policy.clone()
+    }
+}
+
+async fn create_user(service: &UsersService, name: &str, password: &str) -> i64 {
+    service
+        .create(CreateUserDto {
+            username: name.to_string(),
+            email: format!("{}@example.com", name),
+            password: password.to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando usuario")
+        .id
+}
+
+async fn change(
+    service: &UsersService,
+    id: i64,
+    previous: &str,
+    new: &str,
+) -> Result<(), (StatusCode, Json<r_auth_api::utils::errors::HttpError>)> {
+    service
+        .change_password(
+            id.to_string(),
+            ChangePasswordDto {
+                previous_password: previous.to_string(),
+                new_password: new.to_string(),
+            },
+        )
+        .await
+}
+
+async fn history_count(id: i64) -> i64 {
+    let client = common::get_test_pool().get().await.unwrap();
+    client
+        .query_one(
+            "SELECT COUNT(*) FROM password_history WHERE user_id = $1",
+            &[&id],
+        )
+        .await
+        .unwrap()
+        .get(0)
+}
+
+/// ---
+///
+/// ## Test Case 1: No se pueden reutilizar las contraseñas del historial
+///
+#[tokio::test]
+async fn test_history_prevents_reuse() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool).with_password_policy(policy(3, 0));
+    let id = create_user(&service, "history_reuse", "First@Pass1").await;
+
+    change(&service, id, "First@Pass1", "Second@Pass2")
+        .await
+        .unwrap();
+    change(&service, id, "Second@Pass2", "Third@Pass3")
+        .await
+        .unwrap();
+
+    let (status, Json(error)) = change(&service, id, "Third@Pass3", "First@Pass1")
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        error.errors.get("client").unwrap().first().unwrap(),
+        "La contraseña nueva no puede ser igual a ninguna de las últimas 3 contraseñas"
+    );
+
+    let (_, Json(error)) = change(&service, id, "Third@Pass3", "Third@Pass3")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.errors.get("client").unwrap().first().unwrap(),
+        "La contraseña nueva no puede ser igual a la contraseña antigua"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 2: El historial se recorta al tamaño configurado
+///
+#[tokio::test]
+async fn test_history_is_trimmed() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool).with_password_policy(policy(2, 0));
+    let id = create_user(&service, "history_trim", "Pass@Word0").await;
+
+    for i in 1..=4 {
+        change(
+            &service,
+            id,
+            &format!("Pass@Word{}", i - 1),
+            &format!("Pass@Word{}", i),
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(history_count(id).await, 2);
+
+    // La más vieja ya salió del historial y se puede volver a usar.
+    change(&service, id, "Pass@Word4", "Pass@Word1")
+        .await
+        .unwrap();
+
+    let without_history = UsersService::new(pool).with_password_policy(policy(0, 0));
+    change(&without_history, id, "Pass@Word1", "Pass@Word5")
+        .await
+        .unwrap();
+    assert_eq!(history_count(id).await, 0);
+}
+
+/// ---
+///
+/// ## Test Case 3: Con la contraseña vencida el login da un token limitado
+///
+#[tokio::test]
+async fn test_expired_password_limits_token() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool).with_password_policy(policy(5, 30));
+    let id = create_user(&service, "expired_pwd", "Expired@Pass1").await;
+    let login = || LoginRequest {
+        email: "expired_pwd@example.com".to_string(),
+        password: "Expired@Pass1".to_string(),
+    };
+
+    let token = service.login(login()).await.unwrap();
+    assert!(!decode_jwt(&token).unwrap().is_password_change_only());
+
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE users SET password_changed_at = now() - interval '31 days' WHERE id = $1",
+            &[&id],
+        )
+        .await
+        .unwrap();
+
+    let token = service.login(login()).await.unwrap();
+    let claims = decode_jwt(&token).unwrap();
+    assert!(claims.is_password_change_only());
+    assert!(claims.exp - claims.iat <= 10 * 60);
+
+    // Al cambiarla se reinicia la vigencia.
+    change(&service, id, "Expired@Pass1", "Renewed@Pass2")
+        .await
+        .unwrap();
+    let token = service
+        .login(LoginRequest {
+            password: "Renewed@Pass2".to_string(),
+            ..login()
+        })
+        .await
+        .unwrap();
+    assert!(!decode_jwt(&token).unwrap().is_password_change_only());
+}
@@ -0,0 +1,99 @@
+use argon2::Variant;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use r_auth_api::{
+    auth::{HashParams, hash_password_with, needs_rehash_with, verify_password_with},
+    config::{PasswordHashingConfig, Pepper, parse_peppers},
+};
+
+fn pepper(id: &str, byte: u8) -> Pepper {
+    Pepper {
+        id: id.to_string(),
+        secret: vec![byte; 32],
+    }
+}
+
+fn hashing_config(peppers: Vec<Pepper>, current: Option<&str>) -> PasswordHashingConfig {
+    PasswordHashingConfig {
+        variant: Variant::Argon2id,
+        hash_length: 32,
+        mem_cost: 1024,
+        time_cost: 1,
+        lanes: 1,
+        peppers,
+        current_pepper: current.map(str::to_string),
+    }
+}
+
+/// ---
+///
+/// ## Test Case 1: El hash guarda el id del pepper y solo verifica con su secreto
+///
+#[test]
+fn test_peppered_hash_requires_secret() {
+    let config = hashing_config(vec![pepper("p1", 1)], Some("p1"));
+    let hash = hash_password_with("Password@123", &config).unwrap();
+
+    assert!(hash.contains(",keyid=p1$"));
+    assert_eq!(
+        HashParams::parse(&hash).unwrap().pepper_id.as_deref(),
+        Some("p1")
+    );
+    assert!(verify_password_with("Password@123", &hash, &config));
+    assert!(!verify_password_with("Password@124", &hash, &config));
+
+    // Mismo id con otro secreto: el dump de la base no alcanza sin el archivo.
+    let other_secret = hashing_config(vec![pepper("p1", 2)], Some("p1"));
+    assert!(!verify_password_with("Password@123", &hash, &other_secret));
+
+    let without_pepper = hashing_config(vec![], None);
+    assert!(!verify_password_with(
+        "Password@123",
+        &hash,
+        &without_pepper
+    ));
+}
+
+/// ---
+///
+/// ## Test Case 2: Durante la rotación el pepper anterior sigue verificando
+///
+#[test]
+fn test_rotation_keeps_old_pepper_verifiable() {
+    let before = hashing_config(vec![pepper("p1", 1)], Some("p1"));
+    let old_hash = hash_password_with("Password@123", &before).unwrap();
+
+    let rotated = hashing_config(vec![pepper("p1", 1), pepper("p2", 2)], Some("p2"));
+    assert!(verify_password_with("Password@123", &old_hash, &rotated));
+    assert!(needs_rehash_with(&old_hash, &rotated));
+
+    let new_hash = hash_password_with("Password@123", &rotated).unwrap();
+    assert!(new_hash.contains(",keyid=p2$"));
+    assert!(!needs_rehash_with(&new_hash, &rotated));
+
+    // Hashes sin pepper de antes de configurarlo también se migran.
+    let unpeppered = hash_password_with("Password@123", &hashing_config(vec![], None)).unwrap();
+    assert!(verify_password_with("Password@123", &unpeppered, &rotated));
+    assert!(needs_rehash_with(&unpeppered, &rotated));
+}
+
+/// ---
+///
+/// ## Test Case 3: Formato del archivo de peppers
+///
+#[test]
+fn test_parse_pepper_file() {
+    let secret = STANDARD.encode([7u8; 32]);
+    let content = format!("# comentario\n\np1={}\np2 = {}\n", secret, secret);
+    let peppers = parse_peppers(&content).unwrap();
+    assert_eq!(
+        peppers.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+        vec!["p1", "p2"]
+    );
+    assert_eq!(peppers[1].secret, vec![7u8; 32]);
+
+    assert!(parse_peppers("sin-separador").is_err());
+    assert!(parse_peppers(&format!("p$1={}", secret)).is_err());
+    assert!(parse_peppers(&format!("p1={}\np1={}", secret, secret)).is_err());
+    assert!(parse_peppers("p1=no-es-base64!").is_err());
+    assert!(parse_peppers(&format!("p1={}", STANDARD.encode([1u8; 8]))).is_err());
+}
@@ -0,0 +1,186 @@
+use argon2::{Config, Variant, Version};
+use r_auth_api::{
+    auth::{HashParams, hash_password, needs_rehash},
+    database::models::dto::{CreateUserDto, LoginRequest},
+    services::UsersService,
+};
+
+use crate::common;
+
+/// Hash con la configuración vieja por defecto (Argon2i).
+fn legacy_hash(password: &str) -> String {
+    let config = Config {
+        variant: Variant::Argon2i,
+        version: Version::Version13,
+        mem_cost: 1024,
+        time_cost: 1,
+        lanes: 1,
+        secret: &[],
+        ad: &[],
+        hash_length: 32,
+    };
+    argon2::hash_encoded(password.as_bytes(), b"legacy-salt-1234", &config).unwrap()
+}
+
+async fn create_with_hash(service: &UsersService, name: &str, hash: Option<&str>) -> i64 {
+    let user = service
+        .create(CreateUserDto {
+            username: name.to_string(),
+            email: format!("{}@example.com", name),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Fallo al crear usuario de prueba");
+
+    if let Some(hash) = hash {
+        let client = common::get_test_pool().get().await.unwrap();
+        client
+            .execute(
+                "UPDATE users SET password = $1 WHERE id = $2",
+                &[&hash, &user.id],
+            )
+            .await
+            .unwrap();
+    }
+    user.id
+}
+
+async fn stored_hash(id: i64) -> String {
+    let client = common::get_test_pool().get().await.unwrap();
+    client
+        .query_one("SELECT password FROM users WHERE id = $1", &[&id])
+        .await
+        .unwrap()
+        .get("password")
+}
+
+/// ---
+///
+/// ## Test Case 1: Los parámetros se leen del hash codificado
+///
+#[test]
+fn test_parse_hash_params() {
+    let params = HashParams::parse(&legacy_hash("Password@1")).expect("Hash válido");
+    assert_eq!(params.variant, Variant::Argon2i);
+    assert_eq!(params.version, Version::Version13);
+    assert_eq!(
+        (
+            params.mem_cost,
+            params.time_cost,
+            params.lanes,
+            params.hash_length
+        ),
+        (1024, 1, 1, 32)
+    );
+
+    assert!(HashParams::parse("no-es-un-hash").is_none());
+    assert!(HashParams::parse("$argon2id$v=19$m=abc,t=1,p=1$c2FsdA$aGFzaA").is_none());
+
+    let fresh = hash_password("Password@1").unwrap();
+    assert!(fresh.starts_with("$argon2id$"));
+    assert!(!needs_rehash(&fresh));
+    assert!(needs_rehash(&legacy_hash("Password@1")));
+}
+
+/// ---
+///
+/// ## Test Case 2: El login regenera un hash desactualizado
+///
+#[tokio::test]
+async fn test_login_rehashes_outdated_hash() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+
+    let legacy = legacy_hash("Legacy@Password1");
+    let id = create_with_hash(&service, "rehash_user", Some(&legacy)).await;
+
+    let login = || LoginRequest {
+        email: "rehash_user@example.com".to_string(),
+        password: "Legacy@Password1".to_string(),
+    };
+    service
+        .login(login())
+        .await
+        .expect("El hash viejo debería seguir siendo válido");
+
+    let upgraded = stored_hash(id).await;
+    assert_ne!(upgraded, legacy);
+    assert_eq!(HashParams::parse(&upgraded), Some(HashParams::current()));
+
+    service
+        .login(login())
+        .await
+        .expect("El hash regenerado debería validar la misma contraseña");
+    assert_eq!(
+        stored_hash(id).await,
+        upgraded,
+        "No debería regenerarse otra vez"
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: Un login fallido no toca el hash
+///
+#[tokio::test]
+async fn test_failed_login_keeps_hash() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+
+    let legacy = legacy_hash("Legacy@Password1");
+    let id = create_with_hash(&service, "rehash_failed", Some(&legacy)).await;
+
+    let result = service
+        .login(LoginRequest {
+            email: "rehash_failed@example.com".to_string(),
+            password: "Wrong@Password1".to_string(),
+        })
+        .await;
+    assert!(result.is_err());
+    assert_eq!(stored_hash(id).await, legacy);
+}
+
+/// ---
+///
+/// ## Test Case 4: El reporte cuenta los hashes pendientes de migrar
+///
+#[tokio::test]
+async fn test_password_hash_report() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+
+    create_with_hash(&service, "report_current", None).await;
+    create_with_hash(
+        &service,
+        "report_legacy_a",
+        Some(&legacy_hash("A@password1")),
+    )
+    .await;
+    create_with_hash(
+        &service,
+        "report_legacy_b",
+        Some(&legacy_hash("B@password1")),
+    )
+    .await;
+    create_with_hash(&service, "report_broken", Some("texto-plano")).await;
+
+    let report = service.password_hash_report().await.unwrap();
+
+    assert_eq!(report.total, 4);
+    assert_eq!(report.current, 1);
+    assert_eq!(report.outdated, 3);
+    assert_eq!(report.current_params, HashParams::current().to_string());
+
+    let legacy = report
+        .groups
+        .iter()
+        .find(|g| g.params.as_deref() == Some("argon2i v=19 m=1024 t=1 p=1 len=32"))
+        .expect("Debería existir el grupo Argon2i");
+    assert_eq!(legacy.users, 2);
+    assert!(!legacy.current);
+    assert!(report.groups.iter().any(|g| g.params.is_none()));
+}
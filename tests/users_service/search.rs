@@ -0,0 +1,231 @@
+use axum::http::StatusCode;
+use chrono::{TimeZone, Utc};
+use r_auth_api::{
+    database::models::{
+        FindQuery,
+        dto::CreateUserDto,
+        entities::{user::User, user_status::UserStatus},
+    },
+    services::UsersService,
+    utils::Permissions,
+};
+
+use crate::common;
+
+async fn create_users(service: &UsersService, usernames: &[&str]) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for username in usernames {
+        let user = service
+            .create(CreateUserDto {
+                username: username.to_string(),
+                email: format!("{}@example.com", username),
+                password: "StrongPassword@123".to_string(),
+                attributes: None,
+            })
+            .await
+            .expect("Fallo al crear usuario de prueba");
+        ids.push(user.id);
+    }
+    ids
+}
+
+fn usernames(users: &[User]) -> Vec<&str> {
+    users.iter().map(|u| u.username.as_str()).collect()
+}
+
+/// ---
+///
+/// ## Test Case 1: `match=exact` compara el valor completo y `contains` escapa los comodines
+///
+#[tokio::test]
+async fn test_find_exact_and_contains() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    create_users(&service, &["ana", "anabel", "an_x", "anax"]).await;
+
+    let contains = service
+        .find(FindQuery {
+            query_key: Some("username".to_string()),
+            query_value: Some("ana".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(usernames(&contains.results), vec!["ana", "anabel", "anax"]);
+
+    let exact = service
+        .find(FindQuery {
+            query_key: Some("username".to_string()),
+            query_value: Some("ANA".to_string()),
+            match_mode: Some("exact".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(usernames(&exact.results), vec!["ana"]);
+
+    // `_` no actúa como comodín: solo coincide con `an_x`.
+    let underscore = service
+        .find(FindQuery {
+            query_key: Some("username".to_string()),
+            query_value: Some("an_".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(usernames(&underscore.results), vec!["an_x"]);
+}
+
+/// ---
+///
+/// ## Test Case 2: Filtros de estado, permisos y fechas combinados
+///
+#[tokio::test]
+async fn test_find_combined_filters() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let ids = create_users(&service, &["filter_a", "filter_b", "filter_c"]).await;
+
+    service.inactive(ids[1]).await.unwrap();
+    service.delete(ids[2]).await.unwrap();
+
+    let client = pool.get().await.unwrap();
+    let admin = (Permissions::ADMIN | Permissions::READ_USERS).bits();
+    client
+        .execute(
+            "UPDATE users SET permissions = $2, created_at = '2020-01-15T00:00:00Z' WHERE id = $1",
+            &[&ids[0], &admin],
+        )
+        .await
+        .unwrap();
+
+    let inactive = service
+        .find(FindQuery {
+            status: Some(UserStatus::Inactive),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(usernames(&inactive.results), vec!["filter_b"]);
+
+    let deleted = service
+        .find(FindQuery {
+            status: Some(UserStatus::Deleted),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(usernames(&deleted.results), vec!["filter_c"]);
+
+    let admins = service
+        .find(FindQuery {
+            permissions: Some(Permissions::ADMIN.bits()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(usernames(&admins.results), vec!["filter_a"]);
+
+    let old = service
+        .find(FindQuery {
+            query_key: Some("username".to_string()),
+            query_value: Some("filter".to_string()),
+            created_from: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+            created_to: Some(Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(old.total, Some(1));
+    assert_eq!(usernames(&old.results), vec!["filter_a"]);
+}
+
+/// ---
+///
+/// ## Test Case 3: Orden por columna permitida y rechazo de columnas desconocidas
+///
+#[tokio::test]
+async fn test_find_sorting() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    create_users(&service, &["sort_b", "sort_c", "sort_a"]).await;
+
+    let sorted = service
+        .find(FindQuery {
+            sort: Some("username".to_string()),
+            order: Some("desc".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        usernames(&sorted.results),
+        vec!["sort_c", "sort_b", "sort_a"]
+    );
+
+    for (sort, order) in [("password", "asc"), ("username", "sideways")] {
+        let Err((status, _)) = service
+            .find(FindQuery {
+                sort: Some(sort.to_string()),
+                order: Some(order.to_string()),
+                ..Default::default()
+            })
+            .await
+        else {
+            panic!("{} {} debería rechazarse", sort, order);
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+/// ---
+///
+/// ## Test Case 4: `q` busca en username y email a la vez y ordena por relevancia
+///
+#[tokio::test]
+async fn test_find_free_text_ranking() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    create_users(&service, &["gonzales_luis", "pedro", "gonzalez_ana"]).await;
+    service
+        .create(CreateUserDto {
+            username: "mbravo".to_string(),
+            email: "mbravo@gonzalez.org".to_string(),
+            password: "StrongPassword@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .unwrap();
+
+    let found = service
+        .find(FindQuery {
+            q: Some("gonzalez".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    // Las coincidencias exactas (username o email) van antes que la que
+    // solo se parece por trigramas.
+    assert_eq!(found.total, Some(3));
+    assert_eq!(
+        usernames(&found.results),
+        vec!["gonzalez_ana", "mbravo", "gonzales_luis"]
+    );
+
+    let sorted = service
+        .find(FindQuery {
+            q: Some("gonzalez".to_string()),
+            sort: Some("username".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        usernames(&sorted.results),
+        vec!["gonzales_luis", "gonzalez_ana", "mbravo"]
+    );
+}
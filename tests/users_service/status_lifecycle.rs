@@ -0,0 +1,228 @@
+use axum::http::StatusCode;
+use r_auth_api::{
+    database::models::{
+        FindQuery,
+        dto::{CreateUserDto, LoginRequest},
+        entities::user_status::UserStatus,
+    },
+    services::UsersService,
+};
+
+use crate::common;
+
+async fn create_user(service: &UsersService, name: &str) -> i64 {
+    service
+        .create(CreateUserDto {
+            username: name.to_string(),
+            email: format!("{}@example.com", name),
+            password: "Password@123".to_string(),
+            attributes: None,
+        })
+        .await
+        .expect("Error creando usuario")
+        .id
+}
+
+/// ---
+///
+/// ## Test Case 1: Transiciones permitidas y serialización del estado
+///
+#[test]
+fn test_status_transitions_and_serde() {
+    assert!(UserStatus::Active.can_transition_to(UserStatus::Inactive));
+    assert!(UserStatus::Inactive.can_transition_to(UserStatus::Active));
+    assert!(UserStatus::Deleted.can_transition_to(UserStatus::Active));
+    assert!(!UserStatus::Deleted.can_transition_to(UserStatus::Inactive));
+    assert!(!UserStatus::Active.can_transition_to(UserStatus::Active));
+
+    // En el JSON se mantiene el número; al leer se acepta también el nombre.
+    assert_eq!(serde_json::to_string(&UserStatus::Inactive).unwrap(), "2");
+    for input in ["2", "\"inactive\"", "\"2\""] {
+        assert_eq!(
+            serde_json::from_str::<UserStatus>(input).unwrap(),
+            UserStatus::Inactive
+        );
+    }
+    assert!(serde_json::from_str::<UserStatus>("4").is_err());
+    assert!(serde_json::from_str::<UserStatus>("\"paused\"").is_err());
+    assert_eq!(UserStatus::from_code(3), Some(UserStatus::Deleted));
+    assert_eq!(UserStatus::from_code(0), None);
+}
+
+/// ---
+///
+/// ## Test Case 2: Reactivar solo aplica a usuarios inactivos
+///
+#[tokio::test]
+async fn test_reactivate_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "reactivate_user").await;
+
+    let (status, _) = service.reactivate(id).await.unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    service.inactive(id).await.unwrap();
+    assert_eq!(
+        service.find_by_id(id).await.unwrap().status,
+        UserStatus::Inactive
+    );
+
+    service.reactivate(id).await.unwrap();
+    assert_eq!(
+        service.find_by_id(id).await.unwrap().status,
+        UserStatus::Active
+    );
+}
+
+/// ---
+///
+/// ## Test Case 3: Los eliminados no aparecen en find ni find_by_email y se pueden restaurar
+///
+#[tokio::test]
+async fn test_delete_and_restore_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "restore_user").await;
+
+    service.delete(id).await.unwrap();
+    let deleted = service.find_by_id(id).await.unwrap();
+    assert_eq!(deleted.status, UserStatus::Deleted);
+    assert!(deleted.deleted_at.is_some());
+
+    let found = service
+        .find(FindQuery {
+            query_key: Some("username".to_string()),
+            query_value: Some("restore_user".to_string()),
+            limit: Some(10),
+            page: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.total, Some(0));
+    assert!(
+        service
+            .find_by_email("restore_user@example.com")
+            .await
+            .is_err()
+    );
+
+    let (status, _) = service.reactivate(id).await.unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = service.inactive(id).await.unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    service.restore(id).await.unwrap();
+    let restored = service.find_by_id(id).await.unwrap();
+    assert_eq!(restored.status, UserStatus::Active);
+    assert!(restored.deleted_at.is_none());
+    assert!(
+        service
+            .find_by_email("restore_user@example.com")
+            .await
+            .is_ok()
+    );
+}
+
+/// ---
+///
+/// ## Test Case 4: La purga borra solo a los eliminados hace más de N días
+///
+#[tokio::test]
+async fn test_purge_deleted_users() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let old = create_user(&service, "purge_old").await;
+    let recent = create_user(&service, "purge_recent").await;
+    let active = create_user(&service, "purge_active").await;
+
+    service.delete(old).await.unwrap();
+    service.delete(recent).await.unwrap();
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE users SET deleted_at = now() - interval '40 days' WHERE id = $1",
+            &[&old],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(service.purge_deleted(30).await.unwrap(), 1);
+
+    let (status, _) = service.find_by_id(old).await.unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(service.find_by_id(recent).await.is_ok());
+    assert!(service.find_by_id(active).await.is_ok());
+}
+
+fn login_request(name: &str, password: &str) -> LoginRequest {
+    LoginRequest {
+        email: format!("{}@example.com", name),
+        password: password.to_string(),
+    }
+}
+
+/// ---
+///
+/// ## Test Case 5: Un usuario inactivo no puede iniciar sesión hasta ser reactivado
+///
+#[tokio::test]
+async fn test_login_inactive_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "login_inactive").await;
+    service.inactive(id).await.unwrap();
+
+    let (status, _) = service
+        .login(login_request("login_inactive", "Password@123"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Con una contraseña incorrecta no se revela el estado de la cuenta.
+    let (status, _) = service
+        .login(login_request("login_inactive", "Wrong@Pass1"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    service.reactivate(id).await.unwrap();
+    assert!(
+        service
+            .login(login_request("login_inactive", "Password@123"))
+            .await
+            .is_ok()
+    );
+}
+
+/// ---
+///
+/// ## Test Case 6: Un usuario eliminado no puede iniciar sesión hasta ser restaurado
+///
+#[tokio::test]
+async fn test_login_deleted_user() {
+    let pool = common::get_test_pool();
+    common::setup_test_environment(pool).await;
+    let service = UsersService::new(pool);
+    let id = create_user(&service, "login_deleted").await;
+    service.delete(id).await.unwrap();
+
+    let (status, _) = service
+        .login(login_request("login_deleted", "Password@123"))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    service.restore(id).await.unwrap();
+    assert!(
+        service
+            .login(login_request("login_deleted", "Password@123"))
+            .await
+            .is_ok()
+    );
+}
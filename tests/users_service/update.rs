@@ -20,6 +20,7 @@ async fn test_update_user_success() {
         username: "update_success".to_string(),
         email: "update_success@example.com".to_string(),
         password: "StrongPassword@123".to_string(),
+        attributes: None,
     };
 
     let created_user = users_service
@@ -32,6 +33,8 @@ async fn test_update_user_success() {
         username: Some("updated_username".to_string()),
         email: Some("updated_email@example.com".to_string()),
         permissions: None,
+        must_change_password: None,
+        ..Default::default()
     };
 
     let result = users_service.update(update_dto).await;
@@ -62,6 +65,8 @@ async fn test_update_user_invalid_id() {
         username: Some("should_fail".to_string()),
         email: Some("should_fail@example.com".to_string()),
         permissions: None,
+        must_change_password: None,
+        ..Default::default()
     };
 
     let result = users_service.update(update_dto).await;
@@ -92,6 +97,7 @@ async fn test_update_user_duplicate_username() {
             username: "user1".to_string(),
             email: "user1@example.com".to_string(),
             password: "StrongPwd@123".to_string(),
+            attributes: None,
         })
         .await
         .expect("Fallo al crear user1");
@@ -102,6 +108,7 @@ async fn test_update_user_duplicate_username() {
             username: "user2".to_string(),
             email: "user2@example.com".to_string(),
             password: "StrongPwd@123".to_string(),
+            attributes: None,
         })
         .await
         .expect("Fallo al crear user2");
@@ -112,6 +119,8 @@ async fn test_update_user_duplicate_username() {
         username: Some("user1".to_string()),
         email: None,
         permissions: None,
+        must_change_password: None,
+        ..Default::default()
     };
 
     let result = users_service.update(update_dto).await;
@@ -145,6 +154,7 @@ async fn test_update_user_duplicate_email() {
             username: "user1email".to_string(),
             email: "user1email@example.com".to_string(),
             password: "StrongPwd@123".to_string(),
+            attributes: None,
         })
         .await
         .expect("Fallo al crear user1");
@@ -155,6 +165,7 @@ async fn test_update_user_duplicate_email() {
             username: "user2email".to_string(),
             email: "user2email@example.com".to_string(),
             password: "StrongPwd@123".to_string(),
+            attributes: None,
         })
         .await
         .expect("Fallo al crear user2");
@@ -165,6 +176,8 @@ async fn test_update_user_duplicate_email() {
         username: None,
         email: Some("user1email@example.com".to_string()),
         permissions: None,
+        must_change_password: None,
+        ..Default::default()
     };
 
     let result = users_service.update(update_dto).await;
@@ -197,6 +210,7 @@ async fn test_update_user_no_fields() {
             username: "nofields".to_string(),
             email: "nofields@example.com".to_string(),
             password: "StrongPwd@123".to_string(),
+            attributes: None,
         })
         .await
         .expect("Fallo al crear usuario");
@@ -206,6 +220,8 @@ async fn test_update_user_no_fields() {
         username: None,
         email: None,
         permissions: None,
+        must_change_password: None,
+        ..Default::default()
     };
 
     let result = users_service.update(update_dto).await;